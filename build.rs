@@ -0,0 +1,15 @@
+fn main() {
+    // The protos are only needed by the gRPC service.
+    #[cfg(feature = "grpc")]
+    compile_protos();
+}
+
+#[cfg(feature = "grpc")]
+fn compile_protos() {
+    // Use the vendored protoc so the gRPC service builds without a system install.
+    if std::env::var_os("PROTOC").is_none() {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+    }
+    tonic_build::compile_protos("proto/pyprevent.proto").expect("compile proto/pyprevent.proto");
+}
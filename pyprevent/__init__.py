@@ -1,7 +1,9 @@
 from .ascvd_calc import (
     batch_calculate_10_yr_ascvd_risk,
     batch_calculate_30_yr_ascvd_risk,
+    calculate_10_yr_ascvd_log_risk,
     calculate_10_yr_ascvd_risk,
+    calculate_30_yr_ascvd_log_risk,
     calculate_30_yr_ascvd_risk,
 )
 
@@ -9,8 +11,69 @@ from .ascvd_calc import (
 from .heart_failure import (
     batch_calculate_10_yr_heart_failure_risk,
     batch_calculate_30_yr_heart_failure_risk,
+    calculate_10_yr_heart_failure_log_risk,
     calculate_10_yr_heart_failure_risk,
+    calculate_30_yr_heart_failure_log_risk,
     calculate_30_yr_heart_failure_risk,
 )
 
 from .cvd import *
+
+from .range_policy import (
+    adjusted_fields,
+    batch_calculate_risk_with_policy,
+    calculate_risk_with_policy,
+)
+
+from .plausibility import (
+    batch_check_plausibility,
+    check_plausibility,
+    plausibility_warnings,
+)
+
+from .pooled_cohort import (
+    batch_calculate_10_yr_pce_ascvd_risk,
+    calculate_10_yr_pce_ascvd_risk,
+    reclassification_table,
+)
+
+from .models import (
+    available_models,
+    batch_calculate_risk,
+    calculate_risk,
+    coefficient_names,
+    model_metadata,
+)
+
+from .score2 import (
+    batch_calculate_score2_op_risk,
+    batch_calculate_score2_risk,
+    calculate_score2_op_risk,
+    calculate_score2_risk,
+)
+
+from .heart_age import batch_calculate_heart_age, calculate_heart_age
+
+from .peer_comparison import batch_compare_to_peers, compare_to_peers
+
+from .trajectory import batch_risk_trajectory, risk_trajectory
+
+from .horizon import batch_calculate_risk_at_horizon, calculate_risk_at_horizon
+
+from .gradient import batch_risk_gradient, risk_gradient
+
+from .monte_carlo import batch_simulate_measurement_error, simulate_measurement_error
+
+from .validation import batch_validate_model, validate_predictions
+
+from .model_comparison import compare_models
+
+from .survey import batch_survey_summary, survey_summary
+
+from .cohort_summary import cohort_summary
+
+from .disclosure import protected_cohort_summary
+
+from .fhir import assess_fhir_bundle
+
+from .hl7 import parse_hl7_oru
@@ -1,7 +1,10 @@
+from typing import Dict, Optional, Union
+
 import numpy as np
 import pandas as pd
 from pyprevent import _pyprevent
 
+from .models import batch_calculate_risk, calculate_risk
 from .utils import _prepare_df_for_batch, _report_any_null_values
 
 
@@ -17,7 +20,11 @@ def calculate_10_yr_ascvd_risk(
     egfr: float,
     on_htn_meds: bool,
     on_cholesterol_meds: bool,
-) -> float:
+    covariance: Optional[str] = None,
+    level: float = 0.95,
+    samples: int = 1000,
+    seed: int = 0,
+) -> Union[float, Dict[str, float]]:
     """
     Calculate the 10-year risk of atherosclerotic cardiovascular disease (ASCVD).
 
@@ -37,9 +44,12 @@ def calculate_10_yr_ascvd_risk(
     - egfr (float): Estimated glomerular filtration rate (mL/min/1.73 m^2). Must be between 15 and 140.
     - on_htn_meds (bool): Indicates if the individual is on hypertension medication (True or False).
     - on_cholesterol_meds (bool): Indicates if the individual is on cholesterol-lowering medication (True or False).
+    - covariance, level, samples, seed: Optional coefficient confidence intervals, as for
+      calculate_risk.
 
     Returns:
     float: The estimated 10-year ASCVD risk percentage.
+    dict: If covariance is given, the risk and its confidence intervals, as for calculate_risk.
 
     Raises:
     ValueError: If any of the input parameters are outside their valid ranges.
@@ -60,6 +70,25 @@ def calculate_10_yr_ascvd_risk(
     ... )
     # Returns: Estimated 10-year ASCVD risk percentage (e.g., 12.3)
     """
+    if covariance is not None:
+        return calculate_risk(
+            "10_yr_ascvd",
+            sex,
+            age,
+            total_cholesterol,
+            hdl_cholesterol,
+            systolic_bp,
+            has_diabetes,
+            current_smoker,
+            bmi,
+            egfr,
+            on_htn_meds,
+            on_cholesterol_meds,
+            covariance=covariance,
+            level=level,
+            samples=samples,
+            seed=seed,
+        )
     return _pyprevent.calculate_10_yr_ascvd_rust(
         sex,
         age,
@@ -87,7 +116,11 @@ def calculate_30_yr_ascvd_risk(
     egfr: float,
     on_htn_meds: bool,
     on_cholesterol_meds: bool,
-) -> float:
+    covariance: Optional[str] = None,
+    level: float = 0.95,
+    samples: int = 1000,
+    seed: int = 0,
+) -> Union[float, Dict[str, float]]:
     """
     Calculate the 30-year risk of atherosclerotic cardiovascular disease (ASCVD).
 
@@ -107,9 +140,12 @@ def calculate_30_yr_ascvd_risk(
     - egfr (float): Estimated glomerular filtration rate (mL/min/1.73 m^2). Must be between 15 and 140.
     - on_htn_meds (bool): Indicates if the individual is on hypertension medication (True or False).
     - on_cholesterol_meds (bool): Indicates if the individual is on cholesterol-lowering medication (True or False).
+    - covariance, level, samples, seed: Optional coefficient confidence intervals, as for
+      calculate_risk.
 
     Returns:
     float: The estimated 30-year ASCVD risk percentage.
+    dict: If covariance is given, the risk and its confidence intervals, as for calculate_risk.
 
     Raises:
     ValueError: If any of the input parameters are outside their valid ranges.
@@ -130,6 +166,25 @@ def calculate_30_yr_ascvd_risk(
     ... )
     # Returns: Estimated 30-year ASCVD risk percentage (e.g., 12.3)
     """
+    if covariance is not None:
+        return calculate_risk(
+            "30_yr_ascvd",
+            sex,
+            age,
+            total_cholesterol,
+            hdl_cholesterol,
+            systolic_bp,
+            has_diabetes,
+            current_smoker,
+            bmi,
+            egfr,
+            on_htn_meds,
+            on_cholesterol_meds,
+            covariance=covariance,
+            level=level,
+            samples=samples,
+            seed=seed,
+        )
     return _pyprevent.calculate_30_yr_ascvd_rust(
         sex,
         age,
@@ -145,6 +200,88 @@ def calculate_30_yr_ascvd_risk(
     )
 
 
+def calculate_10_yr_ascvd_log_risk(
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> float:
+    """
+    Calculate the natural log of the 10-year risk of atherosclerotic cardiovascular disease (ASCVD).
+
+    Takes the same parameters, with the same valid ranges, as calculate_10_yr_ascvd_risk.
+    The risk is returned as the log of a proportion rather than a percentage, which keeps
+    full precision for very small risks that would otherwise round to 0.
+
+    Returns:
+    float: log(risk / 100), where risk is the 10-year ASCVD risk percentage.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges.
+    """
+    return _pyprevent.calculate_10_yr_ascvd_log_risk_rust(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+
+
+def calculate_30_yr_ascvd_log_risk(
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> float:
+    """
+    Calculate the natural log of the 30-year risk of atherosclerotic cardiovascular disease (ASCVD).
+
+    Takes the same parameters, with the same valid ranges, as calculate_30_yr_ascvd_risk.
+    The risk is returned as the log of a proportion rather than a percentage, which keeps
+    full precision for very small risks that would otherwise round to 0.
+
+    Returns:
+    float: log(risk / 100), where risk is the 30-year ASCVD risk percentage.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges.
+    """
+    return _pyprevent.calculate_30_yr_ascvd_log_risk_rust(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+
+
 def batch_calculate_10_yr_ascvd_risk(
     df: pd.DataFrame,
     sex: str = "sex",
@@ -158,8 +295,12 @@ def batch_calculate_10_yr_ascvd_risk(
     egfr: str = "egfr",
     on_htn_meds: str = "on_htn_meds",
     on_cholesterol_meds: str = "on_cholesterol_meds",
+    covariance: Optional[str] = None,
+    level: float = 0.95,
+    samples: int = 1000,
+    seed: int = 0,
     **kwargs,
-) -> np.ndarray:
+) -> Union[np.ndarray, pd.DataFrame]:
     """
      Batch calculate the 10-year risk of atherosclerotic cardiovascular disease (ASCVD) for a dataset.
 
@@ -180,10 +321,14 @@ def batch_calculate_10_yr_ascvd_risk(
     - egfr (str): Column name for the estimated glomerular filtration rate (mL/min/1.73 m^2). Must be between 15 and 140. Default 'egfr'.
     - on_htn_meds (str): Column name indicating if the individual is on hypertension medication (True or False). Default 'on_htn_meds'.
     - on_cholesterol_meds (str): Column name indicating if the individual is on cholesterol-lowering medication (True or False). Default 'on_cholesterol_meds'.
+    - covariance, level, samples, seed: Optional coefficient confidence intervals, as for
+      batch_calculate_risk.
     - **kwargs: Optional keyword arguments for custom column mappings.
 
     Returns:
     numpy.ndarray: A numpy array of estimated 10-year ASCVD risk percentages for each individual in the DataFrame.
+    pd.DataFrame: If covariance is given, the risks and their confidence intervals, as for
+    batch_calculate_risk.
 
     Raises:
     ValueError: If any of the input parameters are outside their valid ranges or if the specified columns are not found in the DataFrame.
@@ -213,6 +358,28 @@ def batch_calculate_10_yr_ascvd_risk(
     # Returns: A np.array of estimated 10-year ASCVD risk percentages
     """
 
+    if covariance is not None:
+        return batch_calculate_risk(
+            df,
+            "10_yr_ascvd",
+            sex,
+            age,
+            total_cholesterol,
+            hdl_cholesterol,
+            systolic_bp,
+            has_diabetes,
+            current_smoker,
+            bmi,
+            egfr,
+            on_htn_meds,
+            on_cholesterol_meds,
+            covariance=covariance,
+            level=level,
+            samples=samples,
+            seed=seed,
+            **kwargs,
+        )
+
     data = _prepare_df_for_batch(
         df,
         sex,
@@ -249,8 +416,12 @@ def batch_calculate_30_yr_ascvd_risk(
     egfr: str = "egfr",
     on_htn_meds: str = "on_htn_meds",
     on_cholesterol_meds: str = "on_cholesterol_meds",
+    covariance: Optional[str] = None,
+    level: float = 0.95,
+    samples: int = 1000,
+    seed: int = 0,
     **kwargs,
-) -> np.ndarray:
+) -> Union[np.ndarray, pd.DataFrame]:
     """
     Batch calculate the 30-year risk of atherosclerotic cardiovascular disease (ASCVD) for a dataset.
 
@@ -271,10 +442,14 @@ def batch_calculate_30_yr_ascvd_risk(
     - egfr (str): Column name for the estimated glomerular filtration rate (mL/min/1.73 m^2). Must be between 15 and 140. Default 'egfr'.
     - on_htn_meds (str): Column name indicating if the individual is on hypertension medication (True or False). Default 'on_htn_meds'.
     - on_cholesterol_meds (str): Column name indicating if the individual is on cholesterol-lowering medication (True or False). Default 'on_cholesterol_meds'.
+    - covariance, level, samples, seed: Optional coefficient confidence intervals, as for
+      batch_calculate_risk.
     - **kwargs: Optional keyword arguments for custom column mappings.
 
     Returns:
     numpy.ndarray: A list of estimated 30-year ASCVD risk percentages for each individual in the DataFrame.
+    pd.DataFrame: If covariance is given, the risks and their confidence intervals, as for
+    batch_calculate_risk.
 
     Raises:
     ValueError: If any of the input parameters are outside their valid ranges or if the specified columns are not found in the DataFrame.
@@ -303,6 +478,28 @@ def batch_calculate_30_yr_ascvd_risk(
     >>> risks = batch_calculate_30_yr_ascvd_risk(df, **column_mappings)
     # Returns: A numpy array of estimated 30-year ASCVD risk percentages
     """
+    if covariance is not None:
+        return batch_calculate_risk(
+            df,
+            "30_yr_ascvd",
+            sex,
+            age,
+            total_cholesterol,
+            hdl_cholesterol,
+            systolic_bp,
+            has_diabetes,
+            current_smoker,
+            bmi,
+            egfr,
+            on_htn_meds,
+            on_cholesterol_meds,
+            covariance=covariance,
+            level=level,
+            samples=samples,
+            seed=seed,
+            **kwargs,
+        )
+
     # Default column names
     data = _prepare_df_for_batch(
         df,
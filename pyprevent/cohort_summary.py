@@ -0,0 +1,78 @@
+from typing import Dict, List, Optional
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .models import batch_calculate_risk
+from .utils import _prepare_df_for_batch
+
+PREVENT_MODELS = ["10_yr_cvd", "10_yr_ascvd", "10_yr_hf", "30_yr_cvd", "30_yr_ascvd", "30_yr_hf"]
+
+SUMMARY_COLUMNS = [
+    "n",
+    "invalid",
+    "mean",
+    "median",
+    "q1",
+    "q3",
+    "iqr",
+    "proportion_low",
+    "proportion_borderline",
+    "proportion_intermediate",
+    "proportion_high",
+]
+
+
+def cohort_summary(
+    df: pd.DataFrame,
+    models: Optional[List[str]] = None,
+    predictions: Optional[Dict[str, np.ndarray]] = None,
+    **kwargs,
+) -> pd.DataFrame:
+    """
+    Summarize batch risk predictions over a cohort and by sex, age band, diabetes, smoking and
+    CKD stage.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame with the model inputs.
+    - models (list of str, optional): Keys from available_models. Default all PREVENT models.
+    - predictions (dict, optional): Batch results for some of the models, keyed by model, in
+      the row order of df. Models without an entry are calculated with batch_calculate_risk.
+    - **kwargs: Input column names, as for batch_calculate_risk.
+
+    Returns:
+    pd.DataFrame: One row per model, stratifier ('overall', 'sex', 'age_band', 'diabetes',
+    'smoking', 'ckd_stage') and level, e.g. ('10_yr_cvd', 'ckd_stage', 'G3a'), as a
+    MultiIndex. Columns are 'n' (rows in the stratum), 'invalid' (rows whose inputs failed
+    validation, which the other statistics exclude), 'mean', 'median', 'q1', 'q3', 'iqr' and,
+    for 10-year PREVENT models, the proportions in each risk category ('proportion_low',
+    'proportion_borderline', 'proportion_intermediate', 'proportion_high'). Use to_csv or
+    to_json to export it.
+
+    Raises:
+    ValueError: If a model is unknown, a prediction array does not match df, or df is empty.
+
+    Example:
+    >>> cohort_summary(df, ["10_yr_cvd", "30_yr_cvd"]).loc["10_yr_cvd", "sex"]
+    """
+    data, models, columns = _batch_inputs(df, models, predictions, **kwargs)
+    keys, stratifiers, levels, values = _pyprevent.cohort_summary_rust(data, models, columns)
+    return _summary_frame([keys, stratifiers, levels], values).astype({"n": int, "invalid": int})
+
+
+def _batch_inputs(df, models, predictions, **kwargs):
+    models = PREVENT_MODELS if models is None else list(models)
+    predictions = predictions or {}
+    columns = [
+        np.asarray(predictions[model], dtype=np.float64)
+        if model in predictions
+        else batch_calculate_risk(df, model, **kwargs)
+        for model in models
+    ]
+    return _prepare_df_for_batch(df, **kwargs), models, np.column_stack(columns)
+
+
+def _summary_frame(labels, values) -> pd.DataFrame:
+    index = pd.MultiIndex.from_arrays(labels, names=["model", "stratifier", "level"])
+    return pd.DataFrame(np.asarray(values), index=index, columns=SUMMARY_COLUMNS)
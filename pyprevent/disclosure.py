@@ -0,0 +1,57 @@
+from typing import Dict, List, Optional
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .cohort_summary import _batch_inputs, _summary_frame
+
+
+def protected_cohort_summary(
+    df: pd.DataFrame,
+    models: Optional[List[str]] = None,
+    predictions: Optional[Dict[str, np.ndarray]] = None,
+    min_cell_size: int = 11,
+    count_base: int = 1,
+    decimals: Optional[int] = None,
+    epsilon: Optional[float] = None,
+    seed: int = 0,
+    **kwargs,
+) -> pd.DataFrame:
+    """
+    Summarize batch risk predictions as cohort_summary does, with the disclosure controls needed
+    to publish the table outside the secure environment.
+
+    Parameters:
+    - df, models, predictions, **kwargs: As for cohort_summary.
+    - min_cell_size (int): Smallest number of patients a published cell may describe. Default 11.
+      Strata with fewer patients, or fewer valid predictions, are withheld (primary
+      suppression), as is the smallest other level of a stratifier with exactly one withheld
+      level (complementary suppression). Within a published row, invalid counts and category
+      proportions that stand for fewer patients are withheld. The smallest other cell is withheld
+      too wherever one would be alone in its row's categories or across its stratifier's levels,
+      so it cannot be recovered from the rest of the row or from the overall row.
+    - count_base (int): Counts are rounded to the nearest multiple of this. Default 1.
+    - decimals (int, optional): Decimal places of risk statistics. Proportions keep two more.
+    - epsilon (float, optional): Privacy budget of the Laplace noise added to each count, mean
+      and proportion. Quantiles are only rounded. The budget applies per value, not to the
+      whole table.
+    - seed (int): Seed of the random number generator for the noise.
+
+    Returns:
+    pd.DataFrame: As for cohort_summary, with a 'suppression' column ('primary',
+    'complementary' or empty) and withheld statistics as NaN. Use to_csv or to_json to export it.
+
+    Raises:
+    ValueError: As for cohort_summary, or if count_base is below 1 or epsilon is not positive.
+
+    Example:
+    >>> protected_cohort_summary(df, ["10_yr_cvd"], count_base=5, decimals=1).to_csv("cvd.csv")
+    """
+    data, models, columns = _batch_inputs(df, models, predictions, **kwargs)
+    keys, stratifiers, levels, suppression, values = _pyprevent.protected_cohort_summary_rust(
+        data, models, columns, min_cell_size, count_base, seed, decimals, epsilon
+    )
+    summary = _summary_frame([keys, stratifiers, levels], values)
+    summary.insert(0, "suppression", suppression)
+    return summary
@@ -0,0 +1,83 @@
+import datetime
+import json
+from typing import Any, Dict, List, Optional, Union
+
+from pyprevent import _pyprevent
+
+from .cohort_summary import PREVENT_MODELS
+
+INPUT_FIELDS = [
+    "sex",
+    "age",
+    "total_cholesterol",
+    "hdl_cholesterol",
+    "systolic_bp",
+    "has_diabetes",
+    "current_smoker",
+    "bmi",
+    "egfr",
+    "on_htn_meds",
+    "on_cholesterol_meds",
+]
+
+_FLAGS = {"has_diabetes", "current_smoker", "on_htn_meds", "on_cholesterol_meds"}
+
+
+def assess_fhir_bundle(
+    bundle: Union[str, Dict[str, Any]],
+    models: Optional[List[str]] = None,
+    as_of: Optional[Union[str, datetime.date]] = None,
+) -> Dict[str, Any]:
+    """
+    Calculate risk for the patient in a FHIR R4 Bundle and return FHIR RiskAssessments.
+
+    The Bundle needs one Patient with gender and birthDate, and Observations coded by LOINC for
+    total cholesterol (2093-3), HDL cholesterol (2085-9), systolic BP (8480-6, alone or as a
+    blood pressure panel component), BMI (39156-5), eGFR (e.g. 98979-8) and smoking status
+    (72166-2). The latest final, amended or corrected Observation on or before as_of is used;
+    cholesterol may be in mg/dL or mmol/L, and the others must be in mm[Hg], kg/m2 and
+    mL/min/{1.73_m2}. Diabetes comes from an active Condition (SNOMED CT or ICD-10 E08-E13), and treatment from active or
+    intended MedicationStatements coded by ATC or by RxNorm ingredient for antihypertensives and
+    statins; without one dated on or before as_of, or undated, the flag is False. RxNorm drug
+    codes (SCD or SBD) only match through the ingredients of a referenced Medication, so code
+    such statements with ATC as well.
+
+    Parameters:
+    - bundle (str or dict): The Bundle as JSON text or parsed JSON.
+    - models (list of str, optional): Keys from available_models. Default all PREVENT models.
+    - as_of (str or date, optional): Date of the assessment, YYYY-MM-DD. Default today.
+
+    Returns:
+    dict: 'inputs', the calculator inputs; 'provenance', the reference of the resource that
+    supplied each input, or None for a flag that defaulted to False; 'risk_assessments', a
+    collection Bundle with a RiskAssessment per model; and 'errors', the message for each model
+    that could not score the patient, e.g. because an input is out of its range.
+
+    Raises:
+    ValueError: If the Bundle is not valid FHIR JSON, lacks a Patient or a required
+    Observation, a cholesterol unit is unknown, or a model is unknown.
+
+    Example:
+    >>> result = assess_fhir_bundle(bundle_json, ["10_yr_cvd"], as_of="2024-06-01")
+    >>> result["risk_assessments"]["entry"][0]["resource"]["prediction"]
+    """
+    if not isinstance(bundle, str):
+        bundle = json.dumps(bundle)
+    as_of = as_of or datetime.date.today()
+    values, provenance, assessments, errors = _pyprevent.assess_fhir_bundle_rust(
+        bundle, list(models or PREVENT_MODELS), str(as_of)
+    )
+    inputs = {}
+    for field, value in zip(INPUT_FIELDS, values):
+        if field == "sex":
+            inputs[field] = "male" if value == 1 else "female"
+        elif field in _FLAGS:
+            inputs[field] = bool(value)
+        else:
+            inputs[field] = value
+    return {
+        "inputs": inputs,
+        "provenance": dict(zip(INPUT_FIELDS, provenance)),
+        "risk_assessments": json.loads(assessments),
+        "errors": dict(errors),
+    }
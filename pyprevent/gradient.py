@@ -0,0 +1,123 @@
+from typing import Dict
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch
+
+
+def risk_gradient(
+    outcome: str,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> pd.DataFrame:
+    """
+    Calculate how a PREVENT risk changes per unit of each continuous input at this patient's values.
+
+    The derivatives are analytic and include the spline and age interaction terms. The splines
+    are piecewise linear, so at a knot (systolic BP 110, eGFR 60, BMI 30) the slope above the
+    knot is returned.
+
+    Parameters:
+    - outcome (str): One of '10_yr_cvd', '30_yr_cvd', '10_yr_ascvd', '30_yr_ascvd', '10_yr_hf' or '30_yr_hf'.
+    - sex ... on_cholesterol_meds: As for calculate_10_yr_cvd_risk.
+
+    Returns:
+    pd.DataFrame: Indexed by age, total_cholesterol, hdl_cholesterol, systolic_bp, bmi and egfr,
+    with columns 'log_odds' (change in log-odds per unit) and 'risk' (change in risk percentage
+    points per unit). The patient's risk is in the frame's attrs under 'risk'.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges.
+
+    Example:
+    >>> risk_gradient("10_yr_cvd", "female", 50, 220, 45, 145, True, True, 33, 75, True, True)
+    """
+    risk, gradient = _pyprevent.risk_gradient_rust(
+        outcome,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+    result = pd.DataFrame(
+        [(log_odds, risk_change) for _, log_odds, risk_change in gradient],
+        index=[field for field, _, _ in gradient],
+        columns=["log_odds", "risk"],
+    )
+    result.attrs["risk"] = risk
+    return result
+
+
+def batch_risk_gradient(
+    df: pd.DataFrame,
+    outcome: str,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> Dict[str, pd.DataFrame]:
+    """
+    Batch version of risk_gradient.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - outcome (str): As for risk_gradient.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+
+    Returns:
+    dict: 'log_odds' and 'risk' DataFrames with the same index as df and one column per continuous
+    input. Rows whose inputs were out of range are NaN.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    fields, log_odds, risk = _pyprevent.risk_gradient_rust_parallel_np(
+        outcome=outcome, data=data
+    )
+    return {
+        name: pd.DataFrame(
+            np.asarray(values).reshape(len(df), len(fields)),
+            index=df.index,
+            columns=fields,
+        )
+        for name, values in (("log_odds", log_odds), ("risk", risk))
+    }
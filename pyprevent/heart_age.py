@@ -0,0 +1,116 @@
+from typing import Dict, Union
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch, _report_any_null_values
+
+
+def calculate_heart_age(
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> Dict[str, Union[float, str]]:
+    """
+    Calculate heart age: the age at which a person of the same sex with optimal risk factors
+    would have the same PREVENT 10-year CVD risk as this patient.
+
+    The optimal profile is total cholesterol 170 mg/dL, HDL cholesterol 50 mg/dL, systolic BP
+    110 mmHg, BMI 25, eGFR 90, no diabetes, not smoking and on no medication.
+
+    Parameters:
+    - sex ... on_cholesterol_meds: As for calculate_10_yr_cvd_risk.
+
+    Returns:
+    dict: 'heart_age' in years, 'difference' (heart age minus age, positive when the patient's
+    risk is higher than optimal) and 'bound'. Heart age is limited to the 10-year equation's age
+    range of 30 to 79: 'bound' is 'below_minimum' or 'above_maximum' when the heart age was
+    capped at 30 or 79, and 'within' otherwise.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges.
+
+    Example:
+    >>> calculate_heart_age("male", 50, 240, 40, 150, False, True, 30, 80, True, False)
+    """
+    heart_age, difference, bound = _pyprevent.calculate_heart_age_rust(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+    return {"heart_age": heart_age, "difference": difference, "bound": bound}
+
+
+def batch_calculate_heart_age(
+    df: pd.DataFrame,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> pd.DataFrame:
+    """
+    Batch calculate heart age for a dataset.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+
+    Returns:
+    pd.DataFrame: Columns 'heart_age', 'difference' and 'bound' as for calculate_heart_age, with
+    the same index as df. Heart age and difference are NaN where the inputs were out of range.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    heart_age, difference, bound = _pyprevent.calculate_heart_age_rust_parallel_np(
+        data=data
+    )
+    _report_any_null_values(heart_age)
+
+    bound_codes = {-1: "below_minimum", 0: "within", 1: "above_maximum"}
+    return pd.DataFrame(
+        {
+            "heart_age": heart_age,
+            "difference": difference,
+            "bound": [bound_codes[int(code)] for code in bound],
+        },
+        index=df.index,
+    )
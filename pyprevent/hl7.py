@@ -0,0 +1,71 @@
+import datetime
+from typing import Any, Dict, Optional, Union
+
+from pyprevent import _pyprevent
+
+from .fhir import INPUT_FIELDS, _FLAGS
+
+
+def parse_hl7_oru(
+    message: str,
+    local_codes: Optional[Dict[str, str]] = None,
+    as_of: Optional[Union[str, datetime.date]] = None,
+) -> Dict[str, Any]:
+    """
+    Read calculator inputs from an HL7 v2 ORU^R01 lab result message.
+
+    Sex and birth date come from PID-8 and PID-7. Each other value is the latest final or
+    corrected OBX result (or one with no result status) on or before as_of, identified by a
+    LOINC code (coding system LN) in OBX-3: total cholesterol (2093-3), HDL cholesterol
+    (2085-9), systolic BP (8480-6), BMI (39156-5), eGFR (e.g. 98979-8), creatinine (2160-0,
+    14682-9), weight (29463-7), height (8302-2) and smoking status (72166-2). Cholesterol may be
+    in mg/dL or mmol/L and creatinine in mg/dL or umol/L; systolic BP must be in mm[Hg] or mmHg,
+    BMI in kg/m2 and eGFR in mL/min/{1.73_m2}, and results in other units are skipped with a
+    warning. Without a reported eGFR, it is calculated from creatinine by CKD-EPI
+    2021; without a BMI, from weight and height. Diabetes and treatment are not lab results and
+    default to False, as does smoking without a smoking status result.
+
+    Parameters:
+    - message (str): The message, with segments separated by carriage returns or newlines.
+    - local_codes (dict, optional): Local OBX-3 codes mapped to one of 'total_cholesterol',
+      'hdl_cholesterol', 'systolic_bp', 'bmi', 'egfr', 'creatinine', 'weight', 'height' or
+      'current_smoker', e.g. {'CHOL': 'total_cholesterol'}.
+    - as_of (str or date, optional): Date of the assessment, YYYY-MM-DD. Default the message
+      date in MSH-7.
+
+    Returns:
+    dict: 'inputs', the calculator inputs with None for those missing; 'provenance', the
+    segment that supplied each input, e.g. 'OBX-3', or None; 'missing', the required inputs
+    the message had no usable value for; and 'warnings', results that were skipped or read
+    with assumptions, such as an eGFR reported as '>90'.
+
+    Raises:
+    ValueError: If the message is not an ORU^R01 message about one patient, as_of is not a
+    date, or a local code maps to an unknown name.
+
+    Example:
+    >>> result = parse_hl7_oru(message, {"CHOL": "total_cholesterol"})
+    >>> if not result["missing"]:
+    ...     calculate_risk("10_yr_cvd", **result["inputs"])
+    """
+    values, provenance, missing, warnings = _pyprevent.oru_inputs_rust(
+        message,
+        list((local_codes or {}).items()),
+        None if as_of is None else str(as_of),
+    )
+    inputs = {}
+    for field, value in zip(INPUT_FIELDS, values):
+        if value != value:  # NaN
+            inputs[field] = None
+        elif field == "sex":
+            inputs[field] = "male" if value == 1 else "female"
+        elif field in _FLAGS:
+            inputs[field] = bool(value)
+        else:
+            inputs[field] = value
+    return {
+        "inputs": inputs,
+        "provenance": dict(zip(INPUT_FIELDS, provenance)),
+        "missing": list(missing),
+        "warnings": list(warnings),
+    }
@@ -0,0 +1,135 @@
+from typing import Dict, Union
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch, _report_any_null_values
+
+
+def calculate_risk_at_horizon(
+    outcome: str,
+    horizon_years: float,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> Dict[str, Union[float, str, bool]]:
+    """
+    Estimate the risk of an outcome at any horizon from 1 to 30 years.
+
+    PREVENT only predicts 10- and 30-year risks, so this is a derived estimate, not a model
+    prediction. It assumes a constant hazard from 0 to 10 years and another constant hazard from
+    10 to 30 years, chosen so the curve passes exactly through the 10- and 30-year predictions.
+    Horizons up to 10 years use only the 10-year equation; longer horizons also need the 30-year
+    equation, so are limited to ages 30 to 59. The two equations are fitted separately, and for a
+    few extreme patients the 30-year risk is below the 10-year risk; the risk is then held at the
+    10-year risk beyond 10 years.
+
+    Parameters:
+    - outcome (str): 'cvd', 'ascvd' or 'hf'.
+    - horizon_years (float): Between 1 and 30.
+    - sex ... on_cholesterol_meds: As for calculate_10_yr_cvd_risk.
+
+    Returns:
+    dict: 'risk' (percentage), 'horizon_years', 'derived' (always True), 'method', which names
+    the interpolation assumption ('piecewise_constant_hazard'), and 'hazard_clamped', True if the
+    risk was held at the 10-year risk because the 30-year risk was lower.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges, or the outcome or
+    horizon is not recognised.
+
+    Example:
+    >>> calculate_risk_at_horizon("ascvd", 5, "female", 45, 210, 55, 135, True, False, 31, 85, True, False)
+    """
+    risk, method, hazard_clamped = _pyprevent.calculate_risk_at_horizon_rust(
+        outcome,
+        horizon_years,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+    return {
+        "risk": risk,
+        "horizon_years": horizon_years,
+        "derived": True,
+        "method": method,
+        "hazard_clamped": hazard_clamped,
+    }
+
+
+def batch_calculate_risk_at_horizon(
+    df: pd.DataFrame,
+    outcome: str,
+    horizon_years: float,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> pd.Series:
+    """
+    Batch version of calculate_risk_at_horizon.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - outcome, horizon_years: As for calculate_risk_at_horizon.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+
+    Returns:
+    pd.Series: Derived risk percentages with the same index as df, NaN where the inputs were out
+    of range. The series is named after the outcome and horizon, e.g. 'derived_5_yr_ascvd', and
+    its attrs record the method and, as 'hazard_clamped', a boolean array of the rows whose risk
+    was held at the 10-year risk.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    result, hazard_clamped, method = _pyprevent.calculate_risk_at_horizon_rust_parallel_np(
+        outcome=outcome, horizon_years=horizon_years, data=data
+    )
+    _report_any_null_values(result)
+
+    series = pd.Series(
+        result, index=df.index, name=f"derived_{horizon_years:g}_yr_{outcome.lower()}"
+    )
+    series.attrs["derived"] = True
+    series.attrs["method"] = method
+    series.attrs["hazard_clamped"] = hazard_clamped
+    return series
@@ -0,0 +1,93 @@
+from typing import Any, Dict, Optional, Sequence
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+
+def compare_models(
+    reference,
+    comparison,
+    events,
+    times,
+    horizon_years: float = 10,
+    category_thresholds: Sequence[float] = (5, 7.5, 20),
+    net_benefit_thresholds: Optional[Sequence[float]] = None,
+    bootstrap: int = 200,
+    level: float = 0.95,
+    seed: int = 0,
+) -> Dict[str, Any]:
+    """
+    Compare two models' predictions for the same patients against observed outcomes, with net
+    reclassification, integrated discrimination improvement and decision curves.
+
+    Parameters:
+    - reference (array-like): Risk percentages from the reference model, e.g. from
+      batch_calculate_10_yr_pce_ascvd_risk.
+    - comparison (array-like): Risk percentages from the comparison model, e.g. from
+      batch_calculate_risk. Rows where either prediction is NaN are left out.
+    - events (array-like): Whether each patient had the outcome event.
+    - times (array-like): Years from prediction to the event, or to the end of follow-up.
+    - horizon_years (float): Horizon of the predictions. Events after it are censored at it.
+      Default 10.
+    - category_thresholds (sequence): Increasing risk percentages bounding the categories of the
+      categorical NRI. Default 5, 7.5 and 20.
+    - net_benefit_thresholds (sequence): Threshold risk percentages of the decision curve.
+      Default 1 to 50 in steps of 1.
+    - bootstrap (int): Number of bootstrap resamples for confidence intervals, or 0 for none.
+      Default 200.
+    - level (float): Confidence level of the intervals. Default 0.95.
+    - seed (int): Seed of the random number generator for resampling.
+
+    Returns:
+    dict: 'n', the number of patients used; 'events', the events up to the horizon;
+    'metrics', a DataFrame indexed by categorical_nri_events, categorical_nri_non_events,
+    categorical_nri, continuous_nri_events, continuous_nri_non_events, continuous_nri and idi,
+    with columns 'estimate', 'lower' and 'upper'; and 'decision_curve', a DataFrame with one row
+    per threshold and the net benefit of each model ('reference', 'comparison', with '_lower' and
+    '_upper' bounds) and of treating everyone ('treat_all'). Events and non-events are weighted by
+    the inverse probability of censoring, so patients censored before the horizon are accounted
+    for.
+
+    Raises:
+    ValueError: If the inputs differ in length, a prediction is not a percentage, a time is
+    negative, fewer than 10 patients have both predictions, there are no events or no
+    non-events, or a threshold is out of range.
+
+    Example:
+    >>> compare_models(pce_risks, prevent_risks, df["ascvd_event"], df["years_followed"])
+    """
+    if net_benefit_thresholds is None:
+        net_benefit_thresholds = np.arange(1, 51, dtype=np.float64)
+    n, event_count, names, metrics, curve = _pyprevent.compare_models_rust(
+        np.asarray(reference, dtype=np.float64),
+        np.asarray(comparison, dtype=np.float64),
+        np.asarray(events, dtype=bool),
+        np.asarray(times, dtype=np.float64),
+        horizon_years,
+        [float(threshold) for threshold in category_thresholds],
+        [float(threshold) for threshold in net_benefit_thresholds],
+        bootstrap,
+        level,
+        seed,
+    )
+    return {
+        "n": n,
+        "events": event_count,
+        "metrics": pd.DataFrame(
+            np.asarray(metrics), index=names, columns=["estimate", "lower", "upper"]
+        ),
+        "decision_curve": pd.DataFrame(
+            np.asarray(curve),
+            columns=[
+                "threshold",
+                "reference",
+                "reference_lower",
+                "reference_upper",
+                "comparison",
+                "comparison_lower",
+                "comparison_upper",
+                "treat_all",
+            ],
+        ),
+    }
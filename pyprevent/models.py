@@ -0,0 +1,220 @@
+from typing import Dict, List, Optional, Tuple, Union
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch, _report_any_null_values
+
+
+def available_models() -> List[str]:
+    """Keys of the risk models that can be passed to calculate_risk and batch_calculate_risk."""
+    return _pyprevent.available_models_rust()
+
+
+def coefficient_names() -> List[str]:
+    """Names of the PREVENT coefficients, for the rows and columns of a coefficient covariance file."""
+    return _pyprevent.coefficient_names_rust()
+
+
+def model_metadata(model: str) -> Dict[str, Union[str, int]]:
+    """
+    Describe a risk model.
+
+    Parameters:
+    - model (str): A key from available_models, e.g. '10_yr_cvd'.
+
+    Returns:
+    dict: The model's name, version, horizon_years and outcome.
+
+    Raises:
+    ValueError: If the model is not recognised.
+    """
+    name, version, horizon_years, outcome = _pyprevent.model_metadata_rust(model)
+    return {
+        "name": name,
+        "version": version,
+        "horizon_years": horizon_years,
+        "outcome": outcome,
+    }
+
+
+def calculate_risk(
+    model: str,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    covariance: Optional[str] = None,
+    level: float = 0.95,
+    samples: int = 1000,
+    seed: int = 0,
+    warnings: bool = False,
+) -> Union[float, Dict[str, float], Tuple[Union[float, Dict[str, float]], List[Tuple[str, str]]]]:
+    """
+    Calculate the risk from any model in available_models.
+
+    Parameters:
+    - model (str): A key from available_models, e.g. '10_yr_cvd'.
+    - sex ... on_cholesterol_meds: As for calculate_10_yr_cvd_risk. Inputs a model does not use
+      are ignored.
+    - covariance (str): Optional path to a CSV file of coefficient variance-covariance matrices
+      for PREVENT models, with the header model,sex,row,column,covariance and one row per matrix
+      entry, e.g. '10_yr_cvd,female,constant,age_adjustment_factor,-0.0001'. Rows and columns are
+      names from coefficient_names; entries not given are zero. If given, confidence intervals
+      for the risk are returned as well.
+    - level (float): Confidence level of the intervals. Default 0.95.
+    - samples (int): Number of coefficient draws for the simulation interval. Default 1000.
+    - seed (int): Seed of the random number generator for the simulation interval.
+    - warnings (bool): If True, also return check_plausibility's warnings for the inputs. The
+      age warning is only given for 30-year models. Default False.
+
+    Returns:
+    float: The estimated risk percentage, if covariance is not given.
+    dict: Otherwise, 'risk'; 'log_odds_se', the standard error of the log-odds; 'delta_lower'
+    and 'delta_upper', the delta-method interval; and 'simulation_lower' and 'simulation_upper',
+    percentiles of the risk over simulated coefficients. The intervals reflect uncertainty in the
+    coefficients only.
+    tuple: If warnings is True, the float or dict above and the list of (code, message) warnings.
+
+    Raises:
+    ValueError: If the model is not recognised or an input is outside the model's valid range,
+    or if covariance is given and cannot be read, the model is not a PREVENT model, or there is
+    no matrix for the model and sex.
+
+    Example:
+    >>> calculate_risk("10_yr_cvd", "male", 50, 200, 50, 120, False, False, 25, 90, False, False)
+    # Returns the same value as calculate_10_yr_cvd_risk
+    """
+    if covariance is not None:
+        columns, values = _pyprevent.risk_confidence_interval_rust(
+            model,
+            sex,
+            age,
+            total_cholesterol,
+            hdl_cholesterol,
+            systolic_bp,
+            has_diabetes,
+            current_smoker,
+            bmi,
+            egfr,
+            on_htn_meds,
+            on_cholesterol_meds,
+            covariance,
+            level,
+            samples,
+            seed,
+        )
+        result = dict(zip(columns, values))
+    else:
+        result = _pyprevent.calculate_model_risk_rust(
+            model,
+            sex,
+            age,
+            total_cholesterol,
+            hdl_cholesterol,
+            systolic_bp,
+            has_diabetes,
+            current_smoker,
+            bmi,
+            egfr,
+            on_htn_meds,
+            on_cholesterol_meds,
+        )
+    if warnings:
+        return result, _pyprevent.check_plausibility_rust(
+            age, total_cholesterol, hdl_cholesterol, on_cholesterol_meds, _is_thirty_year(model)
+        )
+    return result
+
+
+def batch_calculate_risk(
+    df: pd.DataFrame,
+    model: str,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    covariance: Optional[str] = None,
+    level: float = 0.95,
+    samples: int = 1000,
+    seed: int = 0,
+    warnings: bool = False,
+    **kwargs,
+) -> Union[np.ndarray, pd.DataFrame, Tuple[Union[np.ndarray, pd.DataFrame], np.ndarray]]:
+    """
+    Batch calculate the risk from any model in available_models.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - model (str): A key from available_models, e.g. '10_yr_cvd'.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+    - covariance, level, samples: As for calculate_risk.
+    - seed (int): Row i is simulated with seed + i.
+    - warnings (bool): If True, also return batch_check_plausibility's bit masks, as for
+      calculate_risk. Default False.
+
+    Returns:
+    np.ndarray: A numpy array of risk percentages, NaN where an input was out of range, if
+    covariance is not given.
+    pd.DataFrame: Otherwise, one row per patient with the same index as df and the keys of
+    calculate_risk's dict as columns. Rows whose inputs were out of range, or whose sex has no
+    matrix, are NaN.
+    tuple: If warnings is True, the array or DataFrame above and an array of warning bit masks,
+    one per row, which plausibility_warnings decodes.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    if covariance is not None:
+        columns, intervals = _pyprevent.risk_confidence_interval_rust_parallel_np(
+            outcome=model,
+            data=data,
+            covariance_path=covariance,
+            level=level,
+            samples=samples,
+            seed=seed,
+        )
+        result = pd.DataFrame(
+            np.asarray(intervals).reshape(len(df), len(columns)), index=df.index, columns=columns
+        )
+    else:
+        result = _pyprevent.calculate_model_risk_rust_parallel_np(model=model, data=data)
+        _report_any_null_values(result)
+
+    if warnings:
+        return result, _pyprevent.check_plausibility_rust_parallel_np(
+            data=data, thirty_year=_is_thirty_year(model)
+        )
+    return result
+
+
+def _is_thirty_year(model: str) -> bool:
+    return _pyprevent.model_metadata_rust(model)[2] == 30
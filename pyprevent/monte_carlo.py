@@ -0,0 +1,137 @@
+from typing import Dict, Optional
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch
+
+
+def simulate_measurement_error(
+    model: str,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    sds: Optional[Dict[str, float]] = None,
+    samples: int = 1000,
+    seed: int = 0,
+) -> Dict[str, float]:
+    """
+    Show how stable a risk is to measurement error: the measured values are perturbed with
+    independent normal errors, each perturbed patient is scored, and the distribution of the
+    resulting risks is summarised.
+
+    Parameters:
+    - model (str): A key from available_models, e.g. '10_yr_cvd'.
+    - sex ... on_cholesterol_meds: As for calculate_10_yr_cvd_risk.
+    - sds (dict): Optional standard deviation of the measurement error of any of age,
+      total_cholesterol, hdl_cholesterol, systolic_bp, bmi and egfr. The defaults are
+      total_cholesterol 12, hdl_cholesterol 4, systolic_bp 8, bmi 0.5, egfr 6 and age 0.
+      Errors are truncated to the model's valid range: one that takes a value out of range is
+      drawn again.
+    - samples (int): Number of perturbed patients, at least 2. Default 1000.
+    - seed (int): Seed of the random number generator. The same seed gives the same result.
+
+    Returns:
+    dict: 'risk' at the measured values; 'mean' and 'sd' of the simulated risks; their
+    percentiles 'p2_5', 'p25', 'p50', 'p75' and 'p97_5'; 'probability_at_least_5',
+    'probability_at_least_7_5' and 'probability_at_least_20', the share of simulated risks at or
+    above each risk category threshold; and 'probability_reclassified', the share in a different
+    category from 'risk'. The categories are for 10-year PREVENT risks, so these are NaN for
+    other models.
+
+    Raises:
+    ValueError: If the model is not recognised, an input is outside the model's valid range, an
+    SD is negative, for an unknown input or too wide for the input's range, or samples is below 2.
+
+    Example:
+    >>> simulate_measurement_error("10_yr_cvd", "male", 60, 210, 45, 140, False, False, 28, 85,
+    ...                            False, False, sds={"systolic_bp": 12})
+    """
+    columns, values = _pyprevent.simulate_measurement_error_rust(
+        model,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        sds or {},
+        samples,
+        seed,
+    )
+    return dict(zip(columns, values))
+
+
+def batch_simulate_measurement_error(
+    df: pd.DataFrame,
+    model: str,
+    sds: Optional[Dict[str, float]] = None,
+    samples: int = 1000,
+    seed: int = 0,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> pd.DataFrame:
+    """
+    Batch version of simulate_measurement_error.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - model, sds, samples: As for simulate_measurement_error.
+    - seed (int): Row i is simulated with seed + i, so results do not depend on the number of
+      threads.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+
+    Returns:
+    pd.DataFrame: One row per patient with the same index as df, and the columns of
+    simulate_measurement_error. Rows whose inputs were out of range are NaN.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    columns, values = _pyprevent.simulate_measurement_error_rust_parallel_np(
+        model=model,
+        data=data,
+        sds=sds or {},
+        samples=samples,
+        seed=seed,
+    )
+    return pd.DataFrame(
+        np.asarray(values).reshape(len(df), len(columns)), index=df.index, columns=columns
+    )
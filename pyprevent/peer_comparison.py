@@ -0,0 +1,121 @@
+from typing import Dict, Optional
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch, _report_any_null_values
+
+
+def compare_to_peers(
+    model: str,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    reference_path: Optional[str] = None,
+) -> Dict[str, Optional[float]]:
+    """
+    Put a patient's risk in context: the risk of a person of the same age and sex with optimal
+    risk factors, and the patient's percentile among a reference population.
+
+    The optimal profile is total cholesterol 170 mg/dL, HDL cholesterol 50 mg/dL, systolic BP
+    110 mmHg, BMI 25, eGFR 90, no diabetes, not smoking and on no medication.
+
+    Parameters:
+    - model (str): A key from available_models, e.g. '10_yr_cvd'.
+    - sex ... on_cholesterol_meds: As for calculate_10_yr_cvd_risk.
+    - reference_path (str): Optional CSV file of reference risk quantiles, with the header
+      'model,sex,age_min,age_max,percentile,risk' and one row per quantile. Each band covers ages
+      from age_min up to but not including age_max; percentiles are between 0 and 100.
+
+    Returns:
+    dict: 'risk', 'optimal_risk' and 'percentile'. The percentile is interpolated linearly
+    between the reference quantiles, and is None if no reference file was given or it has no
+    band for the patient's model, sex and age.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges, or the reference
+    file cannot be read.
+    """
+    risk, optimal_risk, percentile = _pyprevent.compare_to_peers_rust(
+        model,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        reference_path,
+    )
+    return {"risk": risk, "optimal_risk": optimal_risk, "percentile": percentile}
+
+
+def batch_compare_to_peers(
+    df: pd.DataFrame,
+    model: str,
+    reference_path: Optional[str] = None,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> pd.DataFrame:
+    """
+    Batch version of compare_to_peers.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - model (str): A key from available_models, e.g. '10_yr_cvd'.
+    - reference_path (str): Optional reference quantile file, as for compare_to_peers.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+
+    Returns:
+    pd.DataFrame: Columns 'risk', 'optimal_risk' and 'percentile' with the same index as df. All
+    are NaN where the inputs were out of range; the percentile is also NaN where there is no
+    reference band for the patient.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    risk, optimal_risk, percentile = _pyprevent.compare_to_peers_rust_parallel_np(
+        model=model, data=data, reference_path=reference_path
+    )
+    _report_any_null_values(risk)
+
+    return pd.DataFrame(
+        {"risk": risk, "optimal_risk": optimal_risk, "percentile": percentile},
+        index=df.index,
+    )
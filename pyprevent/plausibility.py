@@ -0,0 +1,103 @@
+from typing import List, Tuple
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch
+
+# In bit order of the masks returned by batch_check_plausibility
+PLAUSIBILITY_WARNINGS = (
+    "hdl_not_below_total_cholesterol",
+    "non_hdl_cholesterol_out_of_range",
+    "high_non_hdl_cholesterol_on_statin",
+    "age_above_30_yr_range",
+)
+
+
+def check_plausibility(
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    on_cholesterol_meds: bool,
+    thirty_year: bool = False,
+) -> List[Tuple[str, str]]:
+    """
+    Check a patient's inputs for combinations that are individually in range but unlikely together.
+
+    These are warnings rather than validation errors: the PREVENT risks can still be calculated,
+    but the record should be reviewed.
+
+    Parameters:
+    - age (float): Age of the individual in years.
+    - total_cholesterol (float): Total cholesterol level (mg/dL).
+    - hdl_cholesterol (float): High-density lipoprotein cholesterol level (mg/dL).
+    - on_cholesterol_meds (bool): Indicates if the individual is on cholesterol-lowering medication.
+    - thirty_year (bool): Whether 30-year risks will be calculated. Only then is an age above 59
+      reported. Default False.
+
+    Returns:
+    list: (code, message) tuples for each warning, empty if the inputs are plausible.
+
+    Example:
+    >>> check_plausibility(age=79, total_cholesterol=150, hdl_cholesterol=160, on_cholesterol_meds=False,
+    ...                    thirty_year=True)
+    # Returns: [("hdl_not_below_total_cholesterol", ...), ("non_hdl_cholesterol_out_of_range", ...),
+    #           ("age_above_30_yr_range", ...)]
+    """
+    return _pyprevent.check_plausibility_rust(
+        age, total_cholesterol, hdl_cholesterol, on_cholesterol_meds, thirty_year
+    )
+
+
+def batch_check_plausibility(
+    df: pd.DataFrame,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    thirty_year: bool = False,
+    **kwargs,
+) -> np.ndarray:
+    """
+    Batch check the same DataFrame passed to the batch_calculate_* functions for implausible inputs.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+    - thirty_year (bool): Whether 30-year risks will be calculated, as for check_plausibility.
+
+    Returns:
+    np.ndarray: A bit mask of warnings for each row, 0 if the row is plausible. Use
+    plausibility_warnings to decode a mask.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+    return _pyprevent.check_plausibility_rust_parallel_np(data=data, thirty_year=thirty_year)
+
+
+def plausibility_warnings(warnings: int) -> List[str]:
+    """Decode a bit mask returned by batch_check_plausibility into warning codes."""
+    return [
+        code for i, code in enumerate(PLAUSIBILITY_WARNINGS) if int(warnings) & (1 << i)
+    ]
@@ -0,0 +1,140 @@
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch, _report_any_null_values
+
+_AFRICAN_AMERICAN = {"black", "african american", "african_american"}
+
+
+def calculate_10_yr_pce_ascvd_risk(
+    sex: str,
+    race: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    on_htn_meds: bool,
+) -> float:
+    """
+    Calculate the 10-year ASCVD risk using the 2013 ACC/AHA Pooled Cohort Equations (PCE).
+
+    Provided for comparison with calculate_10_yr_ascvd_risk during the transition to PREVENT.
+
+    Parameters:
+    - sex (str): The sex of the individual ('male' or 'female'). Case insensitive.
+    - race (str): 'white', 'african american' (or 'black'), or 'other'. Other races use the
+      white equations. Case insensitive.
+    - age (float): Age of the individual in years. Must be between 40 and 79.
+    - total_cholesterol (float): Total cholesterol level (mg/dL). Must be between 130 and 320.
+    - hdl_cholesterol (float): High-density lipoprotein cholesterol level (mg/dL). Must be between 20 and 100.
+    - systolic_bp (float): Systolic blood pressure (mmHg). Must be between 90 and 200.
+    - has_diabetes (bool): Indicates if the individual has diabetes (True or False).
+    - current_smoker (bool): Indicates if the individual is a current smoker (True or False).
+    - on_htn_meds (bool): Indicates if the individual is on hypertension medication (True or False).
+
+    Returns:
+    float: The estimated 10-year ASCVD risk percentage.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges.
+
+    Example:
+    >>> calculate_10_yr_pce_ascvd_risk("male", "white", 55, 213, 50, 120, False, False, False)
+    # Returns: 5.4
+    """
+    return _pyprevent.calculate_10_yr_pce_ascvd_rust(
+        sex,
+        race,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        on_htn_meds,
+    )
+
+
+def batch_calculate_10_yr_pce_ascvd_risk(
+    df: pd.DataFrame,
+    race: str = "race",
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> np.ndarray:
+    """
+    Batch calculate the 10-year Pooled Cohort Equations ASCVD risk for a dataset.
+
+    Takes the same DataFrame as batch_calculate_10_yr_ascvd_risk plus a race column, so both
+    scores can be computed on one cohort. BMI, eGFR and cholesterol medication are not used by
+    the PCE.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - race (str): Column name for race, with values as for calculate_10_yr_pce_ascvd_risk. Default 'race'.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_ascvd_risk.
+
+    Returns:
+    np.ndarray: A numpy array of estimated 10-year ASCVD risk percentages, NaN where the inputs
+    were out of range.
+    """
+    if race not in df.columns:
+        raise ValueError(f"Column '{race}' for parameter 'race' not found in DataFrame.")
+
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+    race_codes = (
+        df[race].str.lower().isin(_AFRICAN_AMERICAN).astype(int).values.astype(np.float64)
+    )
+
+    result = _pyprevent.calculate_10_yr_pce_ascvd_rust_parallel_np(
+        data=data, race=race_codes
+    )
+    _report_any_null_values(result)
+
+    return result
+
+
+def reclassification_table(reference: np.ndarray, comparison: np.ndarray) -> pd.DataFrame:
+    """
+    Cross-tabulate the 10-year risk categories (<5%, 5-7.5%, 7.5-20%, >=20%) of two models.
+
+    Parameters:
+    - reference (np.ndarray): 10-year risk percentages from the reference model, e.g. the PCE.
+    - comparison (np.ndarray): 10-year risk percentages for the same patients from the comparison
+      model, e.g. PREVENT 10-year ASCVD.
+
+    Returns:
+    pd.DataFrame: Patient counts, with the reference model's category as rows and the comparison
+    model's category as columns. Patients with a NaN risk from either model are left out.
+    """
+    labels, table = _pyprevent.reclassification_table_rust(
+        np.asarray(reference, dtype=np.float64),
+        np.asarray(comparison, dtype=np.float64),
+    )
+    return pd.DataFrame(table, index=labels, columns=labels)
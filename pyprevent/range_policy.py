@@ -0,0 +1,135 @@
+from typing import Dict, List, Optional, Tuple
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch, _report_any_null_values
+
+ADJUSTABLE_FIELDS = (
+    "age",
+    "total_cholesterol",
+    "hdl_cholesterol",
+    "systolic_bp",
+    "bmi",
+    "egfr",
+)
+
+
+def calculate_risk_with_policy(
+    outcome: str,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    policy: Optional[Dict[str, str]] = None,
+) -> Tuple[float, List[str]]:
+    """
+    Calculate a PREVENT risk, handling out of range inputs according to a per-field policy.
+
+    Parameters:
+    - outcome (str): One of '10_yr_cvd', '30_yr_cvd', '10_yr_ascvd', '30_yr_ascvd', '10_yr_hf' or '30_yr_hf'.
+    - sex ... on_cholesterol_meds: As for calculate_10_yr_cvd_risk.
+    - policy (dict): Maps any of age, total_cholesterol, hdl_cholesterol, systolic_bp, bmi and egfr to
+      'reject' (raise ValueError, the default), 'clamp' (use the nearest bound of the valid range) or
+      'extrapolate' (use the value as given).
+
+    Returns:
+    tuple: The estimated risk percentage, and the names of the fields that were clamped or
+    extrapolated. A non-empty list means the estimate is outside the range the equations were
+    derived on.
+
+    Raises:
+    ValueError: If a rejected input is out of range, or the outcome or policy is not recognised.
+
+    Example:
+    >>> calculate_risk_with_policy(
+    ...     "10_yr_cvd", "male", 45, 210, 55, 215, False, True, 28, 65, False, False,
+    ...     policy={"systolic_bp": "clamp"},
+    ... )
+    # Returns: (risk, ["systolic_bp"])
+    """
+    return _pyprevent.calculate_risk_with_policy_rust(
+        outcome,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        policy or {},
+    )
+
+
+def batch_calculate_risk_with_policy(
+    df: pd.DataFrame,
+    outcome: str,
+    policy: Optional[Dict[str, str]] = None,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> Tuple[np.ndarray, np.ndarray]:
+    """
+    Batch calculate a PREVENT risk, handling out of range inputs according to a per-field policy.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - outcome (str): One of '10_yr_cvd', '30_yr_cvd', '10_yr_ascvd', '30_yr_ascvd', '10_yr_hf' or '30_yr_hf'.
+    - policy (dict): As for calculate_risk_with_policy.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+
+    Returns:
+    tuple: A numpy array of risk percentages (NaN where an input was rejected), and a numpy array
+    of bit masks of the fields that were clamped or extrapolated for each row. Use
+    adjusted_fields to decode a mask.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    result, adjusted = _pyprevent.calculate_risk_with_policy_rust_parallel_np(
+        outcome=outcome, data=data, policy=policy or {}
+    )
+    _report_any_null_values(result)
+
+    return result, adjusted
+
+
+def adjusted_fields(adjusted: int) -> List[str]:
+    """Decode a bit mask returned by batch_calculate_risk_with_policy into field names."""
+    return [
+        field for i, field in enumerate(ADJUSTABLE_FIELDS) if int(adjusted) & (1 << i)
+    ]
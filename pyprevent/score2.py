@@ -0,0 +1,214 @@
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch, _report_any_null_values
+
+
+def calculate_score2_risk(
+    region: str,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+) -> float:
+    """
+    Calculate the 10-year fatal and non-fatal CVD risk using ESC SCORE2, for ages 40 to 69.
+
+    Parameters:
+    - region (str): ESC risk region used to recalibrate the score: 'low', 'moderate', 'high' or
+      'very_high'. Case insensitive.
+    - sex (str): The sex of the individual ('male' or 'female'). Case insensitive.
+    - age (float): Age of the individual in years. Must be between 40 and 69.
+    - total_cholesterol (float): Total cholesterol level (mg/dL). Must be between 130 and 320.
+    - hdl_cholesterol (float): High-density lipoprotein cholesterol level (mg/dL). Must be between 20 and 100.
+    - systolic_bp (float): Systolic blood pressure (mmHg). Must be between 90 and 200.
+    - has_diabetes (bool): Indicates if the individual has diabetes (True or False).
+    - current_smoker (bool): Indicates if the individual is a current smoker (True or False).
+
+    Returns:
+    float: The estimated 10-year CVD risk percentage.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges, or the region is
+    not recognised.
+
+    Example:
+    >>> calculate_score2_risk("low", "male", 55, 220, 45, 140, False, True)
+    """
+    return _pyprevent.calculate_score2_rust(
+        region,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+    )
+
+
+def calculate_score2_op_risk(
+    region: str,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+) -> float:
+    """
+    Calculate the 10-year fatal and non-fatal CVD risk using ESC SCORE2-OP, for ages 70 to 89.
+
+    Parameters are as for calculate_score2_risk, except that age must be between 70 and 89.
+
+    Returns:
+    float: The estimated 10-year CVD risk percentage.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges, or the region is
+    not recognised.
+    """
+    return _pyprevent.calculate_score2_op_rust(
+        region,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+    )
+
+
+def _batch_calculate(
+    rust_fn,
+    df: pd.DataFrame,
+    region: str,
+    sex: str,
+    age: str,
+    total_cholesterol: str,
+    hdl_cholesterol: str,
+    systolic_bp: str,
+    has_diabetes: str,
+    current_smoker: str,
+    bmi: str,
+    egfr: str,
+    on_htn_meds: str,
+    on_cholesterol_meds: str,
+    **kwargs,
+) -> np.ndarray:
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    result = rust_fn(region=region, data=data)
+    _report_any_null_values(result)
+
+    return result
+
+
+def batch_calculate_score2_risk(
+    df: pd.DataFrame,
+    region: str,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> np.ndarray:
+    """
+    Batch calculate the 10-year SCORE2 CVD risk for a dataset.
+
+    Takes the same DataFrame as the PREVENT batch functions, so both scores can be computed on
+    one cohort. BMI, eGFR and medication columns are not used by SCORE2.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - region (str): ESC risk region, as for calculate_score2_risk.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+
+    Returns:
+    np.ndarray: A numpy array of estimated 10-year CVD risk percentages, NaN where the inputs
+    were out of range (including ages outside 40 to 69).
+    """
+    return _batch_calculate(
+        _pyprevent.calculate_score2_rust_parallel_np,
+        df,
+        region,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+
+def batch_calculate_score2_op_risk(
+    df: pd.DataFrame,
+    region: str,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> np.ndarray:
+    """
+    Batch calculate the 10-year SCORE2-OP CVD risk for a dataset.
+
+    As batch_calculate_score2_risk, for ages 70 to 89.
+    """
+    return _batch_calculate(
+        _pyprevent.calculate_score2_op_rust_parallel_np,
+        df,
+        region,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
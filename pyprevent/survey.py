@@ -0,0 +1,102 @@
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .models import batch_calculate_risk
+
+
+def _codes(values, n):
+    if values is None:
+        return np.zeros(n), np.array([None], dtype=object)
+    codes, labels = pd.factorize(pd.Series(values), use_na_sentinel=False)
+    return codes.astype(np.float64), np.asarray(labels, dtype=object)
+
+
+def survey_summary(predictions, weights, strata, psu, by=None, model=None) -> pd.DataFrame:
+    """
+    Estimate population risk from predictions for the respondents of a complex survey.
+
+    Standard errors are design-based: Taylor linearization with PSUs treated as sampled with
+    replacement within strata, as in NHANES analyses. Subgroup estimates use the full design.
+
+    Parameters:
+    - predictions (array-like): Predicted risk percentages, e.g. from batch_calculate_risk.
+      Rows that are NaN are left out of the estimates but kept in the design.
+    - weights (array-like): Sampling weights.
+    - strata (array-like): Stratum of each respondent.
+    - psu (array-like): Primary sampling unit of each respondent within its stratum.
+    - by (array-like, optional): Subgroup of each respondent, e.g. sex or age band.
+    - model (str, optional): The key of the model that made the predictions, e.g. '10_yr_ascvd'.
+      Risk categories are only defined for the 10-year PREVENT models.
+
+    Returns:
+    pd.DataFrame: One row for 'overall' and one per subgroup, with columns 'n' (respondents
+    with predictions), 'population' (sum of their weights), 'mean_risk', the share of the
+    population in each risk category ('proportion_low', 'proportion_borderline',
+    'proportion_intermediate', 'proportion_high', NaN unless model is a 10-year PREVENT model) and
+    'expected_events' (weighted sum of predicted probabilities over the model horizon), each
+    estimate followed by its standard error in a column ending in '_se'. A subgroup with no
+    predictions has a population of 0, an expected_events of 0 and NaN means and proportions.
+
+    Raises:
+    ValueError: If the inputs differ in length or are not one-dimensional, a weight is not
+    positive, a stratum has a single PSU, no respondent has a prediction, or the model is not
+    recognised.
+
+    Example:
+    >>> risks = batch_calculate_risk(nhanes, "10_yr_ascvd")
+    >>> survey_summary(risks, nhanes["WTMEC2YR"], nhanes["SDMVSTRA"], nhanes["SDMVPSU"],
+    ...                by=nhanes["sex"], model="10_yr_ascvd")
+    """
+    predictions = np.asarray(predictions, dtype=np.float64)
+    n = len(predictions)
+    group_codes, labels = _codes(by, n)
+    columns, overall, groups, by_group = _pyprevent.survey_summary_rust(
+        predictions,
+        np.asarray(weights, dtype=np.float64),
+        _codes(strata, n)[0],
+        _codes(psu, n)[0],
+        group_codes,
+        model,
+    )
+    rows = [overall]
+    index = ["overall"]
+    if by is not None:
+        rows.extend(np.asarray(by_group))
+        index.extend(labels[groups])
+    return pd.DataFrame(rows, index=index, columns=columns).astype({"n": int})
+
+
+def batch_survey_summary(
+    df: pd.DataFrame,
+    model: str,
+    weight: str,
+    strata: str,
+    psu: str,
+    by: str = None,
+    **kwargs,
+) -> pd.DataFrame:
+    """
+    Predict a model's risk for every respondent in df and estimate population risk.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame with the model inputs and survey design variables.
+    - model (str): A key from available_models, e.g. '10_yr_ascvd'.
+    - weight (str): Column of sampling weights.
+    - strata (str): Column of strata.
+    - psu (str): Column of primary sampling units.
+    - by (str, optional): Column of subgroups.
+    - **kwargs: Input column names, as for batch_calculate_risk.
+
+    Returns:
+    pd.DataFrame: As for survey_summary.
+    """
+    predictions = batch_calculate_risk(df, model, **kwargs)
+    return survey_summary(
+        predictions,
+        df[weight],
+        df[strata],
+        df[psu],
+        by=None if by is None else df[by],
+        model=model,
+    )
@@ -0,0 +1,128 @@
+from typing import Dict, Optional
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .utils import _prepare_df_for_batch
+
+
+def risk_trajectory(
+    outcome: str,
+    sex: str,
+    age: float,
+    total_cholesterol: float,
+    hdl_cholesterol: float,
+    systolic_bp: float,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: float,
+    egfr: float,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    trends: Optional[Dict[str, float]] = None,
+    step_years: float = 5,
+    max_age: float = 79,
+) -> pd.DataFrame:
+    """
+    Project a 10-year PREVENT risk forward: the risk the patient would have at their current age
+    and every step_years after, up to max_age.
+
+    Parameters:
+    - outcome (str): One of '10_yr_cvd', '10_yr_ascvd' or '10_yr_hf'.
+    - sex ... on_cholesterol_meds: As for calculate_10_yr_cvd_risk.
+    - trends (dict): Optional yearly change in any of total_cholesterol, hdl_cholesterol,
+      systolic_bp, bmi and egfr, e.g. {"systolic_bp": 1, "egfr": -1}. Other risk factors stay at
+      their current values. Projected values are held at the nearest bound of their valid range.
+    - step_years (float): Years between points, at least 0.1. Default 5.
+    - max_age (float): Last age to include, at most 79. Default 79.
+
+    Returns:
+    pd.DataFrame: Columns 'age' and 'risk', one row per point.
+
+    Raises:
+    ValueError: If any of the input parameters are outside their valid ranges, the outcome is not
+    a 10-year outcome, a trend is for an unknown input, or step_years is below 0.1.
+
+    Example:
+    >>> risk_trajectory("10_yr_cvd", "male", 52, 200, 45, 130, False, False, 27, 90, False, False,
+    ...                 trends={"systolic_bp": 1})
+    """
+    ages, risks = _pyprevent.risk_trajectory_rust(
+        outcome,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        trends or {},
+        step_years,
+        max_age,
+    )
+    return pd.DataFrame({"age": ages, "risk": risks})
+
+
+def batch_risk_trajectory(
+    df: pd.DataFrame,
+    outcome: str,
+    trends: Optional[Dict[str, float]] = None,
+    step_years: float = 5,
+    max_age: float = 79,
+    sex: str = "sex",
+    age: str = "age",
+    total_cholesterol: str = "total_cholesterol",
+    hdl_cholesterol: str = "hdl_cholesterol",
+    systolic_bp: str = "systolic_bp",
+    has_diabetes: str = "has_diabetes",
+    current_smoker: str = "current_smoker",
+    bmi: str = "bmi",
+    egfr: str = "egfr",
+    on_htn_meds: str = "on_htn_meds",
+    on_cholesterol_meds: str = "on_cholesterol_meds",
+    **kwargs,
+) -> pd.DataFrame:
+    """
+    Batch version of risk_trajectory.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame containing the required data.
+    - outcome, trends, step_years, max_age: As for risk_trajectory.
+    - sex ... on_cholesterol_meds (str): Column names, as for batch_calculate_10_yr_cvd_risk.
+
+    Returns:
+    pd.DataFrame: One row per patient with the same index as df, and one column per number of
+    years from now (0, step_years, ...). Entries beyond max_age, and rows whose inputs were out of
+    range, are NaN.
+    """
+    data = _prepare_df_for_batch(
+        df,
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+        **kwargs,
+    )
+
+    years, risks = _pyprevent.risk_trajectory_rust_parallel_np(
+        outcome=outcome,
+        data=data,
+        trends=trends or {},
+        step_years=step_years,
+        max_age=max_age,
+    )
+    return pd.DataFrame(
+        np.asarray(risks).reshape(len(df), len(years)), index=df.index, columns=years
+    )
@@ -0,0 +1,106 @@
+from typing import Any, Dict
+
+import numpy as np
+import pandas as pd
+from pyprevent import _pyprevent
+
+from .models import batch_calculate_risk, model_metadata
+
+
+def validate_predictions(
+    predictions,
+    events,
+    times,
+    horizon_years: float = 10,
+    bootstrap: int = 200,
+    level: float = 0.95,
+    seed: int = 0,
+) -> Dict[str, Any]:
+    """
+    Measure how well predicted risks discriminate and are calibrated against observed outcomes.
+
+    Parameters:
+    - predictions (array-like): Predicted risk percentages, e.g. from batch_calculate_risk. Rows
+      that are NaN are left out.
+    - events (array-like): Whether each patient had the outcome event.
+    - times (array-like): Years from prediction to the event, or to the end of follow-up.
+    - horizon_years (float): Horizon of the predictions. Events after it are censored at it.
+      Default 10.
+    - bootstrap (int): Number of bootstrap resamples for confidence intervals, or 0 for none.
+      Default 200.
+    - level (float): Confidence level of the intervals. Default 0.95.
+    - seed (int): Seed of the random number generator for resampling.
+
+    Returns:
+    dict: 'n', the number of patients used; 'events', the events up to the horizon;
+    'metrics', a DataFrame indexed by c_statistic (Harrell's C), brier_score (weighted by the
+    inverse probability of censoring), calibration_intercept (slope fixed at 1),
+    calibration_slope and observed_expected (Kaplan-Meier over mean predicted risk), with columns
+    'estimate', 'lower' and 'upper'; and 'deciles', a DataFrame with one row per tenth of
+    patients by predicted risk and columns 'n', 'events', 'mean_predicted' and 'observed'
+    (Kaplan-Meier risk percentage at the horizon).
+
+    Raises:
+    ValueError: If the inputs differ in length, a prediction is not a percentage, a time is
+    negative, fewer than 10 patients have predictions, or there are no events or no non-events.
+
+    Example:
+    >>> risks = batch_calculate_risk(df, "10_yr_cvd")
+    >>> validate_predictions(risks, df["cvd_event"], df["years_followed"])["metrics"]
+    """
+    n, event_count, names, estimates, lower, upper, deciles = _pyprevent.validate_predictions_rust(
+        np.asarray(predictions, dtype=np.float64),
+        np.asarray(events, dtype=bool),
+        np.asarray(times, dtype=np.float64),
+        horizon_years,
+        bootstrap,
+        level,
+        seed,
+    )
+    deciles = pd.DataFrame(
+        np.asarray(deciles), columns=["n", "events", "mean_predicted", "observed"]
+    ).astype({"n": int, "events": int})
+    return {
+        "n": n,
+        "events": event_count,
+        "metrics": pd.DataFrame(
+            {"estimate": estimates, "lower": lower, "upper": upper}, index=names
+        ),
+        "deciles": deciles,
+    }
+
+
+def batch_validate_model(
+    df: pd.DataFrame,
+    model: str,
+    event: str = "event",
+    time: str = "time",
+    bootstrap: int = 200,
+    level: float = 0.95,
+    seed: int = 0,
+    **kwargs,
+) -> Dict[str, Any]:
+    """
+    Predict a model's risk for every patient in df and validate it against their outcomes.
+
+    Parameters:
+    - df (pd.DataFrame): DataFrame with the model inputs and observed outcomes.
+    - model (str): A key from available_models, e.g. '10_yr_cvd'. Its horizon is used.
+    - event (str): Column of event indicators. Default 'event'.
+    - time (str): Column of follow-up years. Default 'time'.
+    - bootstrap, level, seed: As for validate_predictions.
+    - **kwargs: Input column names, as for batch_calculate_risk.
+
+    Returns:
+    dict: As for validate_predictions.
+    """
+    predictions = batch_calculate_risk(df, model, **kwargs)
+    return validate_predictions(
+        predictions,
+        df[event],
+        df[time],
+        horizon_years=model_metadata(model)["horizon_years"],
+        bootstrap=bootstrap,
+        level=level,
+        seed=seed,
+    )
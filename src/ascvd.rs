@@ -1,12 +1,15 @@
 use crate::covariates::Covariates;
-use crate::utils::{calculate_risk_rust_parallel_np, common_calculation, validate_input};
+use crate::model::PreventModel;
+use crate::utils::{
+    calculate_risk_rust_parallel_np, common_calculation, log_risk_from_log_odds,
+    risk_from_log_odds, validate_input,
+};
 use numpy::PyReadonlyArrayDyn;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use std::f64;
-use std::f64::consts::E;
 
-pub fn calculate_10_yr_ascvd_risk(
+pub fn calculate_10_yr_ascvd_log_odds(
     sex: &str,
     age: f64,
     total_cholesterol: f64,
@@ -29,6 +32,34 @@ pub fn calculate_10_yr_ascvd_risk(
         true,
     )?;
 
+    calculate_10_yr_ascvd_log_odds_unchecked(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+}
+
+pub(crate) fn calculate_10_yr_ascvd_log_odds_unchecked(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> Result<f64, String> {
     let cholesterol_diff = total_cholesterol - hdl_cholesterol;
     let adjusted_age = (age - 55.0) / 10.0;
     let adjusted_age_squared = adjusted_age.powi(2);
@@ -36,7 +67,7 @@ pub fn calculate_10_yr_ascvd_risk(
     match sex.to_lowercase().as_str() {
         "female" => {
             let covariates = Covariates::female_10_yr_ascvd();
-            let calculation = common_calculation(
+            Ok(common_calculation(
                 &covariates,
                 has_diabetes,
                 current_smoker,
@@ -49,13 +80,11 @@ pub fn calculate_10_yr_ascvd_risk(
                 adjusted_age_squared,
                 egfr,
                 bmi,
-            );
-            let risk_score = E.powf(calculation) / (1.0 + E.powf(calculation)) * 100.0;
-            Ok(risk_score)
+            ))
         }
         "male" => {
             let covariates = Covariates::male_10_yr_ascvd();
-            let calculation = common_calculation(
+            Ok(common_calculation(
                 &covariates,
                 has_diabetes,
                 current_smoker,
@@ -68,15 +97,42 @@ pub fn calculate_10_yr_ascvd_risk(
                 adjusted_age_squared,
                 egfr,
                 bmi,
-            );
-            let risk_score = E.powf(calculation) / (1.0 + E.powf(calculation)) * 100.0;
-            Ok(risk_score)
+            ))
         }
         _ => Err("Sex must be either 'male' or 'female'.".to_string()),
     }
 }
 
-pub fn calculate_30_yr_ascvd_risk(
+pub fn calculate_10_yr_ascvd_risk(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> Result<f64, String> {
+    calculate_10_yr_ascvd_log_odds(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+    .map(risk_from_log_odds)
+}
+
+pub fn calculate_30_yr_ascvd_log_odds(
     sex: &str,
     age: f64,
     total_cholesterol: f64,
@@ -99,6 +155,34 @@ pub fn calculate_30_yr_ascvd_risk(
         false,
     )?;
 
+    calculate_30_yr_ascvd_log_odds_unchecked(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+}
+
+pub(crate) fn calculate_30_yr_ascvd_log_odds_unchecked(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> Result<f64, String> {
     let cholesterol_diff = total_cholesterol - hdl_cholesterol;
     let adjusted_age = (age - 55.0) / 10.0;
     let adjusted_age_squared = adjusted_age.powi(2);
@@ -106,7 +190,7 @@ pub fn calculate_30_yr_ascvd_risk(
     match sex.to_lowercase().as_str() {
         "female" => {
             let covariates = Covariates::female_30_yr_ascvd();
-            let calculation = common_calculation(
+            Ok(common_calculation(
                 &covariates,
                 has_diabetes,
                 current_smoker,
@@ -119,13 +203,11 @@ pub fn calculate_30_yr_ascvd_risk(
                 adjusted_age_squared,
                 egfr,
                 bmi,
-            );
-            let risk_score = E.powf(calculation) / (1.0 + E.powf(calculation)) * 100.0;
-            Ok(risk_score)
+            ))
         }
         "male" => {
             let covariates = Covariates::male_30_yr_ascvd();
-            let calculation = common_calculation(
+            Ok(common_calculation(
                 &covariates,
                 has_diabetes,
                 current_smoker,
@@ -138,14 +220,41 @@ pub fn calculate_30_yr_ascvd_risk(
                 adjusted_age_squared,
                 egfr,
                 bmi,
-            );
-            let risk_score = E.powf(calculation) / (1.0 + E.powf(calculation)) * 100.0;
-            Ok(risk_score)
+            ))
         }
         _ => Err("Sex must be either 'male' or 'female'.".to_string()),
     }
 }
 
+pub fn calculate_30_yr_ascvd_risk(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> Result<f64, String> {
+    calculate_30_yr_ascvd_log_odds(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )
+    .map(risk_from_log_odds)
+}
+
 #[pyfunction]
 pub fn calculate_10_yr_ascvd_rust(
     sex: String,
@@ -210,12 +319,76 @@ pub fn calculate_30_yr_ascvd_rust(
     }
 }
 
+#[pyfunction]
+pub fn calculate_10_yr_ascvd_log_risk_rust(
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> PyResult<f64> {
+    match calculate_10_yr_ascvd_log_odds(
+        &sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    ) {
+        Ok(value) => Ok(log_risk_from_log_odds(value)),
+        Err(e) => Err(PyValueError::new_err(e)),
+    }
+}
+
+#[pyfunction]
+pub fn calculate_30_yr_ascvd_log_risk_rust(
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> PyResult<f64> {
+    match calculate_30_yr_ascvd_log_odds(
+        &sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    ) {
+        Ok(value) => Ok(log_risk_from_log_odds(value)),
+        Err(e) => Err(PyValueError::new_err(e)),
+    }
+}
+
 #[pyfunction]
 pub fn calculate_10_yr_ascvd_rust_parallel_np(
     py: Python,
     data: PyReadonlyArrayDyn<f64>,
 ) -> PyResult<PyObject> {
-    calculate_risk_rust_parallel_np(py, data, calculate_10_yr_ascvd_risk)
+    calculate_risk_rust_parallel_np(py, data, &PreventModel::Ascvd10Yr)
 }
 
 #[pyfunction]
@@ -223,5 +396,5 @@ pub fn calculate_30_yr_ascvd_rust_parallel_np(
     py: Python,
     data: PyReadonlyArrayDyn<f64>,
 ) -> PyResult<PyObject> {
-    calculate_risk_rust_parallel_np(py, data, calculate_30_yr_ascvd_risk)
+    calculate_risk_rust_parallel_np(py, data, &PreventModel::Ascvd30Yr)
 }
@@ -0,0 +1,117 @@
+use pyprevent::cds_hooks::{handle_request, FhirServer};
+use pyprevent::http;
+use serde_json::Value;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, process};
+use ureq::{Agent, AgentBuilder};
+
+const USAGE: &str =
+    "Usage: pyprevent-cds-hooks [--address HOST] [--port N] [--max-body-bytes N] [--threads N]";
+
+/// CORS headers for the EHR's browser-based sandbox.
+const CORS_HEADERS: [(&str, &str); 3] = [
+    ("Access-Control-Allow-Origin", "*"),
+    ("Access-Control-Allow-Methods", "GET, POST, OPTIONS"),
+    (
+        "Access-Control-Allow-Headers",
+        "Authorization, Content-Type",
+    ),
+];
+
+/// Limits on each request to the EHR's FHIR server, so that a slow server
+/// cannot hold up the hook indefinitely.
+const FHIR_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const FHIR_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Today's UTC date as YYYY-MM-DD, from days since the Unix epoch.
+fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    // Howard Hinnant's civil_from_days, with eras of 400 years from 0000-03-01.
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Reads `query` from the EHR's FHIR server with its access token.
+fn fetch(agent: &Agent, server: &FhirServer, query: &str) -> Result<Value, String> {
+    let url = format!("{}/{}", server.url, query);
+    let mut request = agent.get(&url).set("Accept", "application/fhir+json");
+    if let Some(token) = &server.access_token {
+        request = request.set("Authorization", &format!("Bearer {}", token));
+    }
+    let body = request
+        .call()
+        .map_err(|e| format!("Could not read {}: {}", url, e))?
+        .into_string()
+        .map_err(|e| format!("Could not read {}: {}", url, e))?;
+    serde_json::from_str(&body).map_err(|e| format!("{} is not JSON: {}", url, e))
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1)
+}
+
+fn serve(address: &str, max_body_bytes: usize, threads: usize) -> Result<(), String> {
+    let server = http::listen(address)?;
+    let agent = AgentBuilder::new()
+        .timeout_connect(FHIR_CONNECT_TIMEOUT)
+        .timeout_read(FHIR_READ_TIMEOUT)
+        .build();
+    let fetch = |server: &FhirServer, query: &str| fetch(&agent, server, query);
+    eprintln!("CDS Hooks discovery at http://{}/cds-services", address);
+    http::serve(
+        &server,
+        max_body_bytes,
+        threads,
+        &CORS_HEADERS,
+        |method, path, body| match method {
+            // CORS preflight.
+            "OPTIONS" => (204, Value::Null),
+            _ => handle_request(method, path, body, &today(), &fetch),
+        },
+    );
+    Ok(())
+}
+
+fn main() {
+    let mut host = "127.0.0.1".to_string();
+    let mut port = "8080".to_string();
+    let mut max_body_bytes: usize = 8 << 20;
+    let mut threads: usize = 4;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next();
+        match (arg.as_str(), value) {
+            ("--address", Some(value)) => host = value,
+            ("--port", Some(value)) if value.parse::<u16>().is_ok() => port = value,
+            ("--max-body-bytes", Some(value)) => match value.parse() {
+                Ok(n) => max_body_bytes = n,
+                Err(_) => usage(),
+            },
+            ("--threads", Some(value)) => match value.parse() {
+                Ok(n) if n > 0 => threads = n,
+                _ => usage(),
+            },
+            _ => usage(),
+        }
+    }
+    if let Err(e) = serve(&format!("{}:{}", host, port), max_body_bytes, threads) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
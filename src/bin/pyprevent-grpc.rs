@@ -0,0 +1,32 @@
+use pyprevent::grpc::proto::risk_scoring_server::RiskScoringServer;
+use pyprevent::grpc::ScoringService;
+use std::net::SocketAddr;
+use std::{env, process};
+use tonic::transport::Server;
+
+const USAGE: &str = "Usage: pyprevent-grpc [--address HOST:PORT]";
+
+#[tokio::main]
+async fn main() {
+    let mut address: SocketAddr = "127.0.0.1:50051".parse().expect("default address");
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next().map(|value| value.parse())) {
+            ("--address", Some(Ok(value))) => address = value,
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(1);
+            }
+        }
+    }
+
+    eprintln!("RiskScoring gRPC service at {}", address);
+    let served = Server::builder()
+        .add_service(RiskScoringServer::new(ScoringService))
+        .serve(address)
+        .await;
+    if let Err(e) = served {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
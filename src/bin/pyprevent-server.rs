@@ -0,0 +1,42 @@
+use pyprevent::http;
+use pyprevent::rest::handle_request;
+use std::{env, process};
+
+const USAGE: &str =
+    "Usage: pyprevent-server [--address HOST] [--port N] [--max-body-bytes N] [--threads N]";
+
+fn serve(address: &str, max_body_bytes: usize, threads: usize) -> Result<(), String> {
+    let server = http::listen(address)?;
+    eprintln!("Scoring service at http://{}/ (see /openapi.json)", address);
+    http::serve(&server, max_body_bytes, threads, &[], handle_request);
+    Ok(())
+}
+
+fn main() {
+    let mut host = "127.0.0.1".to_string();
+    let mut port: u16 = 8000;
+    let mut max_body_bytes: usize = 8 << 20;
+    let mut threads: usize = 4;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().unwrap_or_default();
+        let parsed = match arg.as_str() {
+            "--address" => {
+                host = value;
+                true
+            }
+            "--port" => value.parse().map(|n| port = n).is_ok(),
+            "--max-body-bytes" => value.parse().map(|n| max_body_bytes = n).is_ok(),
+            "--threads" => value.parse().map(|n| threads = n).is_ok() && threads > 0,
+            _ => false,
+        };
+        if !parsed {
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        }
+    }
+    if let Err(e) = serve(&format!("{}:{}", host, port), max_body_bytes, threads) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::fhir::{inputs_from_bundle, missing_inputs};
+use crate::model::{risk_category, PreventModel, RiskModel, PATIENT_FIELDS};
+use crate::utils::RiskCategory;
+use serde_json::{json, Value};
+
+/// Identifier of the `patient-view` service, under `/cds-services/`.
+pub const SERVICE_ID: &str = "pyprevent-patient-view";
+
+/// FHIR queries the EHR is asked to prefetch, by prefetch key. Observations
+/// cover the LOINC codes that `fhir::inputs_from_bundle` reads.
+pub const PREFETCH: [(&str, &str); 4] = [
+    ("patient", "Patient/{{context.patientId}}"),
+    (
+        "observations",
+        "Observation?patient={{context.patientId}}\
+&code=2093-3,2085-9,8480-6,85354-9,39156-5,98979-8,62238-1,88293-6,88294-4,33914-3,48642-3,\
+48643-1,72166-2&_sort=-date&_count=200",
+    ),
+    ("conditions", "Condition?patient={{context.patientId}}"),
+    (
+        "medications",
+        "MedicationStatement?patient={{context.patientId}}\
+&_include=MedicationStatement:medication",
+    ),
+];
+
+const SOURCE: &str = "PyPREVENT (AHA PREVENT equations)";
+
+/// The EHR's FHIR server, for queries it did not prefetch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FhirServer {
+    pub url: String,
+    pub access_token: Option<String>,
+}
+
+/// Reads a FHIR query, relative to the server's base URL, and returns the
+/// resource or search Bundle.
+pub type Fetch<'a> = &'a dyn Fn(&FhirServer, &str) -> Result<Value, String>;
+
+/// The CDS Hooks discovery document.
+pub fn discovery() -> Value {
+    let prefetch: serde_json::Map<String, Value> = PREFETCH
+        .iter()
+        .map(|(key, query)| (key.to_string(), json!(query)))
+        .collect();
+    json!({
+        "services": [{
+            "hook": "patient-view",
+            "id": SERVICE_ID,
+            "title": "PREVENT cardiovascular risk",
+            "description": "10- and 30-year risk of CVD, ASCVD and heart failure from the \
+                AHA PREVENT equations.",
+            "prefetch": prefetch,
+        }]
+    })
+}
+
+/// Gathers the prefetched resources, fetching any the EHR left out, into one
+/// collection Bundle for `fhir::inputs_from_bundle`.
+fn patient_bundle(request: &Value, fetch: Fetch) -> Result<Value, String> {
+    let patient_id = request["context"]["patientId"]
+        .as_str()
+        .ok_or("Request context has no patientId.")?;
+    let server = request["fhirServer"].as_str().map(|url| FhirServer {
+        url: url.trim_end_matches('/').to_string(),
+        access_token: request["fhirAuthorization"]["access_token"]
+            .as_str()
+            .map(str::to_string),
+    });
+
+    let mut entries = Vec::new();
+    for (key, template) in PREFETCH {
+        let resource = match &request["prefetch"][key] {
+            Value::Null => match &server {
+                Some(server) => fetch(
+                    server,
+                    &template.replace("{{context.patientId}}", patient_id),
+                )?,
+                None if key == "patient" => {
+                    return Err("The patient was not prefetched and there is no fhirServer.".into())
+                }
+                None => continue,
+            },
+            resource => resource.clone(),
+        };
+        if resource["resourceType"] == "Bundle" {
+            let found = resource["entry"].as_array().cloned().unwrap_or_default();
+            entries.extend(
+                found
+                    .into_iter()
+                    .map(|entry| json!({"resource": entry["resource"]})),
+            );
+        } else {
+            entries.push(json!({"resource": resource}));
+        }
+    }
+    Ok(json!({"resourceType": "Bundle", "type": "collection", "entry": entries}))
+}
+
+fn card(summary: String, indicator: &str, detail: String) -> Value {
+    json!({
+        "summary": summary,
+        "indicator": indicator,
+        "detail": detail,
+        "source": {"label": SOURCE},
+    })
+}
+
+fn label(field: &str) -> &str {
+    match field {
+        "total_cholesterol" => "total cholesterol",
+        "hdl_cholesterol" => "HDL cholesterol",
+        "systolic_bp" => "systolic blood pressure",
+        "current_smoker" => "smoking status",
+        "bmi" => "BMI",
+        "egfr" => "eGFR",
+        "has_diabetes" => "diabetes",
+        "on_htn_meds" => "antihypertensive treatment",
+        "on_cholesterol_meds" => "statin treatment",
+        field => field,
+    }
+}
+
+/// Cards for a `patient-view` request: the PREVENT risks, or a warning
+/// naming the inputs with no Observation, and a note of the flags assumed
+/// false for lack of a Condition or MedicationStatement.
+pub fn patient_view(request: &Value, today: &str, fetch: Fetch) -> Result<Value, String> {
+    let bundle = patient_bundle(request, fetch)?;
+    let missing = missing_inputs(&bundle, today)?;
+    if !missing.is_empty() {
+        let names: Vec<&str> = missing.iter().map(|field| label(field)).collect();
+        let summary = format!("PREVENT risk needs {}", names.join(", "));
+        let detail = format!(
+            "PREVENT risk could not be calculated because the record has no result for: {}.",
+            names.join(", ")
+        );
+        return Ok(json!({"cards": [card(summary, "warning", detail)]}));
+    }
+    let inputs = inputs_from_bundle(&bundle, today)?;
+
+    let mut rows = vec![
+        "| Outcome | 10-year risk | 30-year risk |".to_string(),
+        "| --- | --- | --- |".to_string(),
+    ];
+    let mut headline = None;
+    for pair in PreventModel::ALL.chunks(2) {
+        let cells: Vec<String> = pair
+            .iter()
+            .map(|model| match model.predict(&inputs.patient) {
+                Ok(risk) => match risk_category(model, risk) {
+                    Some(category) => {
+                        if *model == PreventModel::Cvd10Yr {
+                            headline = Some((risk, category));
+                        }
+                        format!("{:.1}% ({})", risk, category.label())
+                    }
+                    _ => format!("{:.1}%", risk),
+                },
+                Err(e) => format!("not available: {}", e),
+            })
+            .collect();
+        let outcome = pair[0].metadata().outcome.to_uppercase();
+        rows.push(format!("| {} | {} |", outcome, cells.join(" | ")));
+    }
+    let detail = rows.join("\n");
+    let risk_card = match headline {
+        Some((risk, category)) => card(
+            format!(
+                "PREVENT 10-year CVD risk {:.1}% ({})",
+                risk,
+                category.label()
+            ),
+            if category == RiskCategory::High {
+                "warning"
+            } else {
+                "info"
+            },
+            detail,
+        ),
+        None => card(
+            "PREVENT 10-year CVD risk is not available".to_string(),
+            "warning",
+            detail,
+        ),
+    };
+
+    let mut cards = vec![risk_card];
+    let assumed: Vec<&str> = PATIENT_FIELDS
+        .iter()
+        .zip(&inputs.provenance)
+        .filter(|(_, source)| source.is_none())
+        .map(|(field, _)| label(field))
+        .collect();
+    if !assumed.is_empty() {
+        cards.push(card(
+            format!("PREVENT assumed no {}", assumed.join(", no ")),
+            "info",
+            "No active Condition or MedicationStatement in the record indicated these, so the \
+             risks above assume they are absent."
+                .to_string(),
+        ));
+    }
+    Ok(json!({ "cards": cards }))
+}
+
+/// Routes a CDS Hooks HTTP request, returning the status code and JSON body.
+pub fn handle_request(
+    method: &str,
+    path: &str,
+    body: &str,
+    today: &str,
+    fetch: Fetch,
+) -> (u16, Value) {
+    let service = format!("/cds-services/{}", SERVICE_ID);
+    match (method, path.trim_end_matches('/')) {
+        ("GET", "/cds-services") => (200, discovery()),
+        ("POST", path) if path == service => {
+            let result = serde_json::from_str::<Value>(body)
+                .map_err(|e| format!("Invalid JSON: {}", e))
+                .and_then(|request| {
+                    if request["hook"] != "patient-view" {
+                        return Err("This service only handles the patient-view hook.".into());
+                    }
+                    patient_view(&request, today, fetch)
+                });
+            match result {
+                Ok(cards) => (200, cards),
+                Err(e) => (400, json!({ "error": e })),
+            }
+        }
+        _ => (
+            404,
+            json!({ "error": format!("No route for {} {}", method, path) }),
+        ),
+    }
+}
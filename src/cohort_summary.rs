@@ -0,0 +1,468 @@
+use crate::model::{
+    find_model, predict_batch, risk_category, Patient, RiskModel, Sex, PATIENT_FIELDS,
+};
+use crate::utils::RiskCategory;
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Patient characteristics that cohort summaries are stratified by, after
+/// the whole cohort (`overall`).
+pub const STRATIFIERS: [&str; 6] = [
+    "overall",
+    "sex",
+    "age_band",
+    "diabetes",
+    "smoking",
+    "ckd_stage",
+];
+
+/// Names of the values in `SummaryRow::values`, in order.
+pub const SUMMARY_COLUMNS: [&str; 11] = [
+    "n",
+    "invalid",
+    "mean",
+    "median",
+    "q1",
+    "q3",
+    "iqr",
+    "proportion_low",
+    "proportion_borderline",
+    "proportion_intermediate",
+    "proportion_high",
+];
+
+/// KDIGO GFR category.
+pub fn ckd_stage(egfr: f64) -> &'static str {
+    match egfr {
+        e if e >= 90.0 => "G1",
+        e if e >= 60.0 => "G2",
+        e if e >= 45.0 => "G3a",
+        e if e >= 30.0 => "G3b",
+        e if e >= 15.0 => "G4",
+        e if e >= 0.0 => "G5",
+        _ => "missing",
+    }
+}
+
+/// Ten-year age band, e.g. `40-49`.
+pub fn age_band(age: f64) -> String {
+    if age.is_finite() {
+        let start = (age / 10.0).floor() * 10.0;
+        format!("{}-{}", start, start + 9.0)
+    } else {
+        "missing".to_string()
+    }
+}
+
+/// The level of `stratifier` that a patient belongs to.
+pub fn stratum(patient: &Patient, stratifier: &str) -> Result<String, String> {
+    let yes_no = |value: bool| if value { "yes" } else { "no" }.to_string();
+    match stratifier {
+        "overall" => Ok("all".to_string()),
+        "sex" => Ok(patient.sex.as_str().to_string()),
+        "age_band" => Ok(age_band(patient.age)),
+        "diabetes" => Ok(yes_no(patient.has_diabetes)),
+        "smoking" => Ok(yes_no(patient.current_smoker)),
+        "ckd_stage" => Ok(ckd_stage(patient.egfr).to_string()),
+        _ => Err(format!(
+            "Stratifier must be one of {}, not '{}'.",
+            STRATIFIERS.join(", "),
+            stratifier
+        )),
+    }
+}
+
+/// The `PATIENT_FIELDS` input a stratifier is based on.
+fn stratifier_field(stratifier: &str) -> Option<&'static str> {
+    match stratifier {
+        "sex" => Some("sex"),
+        "age_band" => Some("age"),
+        "diabetes" => Some("has_diabetes"),
+        "smoking" => Some("current_smoker"),
+        "ckd_stage" => Some("egfr"),
+        _ => None,
+    }
+}
+
+/// Descriptive statistics of one model's predictions in one stratum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SummaryRow {
+    pub model: String,
+    pub stratifier: &'static str,
+    pub level: String,
+    /// Patients in the stratum.
+    pub n: usize,
+    /// Patients in the stratum whose prediction is NaN because their inputs
+    /// failed validation. The statistics below exclude them.
+    pub invalid: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub q1: f64,
+    pub q3: f64,
+    /// Share of valid predictions in each of `RiskCategory::ALL`, or NaN for
+    /// models whose risks have no `risk_category`.
+    pub category_proportions: [f64; 4],
+}
+
+impl SummaryRow {
+    /// The statistics in the order of `SUMMARY_COLUMNS`.
+    pub fn values(&self) -> [f64; 11] {
+        let [low, borderline, intermediate, high] = self.category_proportions;
+        [
+            self.n as f64,
+            self.invalid as f64,
+            self.mean,
+            self.median,
+            self.q1,
+            self.q3,
+            self.q3 - self.q1,
+            low,
+            borderline,
+            intermediate,
+            high,
+        ]
+    }
+}
+
+/// Quantile of sorted values, interpolating linearly between order
+/// statistics as numpy does by default.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let position = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+fn summary_row(
+    model: &dyn RiskModel,
+    stratifier: &'static str,
+    level: String,
+    risks: &[f64],
+) -> SummaryRow {
+    let mut valid: Vec<f64> = risks.iter().copied().filter(|r| !r.is_nan()).collect();
+    valid.sort_by(f64::total_cmp);
+    let count = valid.len() as f64;
+    let categorized = risk_category(model, 0.0).is_some();
+    let category_proportions = RiskCategory::ALL.map(|category| {
+        if categorized {
+            valid
+                .iter()
+                .filter(|risk| risk_category(model, **risk) == Some(category))
+                .count() as f64
+                / count
+        } else {
+            f64::NAN
+        }
+    });
+    SummaryRow {
+        model: model.metadata().key.to_string(),
+        stratifier,
+        level,
+        n: risks.len(),
+        invalid: risks.len() - valid.len(),
+        mean: valid.iter().sum::<f64>() / count,
+        median: quantile(&valid, 0.5),
+        q1: quantile(&valid, 0.25),
+        q3: quantile(&valid, 0.75),
+        category_proportions,
+    }
+}
+
+/// Summarizes each model's batch predictions (NaN for rows that failed
+/// validation) over the cohort and within each level of each of
+/// `STRATIFIERS`, with levels in sorted order.
+pub fn summarize_cohort(
+    patients: &[Patient],
+    predictions: &[(&str, &[f64])],
+) -> Result<Vec<SummaryRow>, String> {
+    summarize_rows(patients, &[], predictions)
+}
+
+/// `summarize_cohort`, where `unreadable[i]` lists the inputs of row `i` that
+/// could not be read. Those rows are in the `missing` level of the
+/// stratifiers based on them.
+fn summarize_rows(
+    patients: &[Patient],
+    unreadable: &[Vec<&str>],
+    predictions: &[(&str, &[f64])],
+) -> Result<Vec<SummaryRow>, String> {
+    if patients.is_empty() {
+        return Err("Cohort has no patients.".to_string());
+    }
+    let mut rows = Vec::new();
+    for (key, risks) in predictions {
+        let model = find_model(key)?;
+        if risks.len() != patients.len() {
+            return Err(format!(
+                "Expected {} predictions for '{}', got {}.",
+                patients.len(),
+                key,
+                risks.len()
+            ));
+        }
+        for stratifier in STRATIFIERS {
+            let mut levels: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+            for (i, (patient, risk)) in patients.iter().zip(risks.iter()).enumerate() {
+                let field = stratifier_field(stratifier);
+                let level = match unreadable.get(i) {
+                    Some(fields) if field.is_some_and(|field| fields.contains(&field)) => {
+                        "missing".to_string()
+                    }
+                    _ => stratum(patient, stratifier)?,
+                };
+                levels.entry(level).or_default().push(*risk);
+            }
+            rows.extend(
+                levels
+                    .into_iter()
+                    .map(|(level, risks)| summary_row(model.as_ref(), stratifier, level, &risks)),
+            );
+        }
+    }
+    Ok(rows)
+}
+
+/// Labels and statistics of one exported row, in the order of `SUMMARY_COLUMNS`.
+pub(crate) type ExportRow<'a> = (Vec<&'a str>, [f64; 11]);
+
+/// CSV with the given label columns followed by `SUMMARY_COLUMNS`. NaN
+/// statistics are left empty.
+pub(crate) fn export_csv(labels: &[&str], rows: &[ExportRow]) -> String {
+    let mut csv = format!("{},{}\n", labels.join(","), SUMMARY_COLUMNS.join(","));
+    for (row_labels, values) in rows {
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| {
+                if value.is_nan() {
+                    String::new()
+                } else {
+                    value.to_string()
+                }
+            })
+            .collect();
+        csv.push_str(&format!("{},{}\n", row_labels.join(","), values.join(",")));
+    }
+    csv
+}
+
+/// An array of objects keyed by the label columns and `SUMMARY_COLUMNS`. NaN
+/// statistics are `null`, and whole numbers such as counts are integers.
+pub(crate) fn export_json(labels: &[&str], rows: &[ExportRow]) -> String {
+    let objects: Vec<Value> = rows
+        .iter()
+        .map(|(row_labels, values)| {
+            let mut object = Map::new();
+            for (name, label) in labels.iter().zip(row_labels) {
+                object.insert(name.to_string(), json!(label));
+            }
+            for (name, value) in SUMMARY_COLUMNS.iter().zip(values) {
+                let value = if value.fract() == 0.0 && value.abs() < 1e15 {
+                    json!(*value as i64)
+                } else {
+                    json!(value)
+                };
+                object.insert(name.to_string(), value);
+            }
+            Value::Object(object)
+        })
+        .collect();
+    let mut json = serde_json::to_string_pretty(&objects).expect("values are serializable");
+    json.push('\n');
+    json
+}
+
+const SUMMARY_LABELS: [&str; 3] = ["model", "stratifier", "level"];
+
+fn export_rows(rows: &[SummaryRow]) -> Vec<ExportRow<'_>> {
+    rows.iter()
+        .map(|row| {
+            (
+                vec![row.model.as_str(), row.stratifier, row.level.as_str()],
+                row.values(),
+            )
+        })
+        .collect()
+}
+
+/// One line per row, with a `model,stratifier,level` header followed by
+/// `SUMMARY_COLUMNS`. NaN statistics are left empty.
+pub fn summary_csv(rows: &[SummaryRow]) -> String {
+    export_csv(&SUMMARY_LABELS, &export_rows(rows))
+}
+
+/// An array of objects with the keys of `summary_csv`'s header. NaN
+/// statistics are `null`.
+pub fn summary_json(rows: &[SummaryRow]) -> String {
+    export_json(&SUMMARY_LABELS, &export_rows(rows))
+}
+
+/// Patients, the inputs of each that could not be read, and the file's other
+/// columns by name.
+type Cohort = (
+    Vec<Patient>,
+    Vec<Vec<&'static str>>,
+    BTreeMap<String, Vec<f64>>,
+);
+
+/// Reads patients from a CSV file with a header naming at least
+/// `PATIENT_FIELDS`, in any order. Sex is `male` or `female` and flags are
+/// `0`/`1` or `true`/`false`. Empty or non-numeric measurements are NaN, so
+/// that the row fails validation, and any other sex or flag is listed as
+/// unreadable for the row, which reads it as female or false. Other columns
+/// are returned by name, as numbers, for use as precomputed predictions.
+pub fn patients_from_csv(csv: &str) -> Result<Cohort, String> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<&str> = lines
+        .next()
+        .ok_or("Cohort file is empty")?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let mut indices = [0; 11];
+    for (index, column) in indices.iter_mut().zip(PATIENT_FIELDS) {
+        *index = header
+            .iter()
+            .position(|name| *name == column)
+            .ok_or_else(|| format!("Cohort file has no '{}' column", column))?;
+    }
+    let mut extra: BTreeMap<String, Vec<f64>> = header
+        .iter()
+        .filter(|name| !PATIENT_FIELDS.contains(name))
+        .map(|name| (name.to_string(), Vec::new()))
+        .collect();
+
+    let mut patients = Vec::new();
+    let mut unreadable = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let row_error = |message: &str| format!("Cohort row {}: {}", i + 1, message);
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if fields.len() != header.len() {
+            return Err(row_error(&format!("expected {} fields", header.len())));
+        }
+        let number = |column: usize| fields[indices[column]].parse().unwrap_or(f64::NAN);
+        let mut unread = Vec::new();
+        let sex = Sex::parse(fields[indices[0]]).unwrap_or_else(|_| {
+            unread.push(PATIENT_FIELDS[0]);
+            Sex::Female
+        });
+        let mut flag = |column: usize| match fields[indices[column]].to_lowercase().as_str() {
+            "1" | "1.0" | "true" => true,
+            "0" | "0.0" | "false" => false,
+            _ => {
+                unread.push(PATIENT_FIELDS[column]);
+                false
+            }
+        };
+        let flags = [flag(5), flag(6), flag(9), flag(10)];
+        patients.push(Patient {
+            sex,
+            age: number(1),
+            total_cholesterol: number(2),
+            hdl_cholesterol: number(3),
+            systolic_bp: number(4),
+            has_diabetes: flags[0],
+            current_smoker: flags[1],
+            bmi: number(7),
+            egfr: number(8),
+            on_htn_meds: flags[2],
+            on_cholesterol_meds: flags[3],
+        });
+        unreadable.push(unread);
+        for (name, field) in header.iter().zip(&fields) {
+            if let Some(values) = extra.get_mut(*name) {
+                values.push(field.parse().unwrap_or(f64::NAN));
+            }
+        }
+    }
+    Ok((patients, unreadable, extra))
+}
+
+/// Summarizes a cohort file for each model in `keys`. A model's predictions
+/// are read from a column named after its key if the file has one, and
+/// calculated from the inputs otherwise. Rows with an unreadable sex or flag
+/// count as invalid, like rows whose measurements fail validation.
+pub fn summarize_cohort_file(path: &str, keys: &[&str]) -> Result<Vec<SummaryRow>, String> {
+    let csv = fs::read_to_string(path)
+        .map_err(|e| format!("Could not read cohort file '{}': {}", path, e))?;
+    let (patients, unreadable, columns) = patients_from_csv(&csv)?;
+    let mut predictions = Vec::new();
+    for key in keys {
+        let mut risks = match columns.get(*key) {
+            Some(risks) => risks.clone(),
+            None => predict_batch(find_model(key)?.as_ref(), &patients),
+        };
+        for (risk, unread) in risks.iter_mut().zip(&unreadable) {
+            if !unread.is_empty() {
+                *risk = f64::NAN;
+            }
+        }
+        predictions.push((*key, risks));
+    }
+    let predictions: Vec<(&str, &[f64])> = predictions
+        .iter()
+        .map(|(key, risks)| (*key, risks.as_slice()))
+        .collect();
+    summarize_rows(&patients, &unreadable, &predictions)
+}
+
+/// Summarizes an (n, k) array of batch `predictions`, one column for each of
+/// the k `models`, for the patients in `data`.
+pub(crate) fn summarize_arrays(
+    data: &PyReadonlyArrayDyn<f64>,
+    models: &[String],
+    predictions: &PyReadonlyArrayDyn<f64>,
+) -> PyResult<Vec<SummaryRow>> {
+    let patients = crate::utils::patients(data)?;
+    let shape = predictions.shape();
+    if shape.len() != 2 || shape[1] != models.len() {
+        return Err(PyValueError::new_err(
+            "Predictions must have one column per model",
+        ));
+    }
+    let columns: Vec<Vec<f64>> = predictions
+        .as_array()
+        .columns()
+        .into_iter()
+        .map(|column| column.to_vec())
+        .collect();
+    let predictions: Vec<(&str, &[f64])> = models
+        .iter()
+        .zip(&columns)
+        .map(|(key, risks)| (key.as_str(), risks.as_slice()))
+        .collect();
+    match summarize_cohort(&patients, &predictions) {
+        Ok(rows) => Ok(rows),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+/// (models, stratifiers, levels, (rows, columns) statistics).
+type CohortSummary = (Vec<String>, Vec<&'static str>, Vec<String>, PyObject);
+
+/// `predictions` is an (n, k) array with a column of batch results for each
+/// of the k `models`.
+#[pyfunction]
+pub fn cohort_summary_rust(
+    py: Python,
+    data: PyReadonlyArrayDyn<f64>,
+    models: Vec<String>,
+    predictions: PyReadonlyArrayDyn<f64>,
+) -> PyResult<CohortSummary> {
+    let rows = summarize_arrays(&data, &models, &predictions)?;
+    let values: Vec<Vec<f64>> = rows.iter().map(|row| row.values().to_vec()).collect();
+    let values = PyArray::from_vec2(py, &values)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?
+        .to_object(py);
+    Ok((
+        rows.iter().map(|row| row.model.clone()).collect(),
+        rows.iter().map(|row| row.stratifier).collect(),
+        rows.iter().map(|row| row.level.clone()).collect(),
+        values,
+    ))
+}
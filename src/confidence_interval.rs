@@ -0,0 +1,464 @@
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+use crate::utils::{common_calculation_design, patients, risk_from_log_odds};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, StandardNormal};
+use rayon::prelude::*;
+use std::fs;
+
+const COVARIANCE_HEADER: [&str; 5] = ["model", "sex", "row", "column", "covariance"];
+
+/// Names of the PREVENT coefficients, as the `Covariates` fields they are
+/// stored in. `cholesterol_base_multiplier` is a unit conversion and has no
+/// uncertainty.
+pub const COEFFICIENT_NAMES: [&str; 24] = [
+    "constant",
+    "diabetes_factor",
+    "smoker_factor",
+    "htn_meds_factor",
+    "cholesterol_meds_factor",
+    "age_adjustment_factor",
+    "age_squared_factor",
+    "total_cholesterol_diff_factor",
+    "hdl_cholesterol_diff_factor",
+    "systolic_bp_min_factor",
+    "systolic_bp_max_factor",
+    "egfr_max_factor",
+    "egfr_min_factor",
+    "htn_meds_systolic_bp_max_factor",
+    "cholesterol_meds_cholesterol_diff_factor",
+    "age_adjustment_cholesterol_diff_factor",
+    "age_adjustment_hdl_cholesterol_diff_factor",
+    "age_adjustment_systolic_bp_max_factor",
+    "age_min_egfr_factor",
+    "diabetes_age_factor",
+    "smoker_age_factor",
+    "bmi_min_factor",
+    "bmi_max_factor",
+    "age_bmi_max_factor",
+];
+
+/// Variance-covariance matrix of the coefficients of one PREVENT equation, in
+/// the order of `COEFFICIENT_NAMES`. Coefficients without a variance are
+/// treated as exact.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CovarianceBlock {
+    pub model: PreventModel,
+    pub sex: Sex,
+    pub matrix: [[f64; 24]; 24],
+    /// Indices of the coefficients with a positive variance, and the lower
+    /// Cholesky factor of their covariance matrix.
+    cholesky: (Vec<usize>, Vec<Vec<f64>>),
+}
+
+impl CovarianceBlock {
+    fn new(model: PreventModel, sex: Sex, matrix: [[f64; 24]; 24]) -> Result<Self, String> {
+        let indices: Vec<usize> = (0..24).filter(|i| matrix[*i][*i] > 0.0).collect();
+        let covaries_without_variance =
+            (0..24).any(|i| !indices.contains(&i) && matrix[i].iter().any(|value| *value != 0.0));
+        let cholesky = cholesky(&indices, &matrix)
+            .filter(|_| !covaries_without_variance)
+            .ok_or_else(|| {
+                format!(
+                    "Coefficient covariance for {} {} must be positive definite",
+                    model.metadata().key,
+                    sex.as_str()
+                )
+            })?;
+        Ok(CovarianceBlock {
+            model,
+            sex,
+            matrix,
+            cholesky: (indices, cholesky),
+        })
+    }
+}
+
+/// Lower Cholesky factor of the submatrix of `matrix` on `indices`, or `None`
+/// if it is not positive definite.
+fn cholesky(indices: &[usize], matrix: &[[f64; 24]; 24]) -> Option<Vec<Vec<f64>>> {
+    let k = indices.len();
+    let mut lower = vec![vec![0.0; k]; k];
+    for i in 0..k {
+        for j in 0..=i {
+            let dot: f64 = (0..j).map(|m| lower[i][m] * lower[j][m]).sum();
+            let value = matrix[indices[i]][indices[j]] - dot;
+            if i == j {
+                if value.is_nan() || value <= 0.0 {
+                    return None;
+                }
+                lower[i][i] = value.sqrt();
+            } else {
+                lower[i][j] = value / lower[j][j];
+            }
+        }
+    }
+    Some(lower)
+}
+
+/// User-supplied coefficient covariance matrices, by PREVENT model and sex.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CoefficientCovariance {
+    pub blocks: Vec<CovarianceBlock>,
+}
+
+impl CoefficientCovariance {
+    /// Parses a CSV file with the header `model,sex,row,column,covariance`
+    /// and one row per matrix entry, e.g.
+    /// `10_yr_cvd,female,constant,age_adjustment_factor,-0.0001`. Rows and
+    /// columns are from `COEFFICIENT_NAMES`. Each pair of coefficients may be
+    /// given in either order or both; entries not given are zero.
+    pub fn from_csv(csv: &str) -> Result<CoefficientCovariance, String> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let header: Vec<_> = lines
+            .next()
+            .ok_or("Coefficient covariance file is empty")?
+            .split(',')
+            .map(str::trim)
+            .collect();
+        if header != COVARIANCE_HEADER {
+            return Err(format!(
+                "Coefficient covariance header must be '{}'",
+                COVARIANCE_HEADER.join(",")
+            ));
+        }
+
+        let mut matrices: Vec<(PreventModel, Sex, [[f64; 24]; 24])> = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let row_error =
+                |message: &str| format!("Coefficient covariance row {}: {}", i + 1, message);
+            let fields: Vec<_> = line.split(',').map(str::trim).collect();
+            if fields.len() != COVARIANCE_HEADER.len() {
+                return Err(row_error("expected 5 fields"));
+            }
+            let model = PreventModel::from_key(fields[0]).map_err(|e| row_error(&e))?;
+            let sex = Sex::parse(fields[1]).map_err(|e| row_error(&e))?;
+            let mut indices = [0; 2];
+            for (index, name) in indices.iter_mut().zip(&fields[2..4]) {
+                *index = COEFFICIENT_NAMES
+                    .iter()
+                    .position(|coefficient| coefficient == name)
+                    .ok_or_else(|| row_error(&format!("unknown coefficient '{}'", name)))?;
+            }
+            let covariance = fields[4]
+                .parse::<f64>()
+                .ok()
+                .filter(|covariance| covariance.is_finite())
+                .ok_or_else(|| row_error(&format!("'{}' is not a number", fields[4])))?;
+
+            let matrix = match matrices
+                .iter_mut()
+                .find(|entry| entry.0 == model && entry.1 == sex)
+            {
+                Some(entry) => &mut entry.2,
+                None => {
+                    matrices.push((model, sex, [[0.0; 24]; 24]));
+                    &mut matrices.last_mut().unwrap().2
+                }
+            };
+            let [row, column] = indices;
+            let previous = matrix[row][column];
+            if previous != 0.0 && previous != covariance {
+                return Err(row_error("conflicts with an earlier entry"));
+            }
+            matrix[row][column] = covariance;
+            matrix[column][row] = covariance;
+        }
+
+        let blocks = matrices
+            .into_iter()
+            .map(|(model, sex, matrix)| CovarianceBlock::new(model, sex, matrix))
+            .collect::<Result<_, _>>()?;
+        Ok(CoefficientCovariance { blocks })
+    }
+
+    pub fn load(path: &str) -> Result<CoefficientCovariance, String> {
+        let csv = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read coefficient covariance '{}': {}", path, e))?;
+        CoefficientCovariance::from_csv(&csv)
+    }
+
+    pub fn block(&self, model: PreventModel, sex: Sex) -> Option<&CovarianceBlock> {
+        self.blocks
+            .iter()
+            .find(|block| block.model == model && block.sex == sex)
+    }
+}
+
+/// Confidence intervals (%) for a predicted risk that reflect uncertainty in
+/// the fitted coefficients only, not in the inputs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RiskConfidenceInterval {
+    pub risk: f64,
+    /// Standard error of the log-odds, sqrt(x' V x) for design vector x.
+    pub log_odds_se: f64,
+    /// Delta method: a normal interval on the log-odds scale, transformed to
+    /// risk.
+    pub delta_lower: f64,
+    pub delta_upper: f64,
+    /// Percentiles of the risk over coefficients drawn from a multivariate
+    /// normal distribution centred on the published values.
+    pub simulation_lower: f64,
+    pub simulation_upper: f64,
+}
+
+/// Inverse of the standard normal CDF, by Acklam's rational approximation
+/// (relative error below 1.2e-9).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+fn check_interval_arguments(level: f64, samples: usize) -> Result<(), String> {
+    if !(level > 0.0 && level < 1.0) {
+        return Err("Confidence level must be between 0 and 1.".to_string());
+    }
+    if samples < 2 {
+        return Err("At least 2 samples are needed.".to_string());
+    }
+    Ok(())
+}
+
+/// The design vector of `model` for a patient, from the same inputs as the
+/// point estimate.
+pub(crate) fn design(model: PreventModel, patient: &Patient) -> [f64; 24] {
+    common_calculation_design(
+        &model.covariates(patient.sex),
+        patient.has_diabetes,
+        patient.current_smoker,
+        patient.on_htn_meds,
+        patient.on_cholesterol_meds,
+        patient.systolic_bp,
+        patient.total_cholesterol - patient.hdl_cholesterol,
+        patient.hdl_cholesterol,
+        (patient.age - 55.0) / 10.0,
+        patient.egfr,
+        if model.uses_bmi() { patient.bmi } else { 0.0 },
+    )
+}
+
+/// Delta-method and simulation confidence intervals at `level` (e.g. 0.95)
+/// for the risk of `model`, given the covariance of its coefficients for the
+/// patient's sex. The log-odds are linear in the coefficients, so the two
+/// intervals agree up to simulation error; the simulation interval does not
+/// rely on that and is reported as a check. The same `seed` always gives the
+/// same result.
+pub fn risk_confidence_interval(
+    model: PreventModel,
+    patient: &Patient,
+    covariance: &CoefficientCovariance,
+    level: f64,
+    samples: usize,
+    seed: u64,
+) -> Result<RiskConfidenceInterval, String> {
+    check_interval_arguments(level, samples)?;
+    let risk = model.predict(patient)?;
+    let block = covariance.block(model, patient.sex).ok_or_else(|| {
+        format!(
+            "No coefficient covariance for {} {}.",
+            model.metadata().key,
+            patient.sex.as_str()
+        )
+    })?;
+    let covariates = model.covariates(patient.sex);
+    let design = design(model, patient);
+    let coefficients = covariates.coefficients();
+    let log_odds: f64 = design.iter().zip(coefficients).map(|(x, b)| x * b).sum();
+
+    let variance: f64 = (0..24)
+        .flat_map(|i| (0..24).map(move |j| (i, j)))
+        .map(|(i, j)| design[i] * block.matrix[i][j] * design[j])
+        .sum();
+    let log_odds_se = variance.max(0.0).sqrt();
+    let z = normal_quantile(0.5 + level / 2.0);
+
+    let (indices, lower) = &block.cholesky;
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut risks: Vec<f64> = (0..samples)
+        .map(|_| {
+            let normals: Vec<f64> = indices
+                .iter()
+                .map(|_| StandardNormal.sample(&mut rng))
+                .collect();
+            let mut drawn = coefficients;
+            for (i, index) in indices.iter().enumerate() {
+                drawn[*index] += (0..=i).map(|j| lower[i][j] * normals[j]).sum::<f64>();
+            }
+            let drawn_log_odds: f64 = design.iter().zip(drawn).map(|(x, b)| x * b).sum();
+            risk_from_log_odds(drawn_log_odds)
+        })
+        .collect();
+    risks.sort_by(f64::total_cmp);
+    let percentile = |p: f64| {
+        let position = p * (samples - 1) as f64;
+        let (below, above) = (position.floor() as usize, position.ceil() as usize);
+        risks[below] + (risks[above] - risks[below]) * (position - below as f64)
+    };
+
+    Ok(RiskConfidenceInterval {
+        risk,
+        log_odds_se,
+        delta_lower: risk_from_log_odds(log_odds - z * log_odds_se),
+        delta_upper: risk_from_log_odds(log_odds + z * log_odds_se),
+        simulation_lower: percentile(0.5 - level / 2.0),
+        simulation_upper: percentile(0.5 + level / 2.0),
+    })
+}
+
+/// Names of the values in a flattened `RiskConfidenceInterval`, in order.
+pub const CONFIDENCE_INTERVAL_COLUMNS: [&str; 6] = [
+    "risk",
+    "log_odds_se",
+    "delta_lower",
+    "delta_upper",
+    "simulation_lower",
+    "simulation_upper",
+];
+
+impl RiskConfidenceInterval {
+    fn to_array(self) -> [f64; 6] {
+        [
+            self.risk,
+            self.log_odds_se,
+            self.delta_lower,
+            self.delta_upper,
+            self.simulation_lower,
+            self.simulation_upper,
+        ]
+    }
+}
+
+#[pyfunction]
+pub fn coefficient_names_rust() -> Vec<&'static str> {
+    COEFFICIENT_NAMES.to_vec()
+}
+
+#[pyfunction]
+pub fn risk_confidence_interval_rust(
+    outcome: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    covariance_path: String,
+    level: f64,
+    samples: usize,
+    seed: u64,
+) -> PyResult<(Vec<&'static str>, Vec<f64>)> {
+    let model = PreventModel::from_key(&outcome).map_err(PyValueError::new_err)?;
+    let covariance =
+        CoefficientCovariance::load(&covariance_path).map_err(PyValueError::new_err)?;
+    let patient = Patient {
+        sex: Sex::parse(&sex).map_err(PyValueError::new_err)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    };
+    match risk_confidence_interval(model, &patient, &covariance, level, samples, seed) {
+        Ok(interval) => Ok((
+            CONFIDENCE_INTERVAL_COLUMNS.to_vec(),
+            interval.to_array().to_vec(),
+        )),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+/// Returns the column names and an (n, 6) array of intervals. Row `i` is
+/// simulated with seed `seed + i`. Rows with invalid inputs, or of a sex
+/// without a covariance matrix, are NaN.
+#[pyfunction]
+pub fn risk_confidence_interval_rust_parallel_np(
+    py: Python,
+    outcome: String,
+    data: PyReadonlyArrayDyn<f64>,
+    covariance_path: String,
+    level: f64,
+    samples: usize,
+    seed: u64,
+) -> PyResult<(Vec<&'static str>, PyObject)> {
+    let model = PreventModel::from_key(&outcome).map_err(PyValueError::new_err)?;
+    let covariance =
+        CoefficientCovariance::load(&covariance_path).map_err(PyValueError::new_err)?;
+    check_interval_arguments(level, samples).map_err(PyValueError::new_err)?;
+    let patients = patients(&data)?;
+
+    let results: Vec<Vec<f64>> = patients
+        .par_iter()
+        .enumerate()
+        .map(|(i, patient)| {
+            risk_confidence_interval(
+                model,
+                patient,
+                &covariance,
+                level,
+                samples,
+                seed.wrapping_add(i as u64),
+            )
+            .map(|interval| interval.to_array().to_vec())
+            .unwrap_or_else(|_| vec![f64::NAN; 6]) // Handle error by returning NaN
+        })
+        .collect();
+
+    let intervals = PyArray::from_vec2(py, &results)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?
+        .to_object(py);
+    Ok((CONFIDENCE_INTERVAL_COLUMNS.to_vec(), intervals))
+}
@@ -385,4 +385,283 @@ impl Covariates {
             age_bmi_max_factor: -0.0182831,
         }
     }
+
+    /// The fitted coefficients, in the order of `COEFFICIENT_NAMES`.
+    /// `cholesterol_base_multiplier` is a unit conversion, not a coefficient.
+    pub(crate) fn coefficients(&self) -> [f64; 24] {
+        [
+            self.constant,
+            self.diabetes_factor,
+            self.smoker_factor,
+            self.htn_meds_factor,
+            self.cholesterol_meds_factor,
+            self.age_adjustment_factor,
+            self.age_squared_factor,
+            self.total_cholesterol_diff_factor,
+            self.hdl_cholesterol_diff_factor,
+            self.systolic_bp_min_factor,
+            self.systolic_bp_max_factor,
+            self.egfr_max_factor,
+            self.egfr_min_factor,
+            self.htn_meds_systolic_bp_max_factor,
+            self.cholesterol_meds_cholesterol_diff_factor,
+            self.age_adjustment_cholesterol_diff_factor,
+            self.age_adjustment_hdl_cholesterol_diff_factor,
+            self.age_adjustment_systolic_bp_max_factor,
+            self.age_min_egfr_factor,
+            self.diabetes_age_factor,
+            self.smoker_age_factor,
+            self.bmi_min_factor,
+            self.bmi_max_factor,
+            self.age_bmi_max_factor,
+        ]
+    }
+}
+
+/// Race- and sex-specific coefficients of the 2013 ACC/AHA Pooled Cohort
+/// Equations (Goff et al., Circulation 2014, Table A).
+pub(crate) struct PooledCohortCoefficients {
+    pub(crate) ln_age: f64,
+    pub(crate) ln_age_squared: f64,
+    pub(crate) ln_total_cholesterol: f64,
+    pub(crate) ln_age_ln_total_cholesterol: f64,
+    pub(crate) ln_hdl_cholesterol: f64,
+    pub(crate) ln_age_ln_hdl_cholesterol: f64,
+    pub(crate) ln_treated_systolic_bp: f64,
+    pub(crate) ln_age_ln_treated_systolic_bp: f64,
+    pub(crate) ln_untreated_systolic_bp: f64,
+    pub(crate) ln_age_ln_untreated_systolic_bp: f64,
+    pub(crate) smoker: f64,
+    pub(crate) ln_age_smoker: f64,
+    pub(crate) diabetes: f64,
+    pub(crate) baseline_survival: f64,
+    pub(crate) mean_sum: f64,
+}
+
+impl PooledCohortCoefficients {
+    pub(crate) fn white_female() -> PooledCohortCoefficients {
+        PooledCohortCoefficients {
+            ln_age: -29.799,
+            ln_age_squared: 4.884,
+            ln_total_cholesterol: 13.54,
+            ln_age_ln_total_cholesterol: -3.114,
+            ln_hdl_cholesterol: -13.578,
+            ln_age_ln_hdl_cholesterol: 3.149,
+            ln_treated_systolic_bp: 2.019,
+            ln_age_ln_treated_systolic_bp: 0.0,
+            ln_untreated_systolic_bp: 1.957,
+            ln_age_ln_untreated_systolic_bp: 0.0,
+            smoker: 7.574,
+            ln_age_smoker: -1.665,
+            diabetes: 0.661,
+            baseline_survival: 0.9665,
+            mean_sum: -29.18,
+        }
+    }
+
+    pub(crate) fn african_american_female() -> PooledCohortCoefficients {
+        PooledCohortCoefficients {
+            ln_age: 17.114,
+            ln_age_squared: 0.0,
+            ln_total_cholesterol: 0.94,
+            ln_age_ln_total_cholesterol: 0.0,
+            ln_hdl_cholesterol: -18.92,
+            ln_age_ln_hdl_cholesterol: 4.475,
+            ln_treated_systolic_bp: 29.291,
+            ln_age_ln_treated_systolic_bp: -6.432,
+            ln_untreated_systolic_bp: 27.82,
+            ln_age_ln_untreated_systolic_bp: -6.087,
+            smoker: 0.691,
+            ln_age_smoker: 0.0,
+            diabetes: 0.874,
+            baseline_survival: 0.9533,
+            mean_sum: 86.61,
+        }
+    }
+
+    pub(crate) fn white_male() -> PooledCohortCoefficients {
+        PooledCohortCoefficients {
+            ln_age: 12.344,
+            ln_age_squared: 0.0,
+            ln_total_cholesterol: 11.853,
+            ln_age_ln_total_cholesterol: -2.664,
+            ln_hdl_cholesterol: -7.99,
+            ln_age_ln_hdl_cholesterol: 1.769,
+            ln_treated_systolic_bp: 1.797,
+            ln_age_ln_treated_systolic_bp: 0.0,
+            ln_untreated_systolic_bp: 1.764,
+            ln_age_ln_untreated_systolic_bp: 0.0,
+            smoker: 7.837,
+            ln_age_smoker: -1.795,
+            diabetes: 0.658,
+            baseline_survival: 0.9144,
+            mean_sum: 61.18,
+        }
+    }
+
+    pub(crate) fn african_american_male() -> PooledCohortCoefficients {
+        PooledCohortCoefficients {
+            ln_age: 2.469,
+            ln_age_squared: 0.0,
+            ln_total_cholesterol: 0.302,
+            ln_age_ln_total_cholesterol: 0.0,
+            ln_hdl_cholesterol: -0.307,
+            ln_age_ln_hdl_cholesterol: 0.0,
+            ln_treated_systolic_bp: 1.916,
+            ln_age_ln_treated_systolic_bp: 0.0,
+            ln_untreated_systolic_bp: 1.809,
+            ln_age_ln_untreated_systolic_bp: 0.0,
+            smoker: 0.549,
+            ln_age_smoker: 0.0,
+            diabetes: 0.645,
+            baseline_survival: 0.8954,
+            mean_sum: 19.54,
+        }
+    }
+}
+
+/// Sex-specific coefficients of SCORE2 (SCORE2 working group, Eur Heart J
+/// 2021) and SCORE2-OP (SCORE2-OP working group, Eur Heart J 2021). Inputs are
+/// centred and scaled before the coefficients are applied; cholesterol is in
+/// mmol/L.
+pub(crate) struct Score2Coefficients {
+    pub(crate) age_centre: f64,
+    pub(crate) age_scale: f64,
+    pub(crate) systolic_bp_centre: f64,
+    pub(crate) systolic_bp_scale: f64,
+    pub(crate) total_cholesterol_centre: f64,
+    pub(crate) hdl_cholesterol_centre: f64,
+    pub(crate) hdl_cholesterol_scale: f64,
+    pub(crate) age: f64,
+    pub(crate) smoker: f64,
+    pub(crate) systolic_bp: f64,
+    pub(crate) diabetes: f64,
+    pub(crate) total_cholesterol: f64,
+    pub(crate) hdl_cholesterol: f64,
+    pub(crate) age_smoker: f64,
+    pub(crate) age_systolic_bp: f64,
+    pub(crate) age_total_cholesterol: f64,
+    pub(crate) age_hdl_cholesterol: f64,
+    pub(crate) age_diabetes: f64,
+    pub(crate) baseline_survival: f64,
+    pub(crate) mean_linear_predictor: f64,
+    /// Recalibration scales (scale1, scale2) for the low, moderate, high and
+    /// very high risk regions, in that order.
+    pub(crate) region_scales: [(f64, f64); 4],
+}
+
+impl Score2Coefficients {
+    pub(crate) fn female() -> Score2Coefficients {
+        Score2Coefficients {
+            age_centre: 60.0,
+            age_scale: 5.0,
+            systolic_bp_centre: 120.0,
+            systolic_bp_scale: 20.0,
+            total_cholesterol_centre: 6.0,
+            hdl_cholesterol_centre: 1.3,
+            hdl_cholesterol_scale: 0.5,
+            age: 0.4648,
+            smoker: 0.7744,
+            systolic_bp: 0.3131,
+            diabetes: 0.8096,
+            total_cholesterol: 0.1002,
+            hdl_cholesterol: -0.2606,
+            age_smoker: -0.1088,
+            age_systolic_bp: -0.0277,
+            age_total_cholesterol: -0.0226,
+            age_hdl_cholesterol: 0.0613,
+            age_diabetes: -0.1272,
+            baseline_survival: 0.9776,
+            mean_linear_predictor: 0.0,
+            region_scales: [
+                (-0.7380, 0.7019),
+                (-0.3143, 0.7701),
+                (0.5710, 0.9369),
+                (0.9412, 0.8329),
+            ],
+        }
+    }
+
+    pub(crate) fn male() -> Score2Coefficients {
+        Score2Coefficients {
+            age_centre: 60.0,
+            age_scale: 5.0,
+            systolic_bp_centre: 120.0,
+            systolic_bp_scale: 20.0,
+            total_cholesterol_centre: 6.0,
+            hdl_cholesterol_centre: 1.3,
+            hdl_cholesterol_scale: 0.5,
+            age: 0.3742,
+            smoker: 0.6012,
+            systolic_bp: 0.2777,
+            diabetes: 0.6457,
+            total_cholesterol: 0.1458,
+            hdl_cholesterol: -0.2698,
+            age_smoker: -0.0755,
+            age_systolic_bp: -0.0255,
+            age_total_cholesterol: -0.0281,
+            age_hdl_cholesterol: 0.0426,
+            age_diabetes: -0.0983,
+            baseline_survival: 0.9605,
+            mean_linear_predictor: 0.0,
+            region_scales: [
+                (-0.5699, 0.7476),
+                (-0.1565, 0.8009),
+                (0.3207, 0.9360),
+                (0.5836, 0.8294),
+            ],
+        }
+    }
+
+    pub(crate) fn older_persons_female() -> Score2Coefficients {
+        Score2Coefficients {
+            age_centre: 73.0,
+            age_scale: 1.0,
+            systolic_bp_centre: 150.0,
+            systolic_bp_scale: 1.0,
+            total_cholesterol_centre: 6.0,
+            hdl_cholesterol_centre: 1.4,
+            hdl_cholesterol_scale: 1.0,
+            age: 0.0789,
+            smoker: 0.4921,
+            systolic_bp: 0.0102,
+            diabetes: 0.6010,
+            total_cholesterol: 0.0605,
+            hdl_cholesterol: -0.3040,
+            age_smoker: -0.0255,
+            age_systolic_bp: -0.0004,
+            age_total_cholesterol: -0.0009,
+            age_hdl_cholesterol: 0.0154,
+            age_diabetes: -0.0107,
+            baseline_survival: 0.8082,
+            mean_linear_predictor: 0.2290,
+            region_scales: [(-0.52, 1.01), (-0.10, 1.10), (0.38, 1.09), (0.38, 0.69)],
+        }
+    }
+
+    pub(crate) fn older_persons_male() -> Score2Coefficients {
+        Score2Coefficients {
+            age_centre: 73.0,
+            age_scale: 1.0,
+            systolic_bp_centre: 150.0,
+            systolic_bp_scale: 1.0,
+            total_cholesterol_centre: 6.0,
+            hdl_cholesterol_centre: 1.4,
+            hdl_cholesterol_scale: 1.0,
+            age: 0.0634,
+            smoker: 0.3524,
+            systolic_bp: 0.0094,
+            diabetes: 0.4245,
+            total_cholesterol: 0.0850,
+            hdl_cholesterol: -0.3564,
+            age_smoker: -0.0247,
+            age_systolic_bp: -0.0005,
+            age_total_cholesterol: 0.0073,
+            age_hdl_cholesterol: 0.0091,
+            age_diabetes: -0.0174,
+            baseline_survival: 0.7576,
+            mean_linear_predictor: 0.0929,
+            region_scales: [(-0.34, 1.19), (0.01, 1.25), (0.08, 1.15), (0.05, 0.70)],
+        }
+    }
 }
@@ -0,0 +1,359 @@
+use crate::cohort_summary::{export_csv, export_json, summarize_arrays, ExportRow, SummaryRow};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+// Positions of the statistics in `SummaryRow::values`.
+const N: usize = 0;
+const INVALID: usize = 1;
+const MEAN: usize = 2;
+const RISK_STATISTICS: std::ops::Range<usize> = 2..7;
+const PROPORTIONS: std::ops::Range<usize> = 7..11;
+
+/// Rules for publishing a cohort summary outside the secure environment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisclosurePolicy {
+    /// Smallest number of patients a published cell may describe.
+    pub min_cell_size: usize,
+    /// Counts are rounded to the nearest multiple of this.
+    pub count_base: usize,
+    /// Decimal places of risk statistics (percentages). Proportions keep two
+    /// more, the same precision as a percentage. `None` leaves them as is.
+    pub decimals: Option<u32>,
+    /// Privacy budget of the Laplace noise added to each count, mean and
+    /// proportion, or `None` for no noise. Each value is perturbed with scale
+    /// sensitivity / epsilon, where the sensitivity is 1 for counts, 100 / n
+    /// for means and 1 / n for proportions of n valid predictions. Quantiles
+    /// are only rounded. The budget applies per value, not to the table.
+    pub epsilon: Option<f64>,
+    pub seed: u64,
+}
+
+impl Default for DisclosurePolicy {
+    fn default() -> DisclosurePolicy {
+        DisclosurePolicy {
+            min_cell_size: 11,
+            count_base: 1,
+            decimals: None,
+            epsilon: None,
+            seed: 0,
+        }
+    }
+}
+
+impl DisclosurePolicy {
+    fn validate(&self) -> Result<(), String> {
+        if self.count_base == 0 {
+            return Err("Count base must be at least 1.".to_string());
+        }
+        if let Some(epsilon) = self.epsilon {
+            if !(epsilon.is_finite() && epsilon > 0.0) {
+                return Err(format!("Epsilon must be positive, not {}.", epsilon));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a row was withheld.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Suppression {
+    /// The stratum, or its valid predictions, are fewer than the minimum cell
+    /// size.
+    Primary,
+    /// Withheld so that a primary suppression cannot be recovered by
+    /// subtracting the other levels of its stratifier from the overall row.
+    Complementary,
+}
+
+impl Suppression {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Suppression::Primary => "primary",
+            Suppression::Complementary => "complementary",
+        }
+    }
+}
+
+/// A cohort summary row that is safe to publish: withheld statistics are
+/// NaN.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProtectedRow {
+    pub model: String,
+    pub stratifier: &'static str,
+    pub level: String,
+    pub suppression: Option<Suppression>,
+    /// The statistics in the order of `SUMMARY_COLUMNS`.
+    pub values: [f64; 11],
+}
+
+fn laplace(rng: &mut ChaCha8Rng, scale: f64) -> f64 {
+    let u: f64 = rng.gen_range(-0.5..0.5);
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
+
+fn round_to(value: f64, decimals: u32) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).round() / factor
+}
+
+/// Rows to withhold entirely: primary suppression of small strata, then
+/// complementary suppression of the smallest other level wherever a
+/// stratifier has exactly one level withheld.
+fn row_suppression(rows: &[SummaryRow], min_cell_size: usize) -> Vec<Option<Suppression>> {
+    let mut suppression: Vec<Option<Suppression>> = rows
+        .iter()
+        .map(|row| {
+            (row.n < min_cell_size || row.n - row.invalid < min_cell_size)
+                .then_some(Suppression::Primary)
+        })
+        .collect();
+    let mut start = 0;
+    for group in rows.chunk_by(|a, b| (&a.model, a.stratifier) == (&b.model, b.stratifier)) {
+        let end = start + group.len();
+        let withheld = suppression[start..end].iter().flatten().count();
+        if group[0].stratifier != "overall" && withheld == 1 {
+            let complement = (start..end)
+                .filter(|i| suppression[*i].is_none())
+                .min_by_key(|i| rows[*i].n);
+            if let Some(i) = complement {
+                suppression[i] = Some(Suppression::Complementary);
+            }
+        }
+        start = end;
+    }
+    suppression
+}
+
+/// The cells of a row that may be withheld on their own, in the order of
+/// `SUMMARY_COLUMNS`: the invalid count, then the category proportions.
+const CELLS: [usize; 5] = [
+    INVALID,
+    PROPORTIONS.start,
+    PROPORTIONS.start + 1,
+    PROPORTIONS.start + 2,
+    PROPORTIONS.start + 3,
+];
+
+/// Patients in each of `CELLS`.
+fn cell_counts(row: &SummaryRow) -> [usize; 5] {
+    let valid = (row.n - row.invalid) as f64;
+    let mut counts = [row.invalid; 5];
+    for (count, p) in counts[1..].iter_mut().zip(row.category_proportions) {
+        *count = if p.is_nan() {
+            0
+        } else {
+            (p * valid).round() as usize
+        };
+    }
+    counts
+}
+
+/// Cells to withhold within the published rows: an invalid count below the
+/// minimum and category proportions that stand for fewer patients than the
+/// minimum. Complementary cells are added until no withheld cell is alone,
+/// either among the categories of its row, which sum to one, or among the
+/// levels of its stratifier, which sum to the overall row. The smallest
+/// non-empty published cell is the complement each time.
+fn cell_suppression(
+    rows: &[SummaryRow],
+    suppression: &[Option<Suppression>],
+    min_cell_size: usize,
+) -> Vec<[bool; 5]> {
+    let counts: Vec<[usize; 5]> = rows.iter().map(cell_counts).collect();
+    let mut withheld: Vec<[bool; 5]> = counts
+        .iter()
+        .zip(suppression)
+        .map(|(counts, suppression)| {
+            counts.map(|count| suppression.is_some() || (count > 0 && count < min_cell_size))
+        })
+        .collect();
+    let complement = |candidates: &mut dyn Iterator<Item = (usize, usize)>| {
+        candidates
+            .filter(|(i, k)| suppression[*i].is_none() && counts[*i][*k] > 0)
+            .min_by_key(|(i, k)| counts[*i][*k])
+    };
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..rows.len() {
+            let categories: Vec<usize> = (1..5).filter(|k| withheld[i][*k]).collect();
+            if suppression[i].is_none() && categories.len() == 1 {
+                let mut others = (1..5).filter(|k| !withheld[i][*k]).map(|k| (i, k));
+                if let Some((i, k)) = complement(&mut others) {
+                    withheld[i][k] = true;
+                    changed = true;
+                }
+            }
+        }
+        let mut start = 0;
+        for group in rows.chunk_by(|a, b| (&a.model, a.stratifier) == (&b.model, b.stratifier)) {
+            let end = start + group.len();
+            for k in 0..5 {
+                let levels = (start..end).filter(|i| withheld[*i][k]).count();
+                if group[0].stratifier != "overall" && levels == 1 {
+                    let mut others = (start..end).filter(|i| !withheld[*i][k]).map(|i| (i, k));
+                    if let Some((i, k)) = complement(&mut others) {
+                        withheld[i][k] = true;
+                        changed = true;
+                    }
+                }
+            }
+            start = end;
+        }
+    }
+    withheld
+}
+
+/// Applies `policy` to cohort summary rows as `summarize_cohort` returns
+/// them. Suppression decisions use the exact counts; noise and then
+/// rounding are applied to what is published.
+pub fn protect_summary(
+    rows: &[SummaryRow],
+    policy: &DisclosurePolicy,
+) -> Result<Vec<ProtectedRow>, String> {
+    policy.validate()?;
+    let mut rng = ChaCha8Rng::seed_from_u64(policy.seed);
+    let suppression = row_suppression(rows, policy.min_cell_size);
+    let cells = cell_suppression(rows, &suppression, policy.min_cell_size);
+
+    Ok(rows
+        .iter()
+        .zip(suppression)
+        .zip(cells)
+        .map(|((row, suppression), cells)| {
+            let mut values = row.values();
+            if suppression.is_some() {
+                values = [f64::NAN; 11];
+            } else {
+                for (i, withheld) in CELLS.into_iter().zip(cells) {
+                    if withheld {
+                        values[i] = f64::NAN;
+                    }
+                }
+                if let Some(epsilon) = policy.epsilon {
+                    let valid = (row.n - row.invalid) as f64;
+                    for (i, value) in values.iter_mut().enumerate() {
+                        let sensitivity = match i {
+                            N | INVALID => 1.0,
+                            MEAN => 100.0 / valid,
+                            i if PROPORTIONS.contains(&i) => 1.0 / valid,
+                            _ => continue,
+                        };
+                        if !value.is_nan() {
+                            *value += laplace(&mut rng, sensitivity / epsilon);
+                        }
+                    }
+                    for i in PROPORTIONS {
+                        values[i] = values[i].clamp(0.0, 1.0);
+                    }
+                    values[MEAN] = values[MEAN].clamp(0.0, 100.0);
+                }
+                let base = policy.count_base as f64;
+                for i in [N, INVALID] {
+                    let rounded = (values[i] / base).round() * base;
+                    if !rounded.is_nan() {
+                        values[i] = if rounded > 0.0 { rounded } else { 0.0 };
+                    }
+                }
+                if let Some(decimals) = policy.decimals {
+                    for i in RISK_STATISTICS {
+                        values[i] = round_to(values[i], decimals);
+                    }
+                    for i in PROPORTIONS {
+                        values[i] = round_to(values[i], decimals + 2);
+                    }
+                }
+            }
+            ProtectedRow {
+                model: row.model.clone(),
+                stratifier: row.stratifier,
+                level: row.level.clone(),
+                suppression,
+                values,
+            }
+        })
+        .collect())
+}
+
+const PROTECTED_LABELS: [&str; 4] = ["model", "stratifier", "level", "suppression"];
+
+fn export_rows(rows: &[ProtectedRow]) -> Vec<ExportRow<'_>> {
+    rows.iter()
+        .map(|row| {
+            let suppression = row.suppression.map_or("", |s| s.label());
+            (
+                vec![
+                    row.model.as_str(),
+                    row.stratifier,
+                    row.level.as_str(),
+                    suppression,
+                ],
+                row.values,
+            )
+        })
+        .collect()
+}
+
+/// As `summary_csv`, with a `suppression` column after the level.
+pub fn protected_csv(rows: &[ProtectedRow]) -> String {
+    export_csv(&PROTECTED_LABELS, &export_rows(rows))
+}
+
+/// As `summary_json`, with a `suppression` key after the level.
+pub fn protected_json(rows: &[ProtectedRow]) -> String {
+    export_json(&PROTECTED_LABELS, &export_rows(rows))
+}
+
+/// (models, stratifiers, levels, suppression labels, (rows, columns) statistics).
+type ProtectedSummary = (
+    Vec<String>,
+    Vec<&'static str>,
+    Vec<String>,
+    Vec<&'static str>,
+    PyObject,
+);
+
+/// As `cohort_summary_rust`, with `policy` applied.
+#[pyfunction]
+pub fn protected_cohort_summary_rust(
+    py: Python,
+    data: PyReadonlyArrayDyn<f64>,
+    models: Vec<String>,
+    predictions: PyReadonlyArrayDyn<f64>,
+    min_cell_size: usize,
+    count_base: usize,
+    seed: u64,
+    decimals: Option<u32>,
+    epsilon: Option<f64>,
+) -> PyResult<ProtectedSummary> {
+    let rows = summarize_arrays(&data, &models, &predictions)?;
+    let policy = DisclosurePolicy {
+        min_cell_size,
+        count_base,
+        decimals,
+        epsilon,
+        seed,
+    };
+
+    match protect_summary(&rows, &policy) {
+        Ok(rows) => {
+            let values: Vec<Vec<f64>> = rows.iter().map(|row| row.values.to_vec()).collect();
+            let values = PyArray::from_vec2(py, &values)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?
+                .to_object(py);
+            Ok((
+                rows.iter().map(|row| row.model.clone()).collect(),
+                rows.iter().map(|row| row.stratifier).collect(),
+                rows.iter().map(|row| row.level.clone()).collect(),
+                rows.iter()
+                    .map(|row| row.suppression.map_or("", |s| s.label()))
+                    .collect(),
+                values,
+            ))
+        }
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
@@ -0,0 +1,579 @@
+use crate::model::{find_model, risk_category, Patient, RiskModel, Sex, PATIENT_FIELDS};
+use crate::score2::CHOLESTEROL_MMOL_PER_MG;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+
+const LOINC: &str = "http://loinc.org";
+const SNOMED: &str = "http://snomed.info/sct";
+const ICD_10: [&str; 2] = [
+    "http://hl7.org/fhir/sid/icd-10-cm",
+    "http://hl7.org/fhir/sid/icd-10",
+];
+const RXNORM: &str = "http://www.nlm.nih.gov/research/umls/rxnorm";
+const ATC: &str = "http://www.whocc.no/atc";
+
+pub(crate) const TOTAL_CHOLESTEROL_LOINC: [&str; 1] = ["2093-3"];
+pub(crate) const HDL_CHOLESTEROL_LOINC: [&str; 1] = ["2085-9"];
+/// Systolic BP, on its own or as a component of a blood pressure panel.
+pub(crate) const SYSTOLIC_BP_LOINC: [&str; 1] = ["8480-6"];
+pub(crate) const BMI_LOINC: [&str; 1] = ["39156-5"];
+/// CKD-EPI 2021 and 2009, and MDRD, eGFR.
+pub(crate) const EGFR_LOINC: [&str; 7] = [
+    "98979-8", "62238-1", "88293-6", "88294-4", "33914-3", "48642-3", "48643-1",
+];
+pub(crate) const SMOKING_STATUS_LOINC: [&str; 1] = ["72166-2"];
+/// Smoking status values that mean a current smoker.
+pub(crate) const CURRENT_SMOKER_SNOMED: [&str; 6] = [
+    "449868002",
+    "428041000124106",
+    "77176002",
+    "65568007",
+    "428071000124103",
+    "428061000124105",
+];
+const DIABETES_SNOMED: [&str; 4] = ["73211009", "44054006", "46635009", "199230006"];
+/// ICD-10 categories E08 to E13.
+const DIABETES_ICD_10: [&str; 6] = ["E08", "E09", "E10", "E11", "E12", "E13"];
+/// ATC groups of antihypertensives, diuretics, beta blockers, calcium channel
+/// blockers and renin-angiotensin agents.
+const ANTIHYPERTENSIVE_ATC: [&str; 5] = ["C02", "C03", "C07", "C08", "C09"];
+/// RxNorm ingredients (TTY IN) only. Statements usually carry a clinical or
+/// branded drug (SCD or SBD) code instead, which matches only through the
+/// ingredients of a referenced Medication or an ATC coding alongside it.
+const ANTIHYPERTENSIVE_RXNORM: [&str; 16] = [
+    "29046", "17767", "52175", "5487", "6918", "2409", "69749", "3827", "35296", "20352", "321064",
+    "83818", "1202", "7417", "3443", "1998",
+];
+/// Statins, alone or in combination.
+const STATIN_ATC: [&str; 3] = ["C10AA", "C10BA", "C10BX"];
+/// RxNorm statin ingredients, matched as `ANTIHYPERTENSIVE_RXNORM` is.
+const STATIN_RXNORM: [&str; 7] = [
+    "83367", "36567", "301542", "42463", "6472", "861634", "41127",
+];
+
+/// The calculator inputs found in a FHIR R4 Bundle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FhirInputs {
+    pub patient: Patient,
+    /// Reference to the Patient resource, e.g. `Patient/123`.
+    pub subject: String,
+    /// Reference to the resource that supplied each of `PATIENT_FIELDS`, or
+    /// `None` for a flag that no resource set, which defaults to false.
+    pub provenance: [Option<String>; 11],
+}
+
+/// A resource of the bundle with the reference other resources use for it.
+struct Entry<'a> {
+    reference: String,
+    full_url: Option<&'a str>,
+    resource: &'a Value,
+}
+
+fn entries(bundle: &Value) -> Result<Vec<Entry<'_>>, String> {
+    if bundle["resourceType"] != "Bundle" {
+        return Err("Expected a FHIR Bundle resource.".to_string());
+    }
+    Ok(bundle["entry"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let resource = &entry["resource"];
+            let full_url = entry["fullUrl"].as_str();
+            let reference = match (resource["resourceType"].as_str(), resource["id"].as_str()) {
+                (Some(kind), Some(id)) => format!("{}/{}", kind, id),
+                _ => full_url?.to_string(),
+            };
+            Some(Entry {
+                reference,
+                full_url,
+                resource,
+            })
+        })
+        .collect())
+}
+
+impl Entry<'_> {
+    fn is(&self, kind: &str) -> bool {
+        self.resource["resourceType"] == kind
+    }
+
+    fn refers_to(&self, reference: &str) -> bool {
+        reference == self.reference || Some(reference) == self.full_url
+    }
+}
+
+/// Whether a CodeableConcept has a coding from one of `systems` whose code is in
+/// `codes`, or starts with one of them if `prefix` is set.
+fn has_code(concept: &Value, systems: &[&str], codes: &[&str], prefix: bool) -> bool {
+    concept["coding"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .any(|coding| {
+            let system = coding["system"].as_str().unwrap_or_default();
+            let code = coding["code"].as_str().unwrap_or_default();
+            systems.contains(&system)
+                && codes.iter().any(|c| {
+                    if prefix {
+                        code.starts_with(c)
+                    } else {
+                        code == *c
+                    }
+                })
+        })
+}
+
+/// When an Observation was made, as an ISO 8601 string.
+fn effective(observation: &Value) -> &str {
+    ["effectiveDateTime", "effectiveInstant", "issued"]
+        .iter()
+        .find_map(|key| observation[key].as_str())
+        .or_else(|| observation["effectivePeriod"]["end"].as_str())
+        .unwrap_or_default()
+}
+
+pub(crate) fn date(value: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = value.get(..10)?.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((year, month, day))
+}
+
+/// When a Condition or MedicationStatement began, or failing that was
+/// recorded, as an ISO 8601 string. Empty if it is undated.
+fn asserted(resource: &Value) -> &str {
+    ["onsetDateTime", "effectiveDateTime"]
+        .iter()
+        .find_map(|key| resource[key].as_str())
+        .or_else(|| resource["onsetPeriod"]["start"].as_str())
+        .or_else(|| resource["effectivePeriod"]["start"].as_str())
+        .or_else(|| resource["recordedDate"].as_str())
+        .or_else(|| resource["dateAsserted"].as_str())
+        .unwrap_or_default()
+}
+
+/// Completed years between two dates.
+pub(crate) fn age_on(birth: (i32, u32, u32), on: (i32, u32, u32)) -> f64 {
+    let years = on.0 - birth.0 - ((on.1, on.2) < (birth.1, birth.2)) as i32;
+    years as f64
+}
+
+/// Resources of type `kind` about the subject, other than those entered in
+/// error or cancelled.
+fn resources<'a>(
+    entries: &'a [Entry],
+    kind: &'a str,
+    subject: &'a Entry,
+) -> impl Iterator<Item = &'a Entry<'a>> {
+    entries.iter().filter(move |entry| {
+        entry.is(kind)
+            && !matches!(
+                entry.resource["status"].as_str(),
+                Some("entered-in-error" | "cancelled")
+            )
+            && entry.resource["subject"]["reference"]
+                .as_str()
+                .is_none_or(|reference| subject.refers_to(reference))
+    })
+}
+
+/// The value and reference of the latest final, amended or corrected
+/// Observation, up to `as_of`, with one of `codes` as its code or the code of
+/// one of its components.
+fn latest_observation<'a>(
+    entries: &'a [Entry],
+    subject: &'a Entry,
+    codes: &[&str],
+    as_of: &str,
+) -> Option<(&'a Value, &'a Entry<'a>)> {
+    resources(entries, "Observation", subject)
+        .filter(|entry| {
+            matches!(
+                entry.resource["status"].as_str(),
+                Some("final" | "amended" | "corrected")
+            )
+        })
+        .filter(|entry| effective(entry.resource).get(..10).unwrap_or_default() <= as_of)
+        .filter_map(|entry| {
+            let observation = entry.resource;
+            if has_code(&observation["code"], &[LOINC], codes, false) {
+                return Some((observation, entry));
+            }
+            observation["component"]
+                .as_array()?
+                .iter()
+                .find(|component| has_code(&component["code"], &[LOINC], codes, false))
+                .map(|component| (component, entry))
+        })
+        .max_by(|(_, a), (_, b)| effective(a.resource).cmp(effective(b.resource)))
+}
+
+/// A measurement in the calculator's units, with the Observation it came
+/// from.
+fn measurement(
+    entries: &[Entry],
+    subject: &Entry,
+    field: &str,
+    codes: &[&str],
+    as_of: &str,
+) -> Result<(f64, String), String> {
+    let (value, entry) = latest_observation(entries, subject, codes, as_of)
+        .ok_or_else(|| format!("No Observation for {} on or before {}.", field, as_of))?;
+    let quantity = &value["valueQuantity"];
+    let number = quantity["value"]
+        .as_f64()
+        .ok_or_else(|| format!("{} has no valueQuantity for {}.", entry.reference, field))?;
+    let unit = quantity["code"]
+        .as_str()
+        .or_else(|| quantity["unit"].as_str())
+        .unwrap_or_default();
+    let number = match (field, unit) {
+        ("total_cholesterol" | "hdl_cholesterol", "mmol/L") => number / CHOLESTEROL_MMOL_PER_MG,
+        ("total_cholesterol" | "hdl_cholesterol", "mg/dL")
+        | ("systolic_bp", "mm[Hg]")
+        | ("bmi", "kg/m2")
+        | ("egfr", "mL/min/{1.73_m2}") => number,
+        _ => {
+            let expected = match field {
+                "systolic_bp" => "mm[Hg]",
+                "bmi" => "kg/m2",
+                "egfr" => "mL/min/{1.73_m2}",
+                _ => "mg/dL or mmol/L",
+            };
+            return Err(format!(
+                "{} must be in {}, not '{}'.",
+                entry.reference, expected, unit
+            ));
+        }
+    };
+    Ok((number, entry.reference.clone()))
+}
+
+/// The first resource of type `kind`, undated or dated on or before `as_of`,
+/// whose code matches, or `None` if the flag should default to false.
+fn flag<'a>(
+    entries: &'a [Entry],
+    subject: &'a Entry,
+    kind: &'a str,
+    as_of: &str,
+    matches: impl Fn(&Value) -> bool,
+) -> Option<String> {
+    resources(entries, kind, subject)
+        .filter(|entry| asserted(entry.resource).get(..10).unwrap_or_default() <= as_of)
+        .find(|entry| matches(entry.resource))
+        .map(|entry| entry.reference.clone())
+}
+
+fn is_diabetes(condition: &Value) -> bool {
+    let clinical = &condition["clinicalStatus"];
+    let verification = &condition["verificationStatus"];
+    let inactive = ["inactive", "resolved", "remission"];
+    let unconfirmed = ["refuted", "entered-in-error"];
+    let status = |concept: &Value, codes: &[&str]| {
+        concept["coding"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .any(|coding| codes.contains(&coding["code"].as_str().unwrap_or_default()))
+    };
+    let code = &condition["code"];
+    !status(clinical, &inactive)
+        && !status(verification, &unconfirmed)
+        && (has_code(code, &[SNOMED], &DIABETES_SNOMED, false)
+            || has_code(code, &ICD_10, &DIABETES_ICD_10, true))
+}
+
+/// The codes of a MedicationStatement's medication: its code and, for a
+/// Medication referenced in the bundle, the code of each ingredient.
+fn medication<'a>(entries: &'a [Entry], statement: &'a Value) -> Vec<&'a Value> {
+    match statement["medicationReference"]["reference"].as_str() {
+        Some(reference) => entries
+            .iter()
+            .find(|entry| entry.is("Medication") && entry.refers_to(reference))
+            .map(|entry| {
+                let ingredients = entry.resource["ingredient"]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|ingredient| &ingredient["itemCodeableConcept"]);
+                std::iter::once(&entry.resource["code"])
+                    .chain(ingredients)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        None => vec![&statement["medicationCodeableConcept"]],
+    }
+}
+
+fn taking(
+    entries: &[Entry],
+    subject: &Entry,
+    as_of: &str,
+    atc: &[&str],
+    rxnorm: &[&str],
+) -> Option<String> {
+    flag(
+        entries,
+        subject,
+        "MedicationStatement",
+        as_of,
+        |statement| {
+            matches!(statement["status"].as_str(), Some("active" | "intended"))
+                && medication(entries, statement).into_iter().any(|code| {
+                    has_code(code, &[ATC], atc, true) || has_code(code, &[RXNORM], rxnorm, false)
+                })
+        },
+    )
+}
+
+fn subject<'a>(entries: &'a [Entry]) -> Result<&'a Entry<'a>, String> {
+    let patients: Vec<&Entry> = entries.iter().filter(|entry| entry.is("Patient")).collect();
+    match patients.as_slice() {
+        [subject] => Ok(subject),
+        _ => Err(format!(
+            "Bundle must have one Patient, not {}.",
+            patients.len()
+        )),
+    }
+}
+
+/// Observation-based inputs, with the LOINC codes that supply them.
+const OBSERVED_INPUTS: [(&str, &[&str]); 6] = [
+    ("total_cholesterol", &TOTAL_CHOLESTEROL_LOINC),
+    ("hdl_cholesterol", &HDL_CHOLESTEROL_LOINC),
+    ("systolic_bp", &SYSTOLIC_BP_LOINC),
+    ("current_smoker", &SMOKING_STATUS_LOINC),
+    ("bmi", &BMI_LOINC),
+    ("egfr", &EGFR_LOINC),
+];
+
+/// The Observation-based inputs that a Bundle has no Observation for on or
+/// before `as_of`, so that `inputs_from_bundle` would fail.
+pub fn missing_inputs(bundle: &Value, as_of: &str) -> Result<Vec<&'static str>, String> {
+    let entries = entries(bundle)?;
+    let subject = subject(&entries)?;
+    Ok(OBSERVED_INPUTS
+        .iter()
+        .filter(|(_, codes)| latest_observation(&entries, subject, codes, as_of).is_none())
+        .map(|(field, _)| *field)
+        .collect())
+}
+
+/// Maps a FHIR R4 Bundle about one patient to the calculator inputs as of
+/// `as_of` (YYYY-MM-DD). Measurements are the latest final, amended or
+/// corrected Observation up to that date, and smoking is the latest smoking
+/// status. Diabetes is an active, unrefuted Condition, and treatment an active
+/// or intended MedicationStatement, either undated or dated up to `as_of`,
+/// whose medication has an ATC code or an RxNorm ingredient code of the
+/// class; without one the flag is false. Cholesterol is in mg/dL or mmol/L, and the other
+/// measurements must have the UCUM code of the calculator's units.
+pub fn inputs_from_bundle(bundle: &Value, as_of: &str) -> Result<FhirInputs, String> {
+    let on = date(as_of).ok_or_else(|| format!("Date must be YYYY-MM-DD, not '{}'.", as_of))?;
+    let entries = entries(bundle)?;
+    let subject = subject(&entries)?;
+    let sex = Sex::parse(subject.resource["gender"].as_str().unwrap_or_default())?;
+    let birth = subject.resource["birthDate"]
+        .as_str()
+        .and_then(date)
+        .ok_or("Patient needs a birthDate of the form YYYY-MM-DD.")?;
+
+    let (total_cholesterol, total_cholesterol_source) = measurement(
+        &entries,
+        subject,
+        "total_cholesterol",
+        &TOTAL_CHOLESTEROL_LOINC,
+        as_of,
+    )?;
+    let (hdl_cholesterol, hdl_cholesterol_source) = measurement(
+        &entries,
+        subject,
+        "hdl_cholesterol",
+        &HDL_CHOLESTEROL_LOINC,
+        as_of,
+    )?;
+    let (systolic_bp, systolic_bp_source) =
+        measurement(&entries, subject, "systolic_bp", &SYSTOLIC_BP_LOINC, as_of)?;
+    let (bmi, bmi_source) = measurement(&entries, subject, "bmi", &BMI_LOINC, as_of)?;
+    let (egfr, egfr_source) = measurement(&entries, subject, "egfr", &EGFR_LOINC, as_of)?;
+    let (smoking, smoking_entry) =
+        latest_observation(&entries, subject, &SMOKING_STATUS_LOINC, as_of)
+            .ok_or_else(|| format!("No smoking status Observation on or before {}.", as_of))?;
+    let current_smoker = has_code(
+        &smoking["valueCodeableConcept"],
+        &[SNOMED],
+        &CURRENT_SMOKER_SNOMED,
+        false,
+    );
+
+    let diabetes = flag(&entries, subject, "Condition", as_of, is_diabetes);
+    let htn_meds = taking(
+        &entries,
+        subject,
+        as_of,
+        &ANTIHYPERTENSIVE_ATC,
+        &ANTIHYPERTENSIVE_RXNORM,
+    );
+    let statin = taking(&entries, subject, as_of, &STATIN_ATC, &STATIN_RXNORM);
+
+    let patient = Patient {
+        sex,
+        age: age_on(birth, on),
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes: diabetes.is_some(),
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds: htn_meds.is_some(),
+        on_cholesterol_meds: statin.is_some(),
+    };
+    let reference = Some(subject.reference.clone());
+    Ok(FhirInputs {
+        patient,
+        subject: subject.reference.clone(),
+        provenance: [
+            reference.clone(),
+            reference,
+            Some(total_cholesterol_source),
+            Some(hdl_cholesterol_source),
+            Some(systolic_bp_source),
+            diabetes,
+            Some(smoking_entry.reference.clone()),
+            Some(bmi_source),
+            Some(egfr_source),
+            htn_meds,
+            statin,
+        ],
+    })
+}
+
+/// A FHIR RiskAssessment of `risk` (%) from `model`, with the resources that
+/// supplied its inputs as the basis.
+pub fn risk_assessment(
+    model: &dyn RiskModel,
+    inputs: &FhirInputs,
+    risk: f64,
+    as_of: &str,
+) -> Value {
+    let metadata = model.metadata();
+    let mut basis: Vec<&str> = Vec::new();
+    for (field, source) in PATIENT_FIELDS.iter().zip(&inputs.provenance) {
+        if model.required_inputs().contains(field) {
+            if let Some(source) = source {
+                if !basis.contains(&source.as_str()) {
+                    basis.push(source);
+                }
+            }
+        }
+    }
+    let mut prediction = json!({
+        "outcome": {"text": metadata.outcome},
+        "probabilityDecimal": risk / 100.0,
+        "whenRange": {
+            "high": {
+                "value": metadata.horizon_years,
+                "unit": "years",
+                "system": "http://unitsofmeasure.org",
+                "code": "a",
+            },
+        },
+    });
+    if let Some(category) = risk_category(model, risk) {
+        prediction["qualitativeRisk"] = json!({"text": category.label()});
+    }
+    json!({
+        "resourceType": "RiskAssessment",
+        "status": "final",
+        "subject": {"reference": inputs.subject},
+        "occurrenceDateTime": as_of,
+        "code": {"text": metadata.key},
+        "method": {"text": format!("{} ({})", metadata.name, metadata.version)},
+        "basis": basis
+            .iter()
+            .map(|reference| json!({"reference": reference}))
+            .collect::<Vec<_>>(),
+        "prediction": [prediction],
+    })
+}
+
+/// The result of running models on a FHIR Bundle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FhirAssessment {
+    pub inputs: FhirInputs,
+    /// A collection Bundle with a RiskAssessment for each model that could
+    /// score the patient.
+    pub bundle: Value,
+    /// (model key, message) for each model that could not.
+    pub errors: Vec<(String, String)>,
+}
+
+/// Reads a FHIR R4 Bundle as JSON and assesses it as `assess` does.
+pub fn assess_bundle(bundle: &str, models: &[&str], as_of: &str) -> Result<FhirAssessment, String> {
+    let bundle: Value =
+        serde_json::from_str(bundle).map_err(|e| format!("Invalid FHIR JSON: {}", e))?;
+    assess(&bundle, models, as_of)
+}
+
+/// Runs each of `models` on the inputs found in a FHIR R4 Bundle as of
+/// `as_of` and returns the predictions as RiskAssessments.
+pub fn assess(bundle: &Value, models: &[&str], as_of: &str) -> Result<FhirAssessment, String> {
+    let inputs = inputs_from_bundle(bundle, as_of)?;
+    let mut assessments = Vec::new();
+    let mut errors = Vec::new();
+    for key in models {
+        let model = find_model(key)?;
+        match model.predict(&inputs.patient) {
+            Ok(risk) => {
+                assessments.push(json!({
+                    "resource": risk_assessment(model.as_ref(), &inputs, risk, as_of)
+                }));
+            }
+            Err(e) => errors.push((key.to_string(), e)),
+        }
+    }
+    Ok(FhirAssessment {
+        inputs,
+        bundle: json!({
+            "resourceType": "Bundle",
+            "type": "collection",
+            "entry": assessments,
+        }),
+        errors,
+    })
+}
+
+/// (inputs in `PATIENT_FIELDS` order, their sources, RiskAssessment Bundle
+/// JSON, (model, error) pairs).
+type FhirSummary = (Vec<f64>, Vec<Option<String>>, String, Vec<(String, String)>);
+
+#[pyfunction]
+pub fn assess_fhir_bundle_rust(
+    bundle: String,
+    models: Vec<String>,
+    as_of: String,
+) -> PyResult<FhirSummary> {
+    let models: Vec<&str> = models.iter().map(String::as_str).collect();
+    match assess_bundle(&bundle, &models, &as_of) {
+        Ok(assessment) => {
+            let inputs = PATIENT_FIELDS
+                .iter()
+                .map(|field| assessment.inputs.patient.value(field).unwrap_or(f64::NAN))
+                .collect();
+            Ok((
+                inputs,
+                assessment.inputs.provenance.to_vec(),
+                assessment.bundle.to_string(),
+                assessment.errors,
+            ))
+        }
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
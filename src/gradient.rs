@@ -0,0 +1,132 @@
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+use crate::utils::{common_calculation_gradient, input_ranges, patients};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// Names of the inputs in a gradient, in the order of `input_ranges`.
+pub fn gradient_fields() -> [&'static str; 6] {
+    input_ranges(true).map(|range| range.field)
+}
+
+/// Sensitivity of a prediction to each continuous input, per unit of the
+/// input (years, mg/dL, mmHg, kg/m², mL/min/1.73m²), in the order of
+/// `gradient_fields`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RiskGradient {
+    pub risk: f64,
+    pub log_odds_gradient: [f64; 6],
+    /// Change in risk percentage points per unit of each input.
+    pub risk_gradient: [f64; 6],
+}
+
+/// Analytic derivatives of the log-odds and risk of `model` at the patient's
+/// inputs, including the spline and age interaction terms. The splines are
+/// piecewise linear, so at a knot (systolic BP 110, eGFR 60, BMI 30) the
+/// slope above the knot is returned.
+pub fn risk_gradient(model: PreventModel, patient: &Patient) -> Result<RiskGradient, String> {
+    let risk = model.predict(patient)?;
+    let log_odds_gradient = common_calculation_gradient(
+        &model.covariates(patient.sex),
+        patient.has_diabetes,
+        patient.current_smoker,
+        patient.on_htn_meds,
+        patient.on_cholesterol_meds,
+        patient.systolic_bp,
+        patient.total_cholesterol - patient.hdl_cholesterol,
+        patient.hdl_cholesterol,
+        (patient.age - 55.0) / 10.0,
+        patient.egfr,
+        patient.bmi,
+    );
+    // d(risk)/dx = 100 σ(1 - σ) d(log odds)/dx, with risk = 100 σ.
+    let slope = risk * (1.0 - risk / 100.0);
+    Ok(RiskGradient {
+        risk,
+        log_odds_gradient,
+        risk_gradient: log_odds_gradient.map(|derivative| slope * derivative),
+    })
+}
+
+/// (field, log-odds gradient, risk gradient) for each continuous input.
+type NamedGradient = Vec<(&'static str, f64, f64)>;
+
+/// Returns the risk and a (field, log-odds gradient, risk gradient) triple
+/// for each continuous input.
+#[pyfunction]
+pub fn risk_gradient_rust(
+    outcome: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> PyResult<(f64, NamedGradient)> {
+    let model = PreventModel::from_key(&outcome).map_err(PyValueError::new_err)?;
+    let patient = Patient {
+        sex: Sex::parse(&sex).map_err(PyValueError::new_err)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    };
+    match risk_gradient(model, &patient) {
+        Ok(gradient) => Ok((
+            gradient.risk,
+            gradient_fields()
+                .into_iter()
+                .zip(gradient.log_odds_gradient)
+                .zip(gradient.risk_gradient)
+                .map(|((field, log_odds), risk)| (field, log_odds, risk))
+                .collect(),
+        )),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+/// Returns the field names and two (n, 6) arrays of log-odds and risk
+/// gradients, with rows of NaN for invalid inputs.
+#[pyfunction]
+pub fn risk_gradient_rust_parallel_np(
+    py: Python,
+    outcome: String,
+    data: PyReadonlyArrayDyn<f64>,
+) -> PyResult<(Vec<&'static str>, PyObject, PyObject)> {
+    let model = PreventModel::from_key(&outcome).map_err(PyValueError::new_err)?;
+    let patients = patients(&data)?;
+
+    let results: Vec<([f64; 6], [f64; 6])> = patients
+        .par_iter()
+        .map(|patient| match risk_gradient(model, patient) {
+            Ok(gradient) => (gradient.log_odds_gradient, gradient.risk_gradient),
+            Err(_) => ([f64::NAN; 6], [f64::NAN; 6]), // Handle error by returning NaN
+        })
+        .collect();
+
+    let log_odds: Vec<Vec<f64>> = results.iter().map(|result| result.0.to_vec()).collect();
+    let risk: Vec<Vec<f64>> = results.iter().map(|result| result.1.to_vec()).collect();
+    let to_array = |rows: &[Vec<f64>]| -> PyResult<PyObject> {
+        Ok(PyArray::from_vec2(py, rows)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?
+            .to_object(py))
+    };
+
+    Ok((
+        gradient_fields().to_vec(),
+        to_array(&log_odds)?,
+        to_array(&risk)?,
+    ))
+}
@@ -0,0 +1,216 @@
+use crate::model::{find_model, models, predict_batch, risk_category, Patient, RiskModel, Sex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status, Streaming};
+
+/// Messages and service generated from `proto/pyprevent.proto`.
+pub mod proto {
+    tonic::include_proto!("pyprevent.v1");
+}
+
+use proto::risk_scoring_server::RiskScoring;
+use proto::{RiskResult, ScoreRequest, ScoreResponse};
+
+/// Most stream requests scored together in one parallel batch.
+pub const CHUNK_SIZE: usize = 4096;
+
+/// How long a stream waits for a chunk to fill before scoring what it has.
+const CHUNK_WAIT: Duration = Duration::from_millis(10);
+
+fn patient(patient: &proto::Patient) -> Result<Patient, String> {
+    let sex = match proto::Sex::try_from(patient.sex) {
+        Ok(proto::Sex::Female) => Sex::Female,
+        Ok(proto::Sex::Male) => Sex::Male,
+        _ => return Err("Sex must be FEMALE or MALE.".to_string()),
+    };
+    Ok(Patient {
+        sex,
+        age: patient.age,
+        total_cholesterol: patient.total_cholesterol,
+        hdl_cholesterol: patient.hdl_cholesterol,
+        systolic_bp: patient.systolic_bp,
+        has_diabetes: patient.has_diabetes,
+        current_smoker: patient.current_smoker,
+        bmi: patient.bmi,
+        egfr: patient.egfr,
+        on_htn_meds: patient.on_htn_meds,
+        on_cholesterol_meds: patient.on_cholesterol_meds,
+    })
+}
+
+/// The patient and positions in `all` of the models a request asks for.
+fn parse(
+    request: &ScoreRequest,
+    all: &[Box<dyn RiskModel>],
+) -> Result<(Patient, Vec<usize>), String> {
+    let patient = patient(request.patient.as_ref().ok_or("Request has no patient.")?)?;
+    let selected = if request.models.is_empty() {
+        (0..all.len()).collect()
+    } else {
+        request
+            .models
+            .iter()
+            .map(|key| {
+                let key = find_model(key)?.metadata().key;
+                Ok(all
+                    .iter()
+                    .position(|model| model.metadata().key == key)
+                    .expect("find_model only returns listed models"))
+            })
+            .collect::<Result<_, String>>()?
+    };
+    Ok((patient, selected))
+}
+
+/// Scores requests together: each model runs once over every patient that
+/// asked for it with the parallel `predict_batch`. Responses are in request
+/// order.
+pub fn score_requests(requests: &[ScoreRequest]) -> Vec<ScoreResponse> {
+    let all = models();
+    let parsed: Vec<Result<(Patient, Vec<usize>), String>> = requests
+        .iter()
+        .map(|request| parse(request, &all))
+        .collect();
+
+    // risks[i][j] is request i's risk from model j, if it asked for it.
+    let mut risks = vec![vec![None; all.len()]; requests.len()];
+    for (j, model) in all.iter().enumerate() {
+        let (indices, patients): (Vec<usize>, Vec<Patient>) = parsed
+            .iter()
+            .enumerate()
+            .filter_map(|(i, parsed)| match parsed {
+                Ok((patient, selected)) if selected.contains(&j) => Some((i, patient.clone())),
+                _ => None,
+            })
+            .unzip();
+        for (i, risk) in indices
+            .into_iter()
+            .zip(predict_batch(model.as_ref(), &patients))
+        {
+            risks[i][j] = Some(risk);
+        }
+    }
+
+    requests
+        .iter()
+        .zip(parsed)
+        .zip(risks)
+        .map(|((request, parsed), risks)| {
+            let (patient, selected) = match parsed {
+                Ok(parsed) => parsed,
+                Err(error) => {
+                    return ScoreResponse {
+                        id: request.id.clone(),
+                        results: Vec::new(),
+                        error,
+                    }
+                }
+            };
+            let results = selected
+                .into_iter()
+                .map(|j| {
+                    let model = all[j].as_ref();
+                    let metadata = model.metadata();
+                    let risk = risks[j].filter(|risk| !risk.is_nan());
+                    // `predict_batch` gives NaN for invalid patients; rerun one for its error.
+                    let error = match risk {
+                        Some(_) => String::new(),
+                        None => model.predict(&patient).err().unwrap_or_default(),
+                    };
+                    RiskResult {
+                        model: metadata.key.to_string(),
+                        version: metadata.version.to_string(),
+                        horizon_years: metadata.horizon_years,
+                        outcome: metadata.outcome.to_string(),
+                        risk,
+                        category: risk
+                            .and_then(|risk| risk_category(model, risk))
+                            .map_or("", |category| category.label())
+                            .to_string(),
+                        error,
+                    }
+                })
+                .collect();
+            ScoreResponse {
+                id: request.id.clone(),
+                results,
+                error: String::new(),
+            }
+        })
+        .collect()
+}
+
+/// The `RiskScoring` gRPC service.
+#[derive(Debug, Default)]
+pub struct ScoringService;
+
+#[tonic::async_trait]
+impl RiskScoring for ScoringService {
+    /// Scores one request on the blocking pool, so that a large request does
+    /// not hold up the async runtime.
+    async fn score(
+        &self,
+        request: Request<ScoreRequest>,
+    ) -> Result<Response<ScoreResponse>, Status> {
+        let request = request.into_inner();
+        let mut responses = tokio::task::spawn_blocking(move || score_requests(&[request]))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(responses.remove(0)))
+    }
+
+    type ScoreStreamStream = ReceiverStream<Result<ScoreResponse, Status>>;
+
+    /// Scores requests as they arrive, in chunks of up to `CHUNK_SIZE` on the
+    /// blocking pool. A failed inbound stream ends the response stream with
+    /// its status after the requests before it.
+    async fn score_stream(
+        &self,
+        request: Request<Streaming<ScoreRequest>>,
+    ) -> Result<Response<Self::ScoreStreamStream>, Status> {
+        let mut chunks = Box::pin(request.into_inner().chunks_timeout(CHUNK_SIZE, CHUNK_WAIT));
+        let (sender, receiver) = mpsc::channel(CHUNK_SIZE);
+        tokio::spawn(async move {
+            while let Some(chunk) = chunks.next().await {
+                let mut requests = Vec::with_capacity(chunk.len());
+                let mut failure = None;
+                for request in chunk {
+                    match request {
+                        Ok(request) => requests.push(request),
+                        Err(status) => {
+                            failure = Some(status);
+                            break;
+                        }
+                    }
+                }
+                let responses = tokio::task::spawn_blocking(move || score_requests(&requests))
+                    .await
+                    .map_err(|e| Status::internal(e.to_string()));
+                let sent = match responses {
+                    Ok(responses) => {
+                        let mut sent = true;
+                        for response in responses {
+                            sent = sent && sender.send(Ok(response)).await.is_ok();
+                        }
+                        sent
+                    }
+                    Err(status) => {
+                        failure = Some(status);
+                        true
+                    }
+                };
+                if let Some(status) = failure {
+                    let _ = sender.send(Err(status)).await;
+                    return;
+                }
+                if !sent {
+                    // The client went away.
+                    return;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
@@ -0,0 +1,177 @@
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+use crate::utils::{input_ranges, patients};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// Whether the heart age could be solved within the 10-year equation's age
+/// range, or was capped at one of its bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeartAgeBound {
+    Within,
+    /// The patient's risk is below that of an optimal-profile 30 year old, so
+    /// the heart age is reported as 30.
+    BelowMinimum,
+    /// The patient's risk is above that of an optimal-profile 79 year old, so
+    /// the heart age is reported as 79.
+    AboveMaximum,
+}
+
+impl HeartAgeBound {
+    pub fn code(&self) -> &'static str {
+        match self {
+            HeartAgeBound::Within => "within",
+            HeartAgeBound::BelowMinimum => "below_minimum",
+            HeartAgeBound::AboveMaximum => "above_maximum",
+        }
+    }
+
+    /// -1 when capped at the lower bound, 1 at the upper bound, 0 otherwise.
+    fn as_i32(&self) -> i32 {
+        match self {
+            HeartAgeBound::Within => 0,
+            HeartAgeBound::BelowMinimum => -1,
+            HeartAgeBound::AboveMaximum => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeartAge {
+    pub heart_age: f64,
+    /// Heart age minus chronological age; positive when the patient's risk is
+    /// higher than that of an optimal-profile person of the same age.
+    pub difference: f64,
+    pub bound: HeartAgeBound,
+}
+
+/// Heart ages are solved to within this many years.
+const HEART_AGE_TOLERANCE: f64 = 1e-6;
+
+/// The 10-year CVD risk of an optimal-profile patient of `sex` at `age`.
+fn optimal_risk(sex: Sex, age: f64) -> Result<f64, String> {
+    PreventModel::Cvd10Yr.predict(&Patient::optimal(sex, age))
+}
+
+/// Solves for the age at which a person of the same sex with optimal risk
+/// factors (`Patient::optimal`) has the same PREVENT 10-year CVD risk as
+/// `patient`. The optimal profile's risk increases with age, so the age is
+/// found by bisection over the 10-year equation's age range. Risks outside the
+/// range spanned by that profile are capped at the bounds and flagged.
+pub fn heart_age(patient: &Patient) -> Result<HeartAge, String> {
+    let risk = PreventModel::Cvd10Yr.predict(patient)?;
+    let age_range = input_ranges(true)[0];
+
+    let (heart_age, bound) = if risk <= optimal_risk(patient.sex, age_range.min)? {
+        (age_range.min, HeartAgeBound::BelowMinimum)
+    } else if risk >= optimal_risk(patient.sex, age_range.max)? {
+        (age_range.max, HeartAgeBound::AboveMaximum)
+    } else {
+        let (mut low, mut high) = (age_range.min, age_range.max);
+        while high - low > HEART_AGE_TOLERANCE {
+            let mid = (low + high) / 2.0;
+            if optimal_risk(patient.sex, mid)? < risk {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        ((low + high) / 2.0, HeartAgeBound::Within)
+    };
+
+    Ok(HeartAge {
+        heart_age,
+        difference: heart_age - patient.age,
+        bound,
+    })
+}
+
+pub fn calculate_heart_age(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> Result<HeartAge, String> {
+    heart_age(&Patient {
+        sex: Sex::parse(sex)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    })
+}
+
+#[pyfunction]
+pub fn calculate_heart_age_rust(
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> PyResult<(f64, f64, &'static str)> {
+    match calculate_heart_age(
+        &sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    ) {
+        Ok(result) => Ok((result.heart_age, result.difference, result.bound.code())),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+/// Returns arrays of heart ages, differences from chronological age (both NaN
+/// for invalid rows) and bound codes (-1 capped at 30, 1 capped at 79, 0
+/// otherwise).
+#[pyfunction]
+pub fn calculate_heart_age_rust_parallel_np(
+    py: Python,
+    data: PyReadonlyArrayDyn<f64>,
+) -> PyResult<(PyObject, PyObject, PyObject)> {
+    let patients = patients(&data)?;
+
+    let results: Vec<(f64, f64, i32)> = patients
+        .par_iter()
+        .map(|patient| match heart_age(patient) {
+            Ok(result) => (result.heart_age, result.difference, result.bound.as_i32()),
+            Err(_) => (f64::NAN, f64::NAN, 0), // Handle error by returning NaN
+        })
+        .collect();
+
+    let heart_ages: Vec<f64> = results.iter().map(|result| result.0).collect();
+    let differences: Vec<f64> = results.iter().map(|result| result.1).collect();
+    let bounds: Vec<i32> = results.iter().map(|result| result.2).collect();
+
+    Ok((
+        PyArray::from_vec(py, heart_ages).to_object(py),
+        PyArray::from_vec(py, differences).to_object(py),
+        PyArray::from_vec(py, bounds).to_object(py),
+    ))
+}
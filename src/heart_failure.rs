@@ -1,12 +1,15 @@
 use crate::covariates::Covariates;
-use crate::utils::{calculate_risk_rust_parallel_np, common_calculation, validate_input};
+use crate::model::PreventModel;
+use crate::utils::{
+    calculate_risk_rust_parallel_np, common_calculation, log_risk_from_log_odds,
+    risk_from_log_odds, validate_input,
+};
 use numpy::PyReadonlyArrayDyn;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use std::f64;
-use std::f64::consts::E;
 
-pub fn calculate_10_yr_heart_failure_risk(
+pub fn calculate_10_yr_heart_failure_log_odds(
     sex: &str,
     age: f64,
     total_cholesterol: f64,
@@ -29,6 +32,34 @@ pub fn calculate_10_yr_heart_failure_risk(
         true,
     )?;
 
+    calculate_10_yr_heart_failure_log_odds_unchecked(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_blood_pressure,
+        has_diabetes,
+        is_current_smoker,
+        body_mass_index,
+        estimated_glomerular_filtration_rate,
+        on_hypertension_meds,
+        _cholesterol_treated,
+    )
+}
+
+pub(crate) fn calculate_10_yr_heart_failure_log_odds_unchecked(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_blood_pressure: f64,
+    has_diabetes: bool,
+    is_current_smoker: bool,
+    body_mass_index: f64,
+    estimated_glomerular_filtration_rate: f64,
+    on_hypertension_meds: bool,
+    _cholesterol_treated: bool,
+) -> Result<f64, String> {
     let cholesterol_difference = total_cholesterol - hdl_cholesterol;
     let age_factor = (age - 55.0) / 10.0;
     let age_squared = age_factor.powi(2);
@@ -37,7 +68,7 @@ pub fn calculate_10_yr_heart_failure_risk(
     match sex_lower.as_str() {
         "female" => {
             let covariates = Covariates::female_10_yr_hf();
-            let calculation = common_calculation(
+            Ok(common_calculation(
                 &covariates,
                 has_diabetes,
                 is_current_smoker,
@@ -50,13 +81,11 @@ pub fn calculate_10_yr_heart_failure_risk(
                 age_squared,
                 estimated_glomerular_filtration_rate,
                 body_mass_index,
-            );
-            let risk_score = E.powf(calculation) / (1.0 + E.powf(calculation)) * 100.0;
-            Ok(risk_score)
+            ))
         }
         "male" => {
             let covariates = Covariates::male_10_yr_hf();
-            let calculation = common_calculation(
+            Ok(common_calculation(
                 &covariates,
                 has_diabetes,
                 is_current_smoker,
@@ -69,15 +98,42 @@ pub fn calculate_10_yr_heart_failure_risk(
                 age_squared,
                 estimated_glomerular_filtration_rate,
                 body_mass_index,
-            );
-            let risk_score = E.powf(calculation) / (1.0 + E.powf(calculation)) * 100.0;
-            Ok(risk_score)
+            ))
         }
         _ => Err("Sex must be either 'male' or 'female'.".to_string()),
     }
 }
 
-pub fn calculate_30_yr_heart_failure_risk(
+pub fn calculate_10_yr_heart_failure_risk(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_blood_pressure: f64,
+    has_diabetes: bool,
+    is_current_smoker: bool,
+    body_mass_index: f64,
+    estimated_glomerular_filtration_rate: f64,
+    on_hypertension_meds: bool,
+    _cholesterol_treated: bool,
+) -> Result<f64, String> {
+    calculate_10_yr_heart_failure_log_odds(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_blood_pressure,
+        has_diabetes,
+        is_current_smoker,
+        body_mass_index,
+        estimated_glomerular_filtration_rate,
+        on_hypertension_meds,
+        _cholesterol_treated,
+    )
+    .map(risk_from_log_odds)
+}
+
+pub fn calculate_30_yr_heart_failure_log_odds(
     sex: &str,
     age: f64,
     total_cholesterol: f64,
@@ -100,6 +156,34 @@ pub fn calculate_30_yr_heart_failure_risk(
         false,
     )?;
 
+    calculate_30_yr_heart_failure_log_odds_unchecked(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        diabetes,
+        smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        _cholesterol_treated,
+    )
+}
+
+pub(crate) fn calculate_30_yr_heart_failure_log_odds_unchecked(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    diabetes: bool,
+    smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    _cholesterol_treated: bool,
+) -> Result<f64, String> {
     let cholesterol_diff = total_cholesterol - hdl_cholesterol;
     let age_factor = (age - 55.0) / 10.0;
     let age_factor_squared = age_factor.powi(2);
@@ -107,7 +191,7 @@ pub fn calculate_30_yr_heart_failure_risk(
     match sex.to_lowercase().as_str() {
         "female" => {
             let covariates = Covariates::female_30_yr_hf();
-            let calculation = common_calculation(
+            Ok(common_calculation(
                 &covariates,
                 diabetes,
                 smoker,
@@ -120,13 +204,11 @@ pub fn calculate_30_yr_heart_failure_risk(
                 age_factor_squared,
                 egfr,
                 bmi,
-            );
-            let risk_score = E.powf(calculation) / (1.0 + E.powf(calculation)) * 100.0;
-            Ok(risk_score)
+            ))
         }
         "male" => {
             let covariates = Covariates::male_30_yr_hf();
-            let calculation = common_calculation(
+            Ok(common_calculation(
                 &covariates,
                 diabetes,
                 smoker,
@@ -139,14 +221,41 @@ pub fn calculate_30_yr_heart_failure_risk(
                 age_factor_squared,
                 egfr,
                 bmi,
-            );
-            let risk_score = E.powf(calculation) / (1.0 + E.powf(calculation)) * 100.0;
-            Ok(risk_score)
+            ))
         }
         _ => Err("Sex must be either 'male' or 'female'.".to_string()),
     }
 }
 
+pub fn calculate_30_yr_heart_failure_risk(
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    diabetes: bool,
+    smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    _cholesterol_treated: bool,
+) -> Result<f64, String> {
+    calculate_30_yr_heart_failure_log_odds(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        diabetes,
+        smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        _cholesterol_treated,
+    )
+    .map(risk_from_log_odds)
+}
+
 #[pyfunction]
 pub fn calculate_10_yr_heart_failure_rust(
     sex: String,
@@ -211,12 +320,76 @@ pub fn calculate_30_yr_heart_failure_rust(
     }
 }
 
+#[pyfunction]
+pub fn calculate_10_yr_heart_failure_log_risk_rust(
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> PyResult<f64> {
+    match calculate_10_yr_heart_failure_log_odds(
+        &sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    ) {
+        Ok(value) => Ok(log_risk_from_log_odds(value)),
+        Err(e) => Err(PyValueError::new_err(e)),
+    }
+}
+
+#[pyfunction]
+pub fn calculate_30_yr_heart_failure_log_risk_rust(
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> PyResult<f64> {
+    match calculate_30_yr_heart_failure_log_odds(
+        &sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    ) {
+        Ok(value) => Ok(log_risk_from_log_odds(value)),
+        Err(e) => Err(PyValueError::new_err(e)),
+    }
+}
+
 #[pyfunction]
 pub fn calculate_10_yr_hf_rust_parallel_np(
     py: Python,
     data: PyReadonlyArrayDyn<f64>,
 ) -> PyResult<PyObject> {
-    calculate_risk_rust_parallel_np(py, data, calculate_10_yr_heart_failure_risk)
+    calculate_risk_rust_parallel_np(py, data, &PreventModel::HeartFailure10Yr)
 }
 
 #[pyfunction]
@@ -224,5 +397,5 @@ pub fn calculate_30_yr_hf_rust_parallel_np(
     py: Python,
     data: PyReadonlyArrayDyn<f64>,
 ) -> PyResult<PyObject> {
-    calculate_risk_rust_parallel_np(py, data, calculate_30_yr_heart_failure_risk)
+    calculate_risk_rust_parallel_np(py, data, &PreventModel::HeartFailure30Yr)
 }
@@ -0,0 +1,534 @@
+use crate::fhir::{
+    age_on, date, BMI_LOINC, CURRENT_SMOKER_SNOMED, EGFR_LOINC, HDL_CHOLESTEROL_LOINC,
+    SMOKING_STATUS_LOINC, SYSTOLIC_BP_LOINC, TOTAL_CHOLESTEROL_LOINC,
+};
+use crate::model::{Patient, Sex, PATIENT_FIELDS};
+use crate::score2::CHOLESTEROL_MMOL_PER_MG;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Serum or plasma creatinine, by mass and by substance concentration.
+const CREATININE_LOINC: [&str; 3] = ["2160-0", "38483-4", "14682-9"];
+const WEIGHT_LOINC: [&str; 2] = ["29463-7", "3141-9"];
+const HEIGHT_LOINC: [&str; 2] = ["8302-2", "3137-7"];
+const CREATININE_UMOL_PER_MG: f64 = 88.42;
+
+/// Values an ORU message can report, with the LOINC codes that identify
+/// them. Local codes can be mapped to any of these names.
+pub const ORU_FIELDS: [(&str, &[&str]); 9] = [
+    ("total_cholesterol", &TOTAL_CHOLESTEROL_LOINC),
+    ("hdl_cholesterol", &HDL_CHOLESTEROL_LOINC),
+    ("systolic_bp", &SYSTOLIC_BP_LOINC),
+    ("bmi", &BMI_LOINC),
+    ("egfr", &EGFR_LOINC),
+    ("creatinine", &CREATININE_LOINC),
+    ("weight", &WEIGHT_LOINC),
+    ("height", &HEIGHT_LOINC),
+    ("current_smoker", &SMOKING_STATUS_LOINC),
+];
+
+/// Inputs every calculator needs. The flags default to false when the
+/// message does not report them.
+const REQUIRED: [&str; 7] = [
+    "sex",
+    "age",
+    "total_cholesterol",
+    "hdl_cholesterol",
+    "systolic_bp",
+    "bmi",
+    "egfr",
+];
+
+/// The calculator inputs found in an HL7 v2 ORU^R01 message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hl7Inputs {
+    /// Inputs in `PATIENT_FIELDS` order, NaN where missing. Sex is 1 for male.
+    pub values: [f64; 11],
+    /// The segment that supplied each input, e.g. `PID` or `OBX-3`, or
+    /// `None` if it is missing or a flag that defaulted to false.
+    pub provenance: [Option<String>; 11],
+    /// Required inputs the message had no usable value for.
+    pub missing: Vec<&'static str>,
+    /// Results that were skipped or read with assumptions.
+    pub warnings: Vec<String>,
+}
+
+impl Hl7Inputs {
+    /// The patient, if no required input is missing.
+    pub fn patient(&self) -> Option<Patient> {
+        if !self.missing.is_empty() {
+            return None;
+        }
+        let value = |field: &str| {
+            let i = PATIENT_FIELDS.iter().position(|f| *f == field).unwrap();
+            self.values[i]
+        };
+        Some(Patient {
+            sex: if value("sex") == 1.0 {
+                Sex::Male
+            } else {
+                Sex::Female
+            },
+            age: value("age"),
+            total_cholesterol: value("total_cholesterol"),
+            hdl_cholesterol: value("hdl_cholesterol"),
+            systolic_bp: value("systolic_bp"),
+            has_diabetes: value("has_diabetes") == 1.0,
+            current_smoker: value("current_smoker") == 1.0,
+            bmi: value("bmi"),
+            egfr: value("egfr"),
+            on_htn_meds: value("on_htn_meds") == 1.0,
+            on_cholesterol_meds: value("on_cholesterol_meds") == 1.0,
+        })
+    }
+}
+
+/// A segment split into fields, where `fields[n]` is SEG-n.
+struct Segment {
+    fields: Vec<String>,
+}
+
+/// The delimiters declared in MSH-1 and MSH-2.
+struct Delimiters {
+    field: char,
+    component: char,
+    repetition: char,
+    escape: char,
+    subcomponent: char,
+}
+
+impl Delimiters {
+    /// Replaces the escape sequences for the delimiters themselves.
+    fn unescape(&self, text: &str) -> String {
+        let escape = self.escape.to_string();
+        if !text.contains(&escape) {
+            return text.to_string();
+        }
+        [
+            ('F', self.field),
+            ('S', self.component),
+            ('T', self.subcomponent),
+            ('R', self.repetition),
+            ('E', self.escape),
+        ]
+        .iter()
+        .fold(text.to_string(), |text, (code, delimiter)| {
+            text.replace(
+                &format!("{}{}{}", escape, code, escape),
+                &delimiter.to_string(),
+            )
+        })
+    }
+}
+
+impl Segment {
+    fn name(&self) -> &str {
+        &self.fields[0]
+    }
+
+    /// The first repetition of SEG-n.
+    fn field<'a>(&'a self, n: usize, delimiters: &Delimiters) -> &'a str {
+        let field = self.fields.get(n).map_or("", String::as_str);
+        field
+            .split(delimiters.repetition)
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Component `c` (from 1) of SEG-n, unescaped.
+    fn component(&self, n: usize, c: usize, delimiters: &Delimiters) -> String {
+        let component = self
+            .field(n, delimiters)
+            .split(delimiters.component)
+            .nth(c - 1)
+            .unwrap_or_default();
+        delimiters.unescape(component.trim())
+    }
+}
+
+fn segments(message: &str) -> Result<(Vec<Segment>, Delimiters), String> {
+    let message = message.trim_start_matches('\u{b}');
+    let header = message
+        .strip_prefix("MSH")
+        .ok_or("HL7 message must start with an MSH segment.")?;
+    let mut declared = header.chars();
+    let field = declared.next().ok_or("MSH segment has no delimiters.")?;
+    let encoding: Vec<char> = declared.take_while(|c| *c != field).collect();
+    let encoding = |i: usize, default: char| encoding.get(i).copied().unwrap_or(default);
+    let delimiters = Delimiters {
+        field,
+        component: encoding(0, '^'),
+        repetition: encoding(1, '~'),
+        escape: encoding(2, '\\'),
+        subcomponent: encoding(3, '&'),
+    };
+
+    let segments = message
+        .split(['\r', '\n'])
+        .map(|line| line.trim_matches(|c: char| c == '\u{b}' || c == '\u{1c}'))
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields: Vec<String> = line.split(field).map(str::to_string).collect();
+            if fields[0] == "MSH" {
+                // MSH-1 is the field separator itself.
+                fields.insert(1, field.to_string());
+            }
+            Segment { fields }
+        })
+        .collect();
+    Ok((segments, delimiters))
+}
+
+/// YYYY-MM-DD from the date of an HL7 timestamp, YYYYMMDD[HHMM[SS]][+ZZZZ].
+fn hl7_date(timestamp: &str) -> Option<String> {
+    let digits = timestamp
+        .get(..8)
+        .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))?;
+    let iso = format!("{}-{}-{}", &digits[..4], &digits[4..6], &digits[6..]);
+    date(&iso).map(|_| iso)
+}
+
+/// A value reported in an OBX segment.
+struct Reported {
+    field: &'static str,
+    value: f64,
+    source: String,
+    timestamp: String,
+}
+
+/// The number in OBX-5, which may be structured (SN) or carry a comparator
+/// such as `>90`, and a warning if a comparator was dropped.
+fn number(obx: &Segment, delimiters: &Delimiters) -> Option<(f64, Option<String>)> {
+    let (comparator, text) = if obx.field(2, delimiters) == "SN" {
+        (
+            obx.component(5, 1, delimiters),
+            obx.component(5, 2, delimiters),
+        )
+    } else {
+        let value = obx.component(5, 1, delimiters);
+        let number = value.trim_start_matches(['<', '>', '=']).trim().to_string();
+        (
+            value[..value.len() - number.len()].trim().to_string(),
+            number,
+        )
+    };
+    let number: f64 = text.parse().ok()?;
+    let warning = (!comparator.is_empty() && comparator != "=")
+        .then(|| format!("reported as '{}{}'; using {}", comparator, text, number));
+    Some((number, warning))
+}
+
+/// Converts a value to the calculator's units, or `None` if the unit is not
+/// one the field can be in.
+fn convert(field: &str, value: f64, unit: &str) -> Option<f64> {
+    let unit = unit.to_lowercase();
+    match (field, unit.as_str()) {
+        ("total_cholesterol" | "hdl_cholesterol" | "creatinine", "mg/dl") => Some(value),
+        ("total_cholesterol" | "hdl_cholesterol", "mmol/l") => {
+            Some(value / CHOLESTEROL_MMOL_PER_MG)
+        }
+        ("creatinine", "umol/l" | "µmol/l") => Some(value / CREATININE_UMOL_PER_MG),
+        ("weight", "kg") => Some(value),
+        ("weight", "g") => Some(value / 1000.0),
+        ("weight", "lb" | "[lb_av]") => Some(value * 0.453_592_37),
+        ("height", "cm") => Some(value),
+        ("height", "m") => Some(value * 100.0),
+        ("height", "in" | "[in_i]") => Some(value * 2.54),
+        ("systolic_bp", "mm[hg]" | "mmhg") => Some(value),
+        ("bmi", "kg/m2") => Some(value),
+        ("egfr", "ml/min/{1.73_m2}") => Some(value),
+        _ => None,
+    }
+}
+
+/// eGFR (mL/min/1.73 m2) from serum creatinine (mg/dL) by the race-free
+/// CKD-EPI 2021 equation.
+pub fn ckd_epi_2021(creatinine: f64, age: f64, sex: Sex) -> f64 {
+    let (kappa, alpha, factor) = match sex {
+        Sex::Female => (0.7, -0.241, 1.012),
+        Sex::Male => (0.9, -0.302, 1.0),
+    };
+    let ratio = creatinine / kappa;
+    142.0 * ratio.min(1.0).powf(alpha) * ratio.max(1.0).powf(-1.2) * 0.9938f64.powf(age) * factor
+}
+
+/// Maps an OBX to one of `ORU_FIELDS` by LOINC code, in the primary or
+/// alternate identifier of OBX-3, or else by a local code in either.
+fn identify(
+    obx: &Segment,
+    delimiters: &Delimiters,
+    local_codes: &[(&str, &'static str)],
+) -> Option<&'static str> {
+    let codings = [
+        (
+            obx.component(3, 1, delimiters),
+            obx.component(3, 3, delimiters),
+        ),
+        (
+            obx.component(3, 4, delimiters),
+            obx.component(3, 6, delimiters),
+        ),
+    ];
+    let loinc = codings.iter().find_map(|(code, system)| {
+        (system == "LN" || system == "LOINC")
+            .then(|| {
+                ORU_FIELDS
+                    .iter()
+                    .find(|(_, codes)| codes.contains(&code.as_str()))
+            })
+            .flatten()
+            .map(|(field, _)| *field)
+    });
+    loinc.or_else(|| {
+        codings.iter().find_map(|(code, _)| {
+            local_codes
+                .iter()
+                .find(|(local, _)| !code.is_empty() && local == code)
+                .map(|(_, field)| *field)
+        })
+    })
+}
+
+/// Reads the inputs for the patient of an HL7 v2 ORU^R01 message as of
+/// `as_of` (YYYY-MM-DD), by default the date of the message. Sex and birth
+/// date come from PID-8 and PID-7. Each other value is the latest final or
+/// corrected OBX result on or before that date (or, leniently, one with no
+/// OBX-11 status, which some senders leave out), identified by LOINC or by
+/// `local_codes`, pairs of a local OBX-3 code and a name from `ORU_FIELDS`.
+/// eGFR is calculated from creatinine by CKD-EPI 2021, and BMI from weight
+/// and height, when not reported. Results in a unit `convert` does not know
+/// are skipped with a warning. Smoking status (72166-2) sets the smoker
+/// flag; diabetes and treatment are not lab results and default to false.
+pub fn inputs_from_oru(
+    message: &str,
+    local_codes: &[(&str, &str)],
+    as_of: Option<&str>,
+) -> Result<Hl7Inputs, String> {
+    let local_codes: Vec<(&str, &'static str)> = local_codes
+        .iter()
+        .map(|(code, field)| {
+            ORU_FIELDS
+                .iter()
+                .find(|(name, _)| name == field)
+                .map(|(name, _)| (*code, *name))
+                .ok_or_else(|| {
+                    let names: Vec<&str> = ORU_FIELDS.iter().map(|(name, _)| *name).collect();
+                    format!(
+                        "Local codes must map to one of {}, not '{}'.",
+                        names.join(", "),
+                        field
+                    )
+                })
+        })
+        .collect::<Result<_, String>>()?;
+
+    let (segments, delimiters) = segments(message)?;
+    let msh = &segments[0];
+    let message_type = (
+        msh.component(9, 1, &delimiters),
+        msh.component(9, 2, &delimiters),
+    );
+    if message_type != ("ORU".to_string(), "R01".to_string()) {
+        return Err(format!(
+            "Expected an ORU^R01 message, not '{}^{}'.",
+            message_type.0, message_type.1
+        ));
+    }
+    let message_time = msh.field(7, &delimiters).to_string();
+    let as_of = match as_of {
+        Some(as_of) => {
+            date(as_of).ok_or_else(|| format!("Date must be YYYY-MM-DD, not '{}'.", as_of))?;
+            as_of.to_string()
+        }
+        None => hl7_date(&message_time).ok_or("MSH-7 has no message date; give as_of.")?,
+    };
+
+    let patients: Vec<&Segment> = segments.iter().filter(|s| s.name() == "PID").collect();
+    if patients.len() > 1 {
+        return Err(format!(
+            "Message must be about one patient, not {}.",
+            patients.len()
+        ));
+    }
+    let mut warnings = Vec::new();
+    let sex = patients
+        .first()
+        .and_then(|pid| match pid.field(8, &delimiters) {
+            "M" => Some(Sex::Male),
+            "F" => Some(Sex::Female),
+            _ => None,
+        });
+    let age = patients.first().and_then(|pid| {
+        let birth = date(&hl7_date(pid.field(7, &delimiters))?)?;
+        Some(age_on(birth, date(&as_of)?))
+    });
+
+    let mut results: Vec<Reported> = Vec::new();
+    let mut observed = message_time;
+    let mut count = 0;
+    for segment in &segments {
+        match segment.name() {
+            "OBR" if !segment.field(7, &delimiters).is_empty() => {
+                observed = segment.field(7, &delimiters).to_string();
+            }
+            "OBX" => {
+                count += 1;
+                let set_id = segment.field(1, &delimiters);
+                let source = format!(
+                    "OBX-{}",
+                    if set_id.is_empty() {
+                        count.to_string()
+                    } else {
+                        set_id.to_string()
+                    }
+                );
+                let Some(field) = identify(segment, &delimiters, &local_codes) else {
+                    continue;
+                };
+                if !matches!(segment.field(11, &delimiters), "F" | "C" | "") {
+                    continue;
+                }
+                let timestamp = match segment.field(14, &delimiters) {
+                    "" => observed.clone(),
+                    timestamp => timestamp.to_string(),
+                };
+                if hl7_date(&timestamp).is_some_and(|date| date > as_of) {
+                    continue;
+                }
+                let value = if field == "current_smoker" {
+                    let current = [1, 4].iter().any(|c| {
+                        CURRENT_SMOKER_SNOMED
+                            .contains(&segment.component(5, *c, &delimiters).as_str())
+                    });
+                    current as u8 as f64
+                } else {
+                    let Some((number, comparator)) = number(segment, &delimiters) else {
+                        warnings.push(format!("{} {} is not a number; skipped.", source, field));
+                        continue;
+                    };
+                    if let Some(comparator) = comparator {
+                        warnings.push(format!("{} {} {}.", source, field, comparator));
+                    }
+                    let unit = match segment.component(6, 1, &delimiters) {
+                        unit if unit.is_empty() => segment.component(6, 2, &delimiters),
+                        unit => unit,
+                    };
+                    match convert(field, number, &unit) {
+                        Some(value) => value,
+                        None => {
+                            warnings.push(format!(
+                                "{} {} has unknown unit '{}'; skipped.",
+                                source, field, unit
+                            ));
+                            continue;
+                        }
+                    }
+                };
+                results.push(Reported {
+                    field,
+                    value,
+                    source,
+                    timestamp,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // The latest result for a field; the later segment wins a tie.
+    let latest = |field: &str| {
+        results
+            .iter()
+            .filter(|result| result.field == field)
+            .max_by(|a, b| a.timestamp.cmp(&b.timestamp))
+            .map(|result| (result.value, result.source.clone()))
+    };
+
+    let mut values = [f64::NAN; 11];
+    let mut provenance: [Option<String>; 11] = Default::default();
+    let mut set = |field: &str, found: Option<(f64, String)>| {
+        let i = PATIENT_FIELDS.iter().position(|f| *f == field).unwrap();
+        if let Some((value, source)) = found {
+            values[i] = value;
+            provenance[i] = Some(source);
+        }
+    };
+    set(
+        "sex",
+        sex.map(|sex| ((sex == Sex::Male) as u8 as f64, "PID".to_string())),
+    );
+    set("age", age.map(|age| (age, "PID".to_string())));
+    for field in ["total_cholesterol", "hdl_cholesterol", "systolic_bp"] {
+        set(field, latest(field));
+    }
+    let bmi = latest("bmi").or_else(|| {
+        let (weight, weight_source) = latest("weight")?;
+        let (height, height_source) = latest("height")?;
+        let bmi = weight / (height / 100.0).powi(2);
+        Some((bmi, format!("{}, {}", weight_source, height_source)))
+    });
+    set("bmi", bmi);
+    let egfr = latest("egfr").or_else(|| {
+        let (creatinine, source) = latest("creatinine")?;
+        let egfr = ckd_epi_2021(creatinine, age?, sex?);
+        Some((egfr, format!("{} (CKD-EPI 2021)", source)))
+    });
+    set("egfr", egfr);
+    set("current_smoker", latest("current_smoker"));
+    for flag in [
+        "has_diabetes",
+        "current_smoker",
+        "on_htn_meds",
+        "on_cholesterol_meds",
+    ] {
+        let i = PATIENT_FIELDS.iter().position(|f| *f == flag).unwrap();
+        if values[i].is_nan() {
+            values[i] = 0.0;
+        }
+    }
+
+    let missing = REQUIRED
+        .iter()
+        .filter(|field| {
+            let i = PATIENT_FIELDS.iter().position(|f| f == *field).unwrap();
+            values[i].is_nan()
+        })
+        .copied()
+        .collect();
+    Ok(Hl7Inputs {
+        values,
+        provenance,
+        missing,
+        warnings,
+    })
+}
+
+/// (inputs in `PATIENT_FIELDS` order, their sources, missing inputs,
+/// warnings).
+type OruSummary = (
+    Vec<f64>,
+    Vec<Option<String>>,
+    Vec<&'static str>,
+    Vec<String>,
+);
+
+#[pyfunction]
+pub fn oru_inputs_rust(
+    message: String,
+    local_codes: Vec<(String, String)>,
+    as_of: Option<String>,
+) -> PyResult<OruSummary> {
+    let local_codes: Vec<(&str, &str)> = local_codes
+        .iter()
+        .map(|(code, field)| (code.as_str(), field.as_str()))
+        .collect();
+    match inputs_from_oru(&message, &local_codes, as_of.as_deref()) {
+        Ok(inputs) => Ok((
+            inputs.values.to_vec(),
+            inputs.provenance.to_vec(),
+            inputs.missing,
+            inputs.warnings,
+        )),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
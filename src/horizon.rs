@@ -0,0 +1,155 @@
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+use crate::utils::patients;
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// How risks at other horizons are derived from the 10- and 30-year
+/// predictions. Reported alongside every derived risk.
+pub const HORIZON_METHOD: &str = "piecewise_constant_hazard";
+
+/// A risk at a horizon the PREVENT equations do not predict directly. It is
+/// an estimate derived under `HORIZON_METHOD`, not a model prediction.
+/// `hazard_clamped` is set when the 30-year prediction was below the 10-year
+/// one, so the risk beyond 10 years was held at the 10-year risk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DerivedRisk {
+    pub risk: f64,
+    pub horizon_years: f64,
+    pub method: &'static str,
+    pub hazard_clamped: bool,
+}
+
+/// Derives the cumulative risk (%) at `horizon_years` from the 10- and 30-year
+/// risks (%), assuming the hazard is constant from 0 to 10 years and,
+/// separately, from 10 to 30 years. The cumulative hazard -ln(1 - risk) is
+/// then linear in time within each interval, so the curve passes exactly
+/// through both predictions. Horizons up to 10 years only need the 10-year
+/// risk.
+///
+/// The 10- and 30-year equations are fitted separately, so for some extreme
+/// patients the 30-year risk is below the 10-year risk. A cumulative hazard
+/// cannot fall, so the 30-year hazard is then clamped to the 10-year hazard
+/// and the risk stays flat after 10 years.
+pub fn risk_at_horizon(
+    ten_year_risk: f64,
+    thirty_year_risk: Option<f64>,
+    horizon_years: f64,
+) -> Result<f64, String> {
+    if !(1.0..=30.0).contains(&horizon_years) {
+        return Err("Horizon must be between 1 and 30 years".to_string());
+    }
+    let cumulative_hazard = |risk: f64| -(-risk / 100.0).ln_1p();
+    let ten_year_hazard = cumulative_hazard(ten_year_risk);
+
+    let hazard = if horizon_years <= 10.0 {
+        ten_year_hazard * horizon_years / 10.0
+    } else {
+        let thirty_year_risk =
+            thirty_year_risk.ok_or("A 30-year risk is needed for horizons beyond 10 years")?;
+        let thirty_year_hazard = cumulative_hazard(thirty_year_risk).max(ten_year_hazard);
+        ten_year_hazard + (thirty_year_hazard - ten_year_hazard) * (horizon_years - 10.0) / 20.0
+    };
+    Ok(-(-hazard).exp_m1() * 100.0)
+}
+
+/// The 10- and 30-year models of an outcome (`cvd`, `ascvd` or `hf`).
+fn outcome_models(outcome: &str) -> Result<(PreventModel, PreventModel), String> {
+    let models: Vec<_> = PreventModel::ALL
+        .into_iter()
+        .filter(|model| model.metadata().outcome == outcome.to_lowercase())
+        .collect();
+    match models[..] {
+        [ten_year, thirty_year] => Ok((ten_year, thirty_year)),
+        _ => Err(format!(
+            "Outcome must be one of cvd, ascvd or hf, not '{}'.",
+            outcome
+        )),
+    }
+}
+
+/// Risk of `outcome` at `horizon_years` for a patient, derived from the PREVENT
+/// 10- and 30-year predictions by `risk_at_horizon`. The 30-year model is only
+/// used, and its age limit only applies, for horizons beyond 10 years.
+pub fn calculate_risk_at_horizon(
+    outcome: &str,
+    patient: &Patient,
+    horizon_years: f64,
+) -> Result<DerivedRisk, String> {
+    let (ten_year_model, thirty_year_model) = outcome_models(outcome)?;
+    let ten_year_risk = ten_year_model.predict(patient)?;
+    let thirty_year_risk = if horizon_years > 10.0 {
+        Some(thirty_year_model.predict(patient)?)
+    } else {
+        None
+    };
+    Ok(DerivedRisk {
+        risk: risk_at_horizon(ten_year_risk, thirty_year_risk, horizon_years)?,
+        horizon_years,
+        method: HORIZON_METHOD,
+        hazard_clamped: thirty_year_risk.is_some_and(|risk| risk < ten_year_risk),
+    })
+}
+
+#[pyfunction]
+pub fn calculate_risk_at_horizon_rust(
+    outcome: String,
+    horizon_years: f64,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> PyResult<(f64, &'static str, bool)> {
+    let patient = Patient {
+        sex: Sex::parse(&sex).map_err(PyValueError::new_err)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    };
+    match calculate_risk_at_horizon(&outcome, &patient, horizon_years) {
+        Ok(derived) => Ok((derived.risk, derived.method, derived.hazard_clamped)),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+#[pyfunction]
+pub fn calculate_risk_at_horizon_rust_parallel_np(
+    py: Python,
+    outcome: String,
+    horizon_years: f64,
+    data: PyReadonlyArrayDyn<f64>,
+) -> PyResult<(PyObject, PyObject, &'static str)> {
+    outcome_models(&outcome).map_err(PyValueError::new_err)?;
+    risk_at_horizon(0.0, Some(0.0), horizon_years).map_err(PyValueError::new_err)?;
+    let patients = patients(&data)?;
+
+    let (results, clamped): (Vec<f64>, Vec<bool>) = patients
+        .par_iter()
+        .map(|patient| {
+            calculate_risk_at_horizon(&outcome, patient, horizon_years)
+                .map(|derived| (derived.risk, derived.hazard_clamped))
+                .unwrap_or((f64::NAN, false)) // Handle error by returning NaN
+        })
+        .unzip();
+
+    Ok((
+        PyArray::from_vec(py, results).to_object(py),
+        PyArray::from_vec(py, clamped).to_object(py),
+        HORIZON_METHOD,
+    ))
+}
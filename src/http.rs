@@ -0,0 +1,77 @@
+use serde_json::{json, Value};
+use std::io::Read;
+use tiny_http::{Header, Request, Response, Server};
+
+/// Listens for HTTP requests on `address`, e.g. `127.0.0.1:8000`.
+pub fn listen(address: &str) -> Result<Server, String> {
+    Server::http(address).map_err(|e| format!("Could not listen on {}: {}", address, e))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("header names and values are ASCII")
+}
+
+/// The status and JSON body for a request: 413 if the body is larger than
+/// `max_body_bytes`, which is not read past the limit, 400 if it is not
+/// UTF-8, and otherwise what `handle` returns for the method, path without
+/// query string, and body.
+fn respond_to(
+    request: &mut Request,
+    max_body_bytes: usize,
+    handle: &impl Fn(&str, &str, &str) -> (u16, Value),
+) -> (u16, Value) {
+    let too_large = json!({
+        "error": format!("Request body is larger than {} bytes.", max_body_bytes)
+    });
+    if request.body_length().is_some_and(|n| n > max_body_bytes) {
+        return (413, too_large);
+    }
+    let mut body = Vec::new();
+    let limit = max_body_bytes as u64 + 1;
+    match request.as_reader().take(limit).read_to_end(&mut body) {
+        Err(e) => (400, json!({ "error": e.to_string() })),
+        Ok(n) if n > max_body_bytes => (413, too_large),
+        Ok(_) => match String::from_utf8(body) {
+            Ok(body) => {
+                let path = request.url().split('?').next().unwrap_or_default();
+                handle(request.method().as_str(), path, &body)
+            }
+            Err(_) => (400, json!({"error": "Body must be UTF-8."})),
+        },
+    }
+}
+
+/// Answers requests on `server` with `threads` worker threads until it is
+/// unblocked. Responses are JSON with `headers` added; a null response is
+/// sent with an empty body.
+pub fn serve(
+    server: &Server,
+    max_body_bytes: usize,
+    threads: usize,
+    headers: &[(&str, &str)],
+    handle: impl Fn(&str, &str, &str) -> (u16, Value) + Sync,
+) {
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                while let Ok(mut request) = server.recv() {
+                    let (status, response) = respond_to(&mut request, max_body_bytes, &handle);
+                    let body = if response.is_null() {
+                        String::new()
+                    } else {
+                        response.to_string()
+                    };
+                    let response = headers.iter().fold(
+                        Response::from_string(body)
+                            .with_status_code(status)
+                            .with_header(header("Content-Type", "application/json")),
+                        |response, (name, value)| response.with_header(header(name, value)),
+                    );
+                    if let Err(e) = request.respond(response) {
+                        eprintln!("Could not respond: {}", e);
+                    }
+                }
+            });
+        }
+    });
+}
@@ -1,13 +1,64 @@
+#![allow(clippy::too_many_arguments)]
+
 use ascvd::*;
+use cohort_summary::*;
+use confidence_interval::*;
 use cvd::*;
+use disclosure::*;
+use fhir::*;
+use gradient::*;
+use heart_age::*;
 use heart_failure::*;
+use hl7::*;
+use horizon::*;
+use model::*;
+use model_comparison::*;
+use monte_carlo::*;
+use peer_comparison::*;
+use plausibility::*;
+use pooled_cohort::*;
 use pyo3::prelude::*;
+use range_policy::*;
+use score2::*;
+use survey::*;
+use trajectory::*;
+use validation::*;
 
-mod ascvd;
+pub mod ascvd;
+#[cfg(feature = "cds-hooks")]
+pub mod cds_hooks;
+pub mod cohort_summary;
+pub mod confidence_interval;
 mod covariates;
-mod cvd;
-mod heart_failure;
-mod utils;
+pub mod cvd;
+pub mod disclosure;
+pub mod fhir;
+pub mod gradient;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod heart_age;
+pub mod heart_failure;
+pub mod hl7;
+pub mod horizon;
+#[cfg(any(feature = "rest", feature = "cds-hooks"))]
+pub mod http;
+pub mod model;
+pub mod model_comparison;
+pub mod monte_carlo;
+pub mod peer_comparison;
+pub mod plausibility;
+pub mod pooled_cohort;
+pub mod range_policy;
+#[cfg(feature = "rest")]
+pub mod rest;
+pub mod score2;
+pub mod survey;
+pub mod trajectory;
+pub mod utils;
+pub mod validation;
+
+#[cfg(test)]
+mod tests;
 
 #[pymodule]
 fn _pyprevent(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -18,6 +69,18 @@ fn _pyprevent(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(calculate_30_yr_ascvd_rust, m)?)?;
     m.add_function(wrap_pyfunction!(calculate_10_yr_cvd_rust, m)?)?;
     m.add_function(wrap_pyfunction!(calculate_30_yr_cvd_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_10_yr_cvd_log_risk_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_30_yr_cvd_log_risk_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_10_yr_ascvd_log_risk_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_30_yr_ascvd_log_risk_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        calculate_10_yr_heart_failure_log_risk_rust,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(
+        calculate_30_yr_heart_failure_log_risk_rust,
+        m
+    )?)?;
     // batch patients
     m.add_function(wrap_pyfunction!(calculate_10_yr_ascvd_rust_parallel_np, m)?)?;
     m.add_function(wrap_pyfunction!(calculate_30_yr_ascvd_rust_parallel_np, m)?)?;
@@ -25,5 +88,76 @@ fn _pyprevent(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(calculate_30_yr_cvd_rust_parallel_np, m)?)?;
     m.add_function(wrap_pyfunction!(calculate_10_yr_hf_rust_parallel_np, m)?)?;
     m.add_function(wrap_pyfunction!(calculate_30_yr_hf_rust_parallel_np, m)?)?;
+    // out of range policy
+    m.add_function(wrap_pyfunction!(calculate_risk_with_policy_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        calculate_risk_with_policy_rust_parallel_np,
+        m
+    )?)?;
+    // plausibility checks
+    m.add_function(wrap_pyfunction!(check_plausibility_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(check_plausibility_rust_parallel_np, m)?)?;
+    // pooled cohort equations
+    m.add_function(wrap_pyfunction!(calculate_10_yr_pce_ascvd_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        calculate_10_yr_pce_ascvd_rust_parallel_np,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(reclassification_table_rust, m)?)?;
+    // risk models
+    m.add_function(wrap_pyfunction!(available_models_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(model_metadata_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_model_risk_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_model_risk_rust_parallel_np, m)?)?;
+    // SCORE2 and SCORE2-OP
+    m.add_function(wrap_pyfunction!(calculate_score2_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_score2_op_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_score2_rust_parallel_np, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_score2_op_rust_parallel_np, m)?)?;
+    // heart age
+    m.add_function(wrap_pyfunction!(calculate_heart_age_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate_heart_age_rust_parallel_np, m)?)?;
+    // optimal risk and peer percentiles
+    m.add_function(wrap_pyfunction!(compare_to_peers_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_to_peers_rust_parallel_np, m)?)?;
+    // risk trajectories
+    m.add_function(wrap_pyfunction!(risk_trajectory_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(risk_trajectory_rust_parallel_np, m)?)?;
+    // risk at other horizons
+    m.add_function(wrap_pyfunction!(calculate_risk_at_horizon_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        calculate_risk_at_horizon_rust_parallel_np,
+        m
+    )?)?;
+    // gradients
+    m.add_function(wrap_pyfunction!(risk_gradient_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(risk_gradient_rust_parallel_np, m)?)?;
+    // measurement uncertainty
+    m.add_function(wrap_pyfunction!(simulate_measurement_error_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        simulate_measurement_error_rust_parallel_np,
+        m
+    )?)?;
+    // coefficient confidence intervals
+    m.add_function(wrap_pyfunction!(coefficient_names_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(risk_confidence_interval_rust, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        risk_confidence_interval_rust_parallel_np,
+        m
+    )?)?;
+    // validation on observed outcomes
+    m.add_function(wrap_pyfunction!(validate_predictions_rust, m)?)?;
+    // model comparison
+    m.add_function(wrap_pyfunction!(compare_models_rust, m)?)?;
+    // survey-weighted population estimates
+    m.add_function(wrap_pyfunction!(survey_summary_rust, m)?)?;
+    // cohort summaries by subgroup
+    m.add_function(wrap_pyfunction!(cohort_summary_rust, m)?)?;
+    // privacy-safe aggregate exports
+    m.add_function(wrap_pyfunction!(protected_cohort_summary_rust, m)?)?;
+    // FHIR R4 input adapter
+    m.add_function(wrap_pyfunction!(assess_fhir_bundle_rust, m)?)?;
+    // HL7 v2 ORU^R01 lab feed ingestion
+    m.add_function(wrap_pyfunction!(oru_inputs_rust, m)?)?;
     Ok(())
 }
@@ -1,2 +1,93 @@
+use pyprevent::cohort_summary::{summarize_cohort_file, summary_csv, summary_json};
+use pyprevent::disclosure::{protect_summary, protected_csv, protected_json, DisclosurePolicy};
+use pyprevent::model::{PreventModel, RiskModel};
+use std::{env, fs, process};
+
+const USAGE: &str = "Usage: pyprevent summarize <cohort.csv> [--models KEY,KEY...] \
+[--format csv|json] [--output FILE] [--protect] [--min-cell-size N] [--count-base N] \
+[--decimals N] [--epsilon X] [--seed N]";
+
+fn parse<T: std::str::FromStr>(arg: &str, value: String) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for {}", value, arg))
+}
+
+/// Runs a subcommand, returning what to print on success.
+fn run(args: &[String]) -> Result<String, String> {
+    match args.first().map(String::as_str) {
+        Some("summarize") => summarize(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn summarize(args: &[String]) -> Result<String, String> {
+    let mut input = None;
+    let mut models: Vec<String> = PreventModel::ALL
+        .iter()
+        .map(|model| model.metadata().key.to_string())
+        .collect();
+    let mut format = "csv".to_string();
+    let mut output = None;
+    // Any disclosure option turns on the aggregate export mode.
+    let mut policy: Option<DisclosurePolicy> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE))
+        };
+        match arg.as_str() {
+            "--models" => models = value()?.split(',').map(str::to_string).collect(),
+            "--format" => format = value()?,
+            "--output" => output = Some(value()?),
+            "--protect" => {
+                policy.get_or_insert_with(DisclosurePolicy::default);
+            }
+            "--min-cell-size" | "--count-base" | "--decimals" | "--epsilon" | "--seed" => {
+                let value = value()?;
+                let policy = policy.get_or_insert_with(DisclosurePolicy::default);
+                match arg.as_str() {
+                    "--min-cell-size" => policy.min_cell_size = parse(arg, value)?,
+                    "--count-base" => policy.count_base = parse(arg, value)?,
+                    "--decimals" => policy.decimals = Some(parse(arg, value)?),
+                    "--epsilon" => policy.epsilon = Some(parse(arg, value)?),
+                    _ => policy.seed = parse(arg, value)?,
+                }
+            }
+            _ if input.is_none() && !arg.starts_with("--") => input = Some(arg.clone()),
+            _ => return Err(format!("Unexpected argument '{}'\n{}", arg, USAGE)),
+        }
+    }
+    let input = input.ok_or(USAGE)?;
+
+    let keys: Vec<&str> = models.iter().map(String::as_str).collect();
+    let rows = summarize_cohort_file(&input, &keys)?;
+    let report = match (format.as_str(), policy) {
+        ("csv", None) => summary_csv(&rows),
+        ("json", None) => summary_json(&rows),
+        ("csv", Some(policy)) => protected_csv(&protect_summary(&rows, &policy)?),
+        ("json", Some(policy)) => protected_json(&protect_summary(&rows, &policy)?),
+        _ => return Err(format!("Format must be csv or json, not '{}'", format)),
+    };
+    match output {
+        Some(path) => {
+            fs::write(&path, report).map_err(|e| format!("Could not write '{}': {}", path, e))?;
+            Ok(String::new())
+        }
+        None => Ok(report),
+    }
+}
+
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(report) => print!("{}", report),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
 }
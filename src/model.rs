@@ -0,0 +1,410 @@
+use crate::ascvd::{
+    calculate_10_yr_ascvd_log_odds_unchecked, calculate_10_yr_ascvd_risk,
+    calculate_30_yr_ascvd_log_odds_unchecked, calculate_30_yr_ascvd_risk,
+};
+use crate::covariates::Covariates;
+use crate::cvd::{
+    calculate_10_yr_cvd_log_odds_unchecked, calculate_10_yr_cvd_risk,
+    calculate_30_yr_cvd_log_odds_unchecked, calculate_30_yr_cvd_risk,
+};
+use crate::heart_failure::{
+    calculate_10_yr_heart_failure_log_odds_unchecked, calculate_10_yr_heart_failure_risk,
+    calculate_30_yr_heart_failure_log_odds_unchecked, calculate_30_yr_heart_failure_risk,
+};
+use crate::score2::Score2Model;
+use crate::utils::{
+    calculate_risk_rust_parallel_np, input_ranges, InputRange, RiskCalcFn, RiskCategory,
+};
+use numpy::PyReadonlyArrayDyn;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sex {
+    Female,
+    Male,
+}
+
+impl Sex {
+    pub fn parse(sex: &str) -> Result<Sex, String> {
+        match sex.to_lowercase().as_str() {
+            "female" => Ok(Sex::Female),
+            "male" => Ok(Sex::Male),
+            _ => Err("Sex must be either 'male' or 'female'.".to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sex::Female => "female",
+            Sex::Male => "male",
+        }
+    }
+}
+
+/// One patient's inputs, with the same fields and units as the columns of the
+/// batch input array.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Patient {
+    pub sex: Sex,
+    pub age: f64,
+    pub total_cholesterol: f64,
+    pub hdl_cholesterol: f64,
+    pub systolic_bp: f64,
+    pub has_diabetes: bool,
+    pub current_smoker: bool,
+    pub bmi: f64,
+    pub egfr: f64,
+    pub on_htn_meds: bool,
+    pub on_cholesterol_meds: bool,
+}
+
+impl Patient {
+    /// A patient of the given sex and age with optimal risk factors: total
+    /// cholesterol 170 mg/dL, HDL cholesterol 50 mg/dL, systolic BP 110 mmHg,
+    /// BMI 25, eGFR 90, no diabetes, not smoking and on no medication.
+    pub fn optimal(sex: Sex, age: f64) -> Patient {
+        Patient {
+            sex,
+            age,
+            total_cholesterol: 170.0,
+            hdl_cholesterol: 50.0,
+            systolic_bp: 110.0,
+            has_diabetes: false,
+            current_smoker: false,
+            bmi: 25.0,
+            egfr: 90.0,
+            on_htn_meds: false,
+            on_cholesterol_meds: false,
+        }
+    }
+
+    /// Value of an input by its column name, with booleans as 0 or 1.
+    pub fn value(&self, field: &str) -> Option<f64> {
+        match field {
+            "sex" => Some(if self.sex == Sex::Male { 1.0 } else { 0.0 }),
+            "age" => Some(self.age),
+            "total_cholesterol" => Some(self.total_cholesterol),
+            "hdl_cholesterol" => Some(self.hdl_cholesterol),
+            "systolic_bp" => Some(self.systolic_bp),
+            "has_diabetes" => Some(self.has_diabetes as u8 as f64),
+            "current_smoker" => Some(self.current_smoker as u8 as f64),
+            "bmi" => Some(self.bmi),
+            "egfr" => Some(self.egfr),
+            "on_htn_meds" => Some(self.on_htn_meds as u8 as f64),
+            "on_cholesterol_meds" => Some(self.on_cholesterol_meds as u8 as f64),
+            _ => None,
+        }
+    }
+
+    /// Mutable reference to a continuous input by its column name.
+    pub fn value_mut(&mut self, field: &str) -> Option<&mut f64> {
+        match field {
+            "age" => Some(&mut self.age),
+            "total_cholesterol" => Some(&mut self.total_cholesterol),
+            "hdl_cholesterol" => Some(&mut self.hdl_cholesterol),
+            "systolic_bp" => Some(&mut self.systolic_bp),
+            "bmi" => Some(&mut self.bmi),
+            "egfr" => Some(&mut self.egfr),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn score(&self, risk_calc_fn: RiskCalcFn) -> Result<f64, String> {
+        risk_calc_fn(
+            self.sex.as_str(),
+            self.age,
+            self.total_cholesterol,
+            self.hdl_cholesterol,
+            self.systolic_bp,
+            self.has_diabetes,
+            self.current_smoker,
+            self.bmi,
+            self.egfr,
+            self.on_htn_meds,
+            self.on_cholesterol_meds,
+        )
+    }
+}
+
+/// Column names of the batch input array, in order.
+pub const PATIENT_FIELDS: [&str; 11] = [
+    "sex",
+    "age",
+    "total_cholesterol",
+    "hdl_cholesterol",
+    "systolic_bp",
+    "has_diabetes",
+    "current_smoker",
+    "bmi",
+    "egfr",
+    "on_htn_meds",
+    "on_cholesterol_meds",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModelMetadata {
+    /// Identifier used to select the model, e.g. `10_yr_cvd`.
+    pub key: &'static str,
+    pub name: &'static str,
+    pub version: &'static str,
+    pub horizon_years: u32,
+    pub outcome: &'static str,
+}
+
+/// A risk score that can be run through the crate's single-patient, batch and
+/// Python entry points.
+pub trait RiskModel: Send + Sync {
+    fn metadata(&self) -> ModelMetadata;
+
+    /// Names of the `Patient` fields the model needs, from `PATIENT_FIELDS`.
+    fn required_inputs(&self) -> &'static [&'static str];
+
+    /// Valid range of each continuous input the model checks.
+    fn input_ranges(&self) -> Vec<InputRange>;
+
+    fn validate(&self, patient: &Patient) -> Result<(), String> {
+        for range in self.input_ranges() {
+            let value = patient
+                .value(range.field)
+                .ok_or_else(|| format!("Unknown input '{}'", range.field))?;
+            if !(range.min..=range.max).contains(&value) {
+                return Err(range.message.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Predicted risk as a percentage, or the validation error.
+    fn predict(&self, patient: &Patient) -> Result<f64, String>;
+}
+
+/// The six PREVENT base model equations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PreventModel {
+    Cvd10Yr,
+    Cvd30Yr,
+    Ascvd10Yr,
+    Ascvd30Yr,
+    HeartFailure10Yr,
+    HeartFailure30Yr,
+}
+
+impl PreventModel {
+    pub const ALL: [PreventModel; 6] = [
+        PreventModel::Cvd10Yr,
+        PreventModel::Cvd30Yr,
+        PreventModel::Ascvd10Yr,
+        PreventModel::Ascvd30Yr,
+        PreventModel::HeartFailure10Yr,
+        PreventModel::HeartFailure30Yr,
+    ];
+
+    pub fn from_key(key: &str) -> Result<PreventModel, String> {
+        PreventModel::ALL
+            .into_iter()
+            .find(|model| model.metadata().key == key.to_lowercase())
+            .ok_or_else(|| {
+                format!(
+                    "Outcome must be one of 10_yr_cvd, 30_yr_cvd, 10_yr_ascvd, 30_yr_ascvd, \
+                     10_yr_hf or 30_yr_hf, not '{}'.",
+                    key
+                )
+            })
+    }
+
+    pub fn is_ten_year(&self) -> bool {
+        self.metadata().horizon_years == 10
+    }
+
+    /// Only the heart failure equations have BMI terms. The CVD and ASCVD
+    /// BMI coefficients are zero, and their design vectors take a BMI of 0.0,
+    /// as the male and 30-year CVD point estimates do.
+    pub(crate) fn uses_bmi(&self) -> bool {
+        matches!(
+            self,
+            PreventModel::HeartFailure10Yr | PreventModel::HeartFailure30Yr
+        )
+    }
+
+    pub fn risk_calc_fn(&self) -> RiskCalcFn {
+        match self {
+            PreventModel::Cvd10Yr => calculate_10_yr_cvd_risk,
+            PreventModel::Cvd30Yr => calculate_30_yr_cvd_risk,
+            PreventModel::Ascvd10Yr => calculate_10_yr_ascvd_risk,
+            PreventModel::Ascvd30Yr => calculate_30_yr_ascvd_risk,
+            PreventModel::HeartFailure10Yr => calculate_10_yr_heart_failure_risk,
+            PreventModel::HeartFailure30Yr => calculate_30_yr_heart_failure_risk,
+        }
+    }
+
+    /// Coefficients of the equation for `sex`.
+    pub(crate) fn covariates(&self, sex: Sex) -> Covariates {
+        match (self, sex) {
+            (PreventModel::Cvd10Yr, Sex::Female) => Covariates::female_10_yr_cvd(),
+            (PreventModel::Cvd10Yr, Sex::Male) => Covariates::male_10_yr_cvd(),
+            (PreventModel::Cvd30Yr, Sex::Female) => Covariates::female_30_yr_cvd(),
+            (PreventModel::Cvd30Yr, Sex::Male) => Covariates::male_30_yr_cvd(),
+            (PreventModel::Ascvd10Yr, Sex::Female) => Covariates::female_10_yr_ascvd(),
+            (PreventModel::Ascvd10Yr, Sex::Male) => Covariates::male_10_yr_ascvd(),
+            (PreventModel::Ascvd30Yr, Sex::Female) => Covariates::female_30_yr_ascvd(),
+            (PreventModel::Ascvd30Yr, Sex::Male) => Covariates::male_30_yr_ascvd(),
+            (PreventModel::HeartFailure10Yr, Sex::Female) => Covariates::female_10_yr_hf(),
+            (PreventModel::HeartFailure10Yr, Sex::Male) => Covariates::male_10_yr_hf(),
+            (PreventModel::HeartFailure30Yr, Sex::Female) => Covariates::female_30_yr_hf(),
+            (PreventModel::HeartFailure30Yr, Sex::Male) => Covariates::male_30_yr_hf(),
+        }
+    }
+
+    /// Linear predictor without input validation.
+    pub(crate) fn unchecked_log_odds_fn(&self) -> RiskCalcFn {
+        match self {
+            PreventModel::Cvd10Yr => calculate_10_yr_cvd_log_odds_unchecked,
+            PreventModel::Cvd30Yr => calculate_30_yr_cvd_log_odds_unchecked,
+            PreventModel::Ascvd10Yr => calculate_10_yr_ascvd_log_odds_unchecked,
+            PreventModel::Ascvd30Yr => calculate_30_yr_ascvd_log_odds_unchecked,
+            PreventModel::HeartFailure10Yr => calculate_10_yr_heart_failure_log_odds_unchecked,
+            PreventModel::HeartFailure30Yr => calculate_30_yr_heart_failure_log_odds_unchecked,
+        }
+    }
+}
+
+impl RiskModel for PreventModel {
+    fn metadata(&self) -> ModelMetadata {
+        let (key, name, horizon_years, outcome) = match self {
+            PreventModel::Cvd10Yr => ("10_yr_cvd", "PREVENT 10-year CVD", 10, "cvd"),
+            PreventModel::Cvd30Yr => ("30_yr_cvd", "PREVENT 30-year CVD", 30, "cvd"),
+            PreventModel::Ascvd10Yr => ("10_yr_ascvd", "PREVENT 10-year ASCVD", 10, "ascvd"),
+            PreventModel::Ascvd30Yr => ("30_yr_ascvd", "PREVENT 30-year ASCVD", 30, "ascvd"),
+            PreventModel::HeartFailure10Yr => {
+                ("10_yr_hf", "PREVENT 10-year heart failure", 10, "hf")
+            }
+            PreventModel::HeartFailure30Yr => {
+                ("30_yr_hf", "PREVENT 30-year heart failure", 30, "hf")
+            }
+        };
+        ModelMetadata {
+            key,
+            name,
+            version: "2024 base model",
+            horizon_years,
+            outcome,
+        }
+    }
+
+    fn required_inputs(&self) -> &'static [&'static str] {
+        &PATIENT_FIELDS
+    }
+
+    fn input_ranges(&self) -> Vec<InputRange> {
+        input_ranges(self.is_ten_year()).into()
+    }
+
+    fn predict(&self, patient: &Patient) -> Result<f64, String> {
+        patient.score(self.risk_calc_fn())
+    }
+}
+
+/// Every model that can be selected by key.
+pub fn models() -> Vec<Box<dyn RiskModel>> {
+    let prevent = PreventModel::ALL
+        .into_iter()
+        .map(|model| Box::new(model) as Box<dyn RiskModel>);
+    let score2 = Score2Model::all()
+        .into_iter()
+        .map(|model| Box::new(model) as Box<dyn RiskModel>);
+    prevent.chain(score2).collect()
+}
+
+pub fn find_model(key: &str) -> Result<Box<dyn RiskModel>, String> {
+    models()
+        .into_iter()
+        .find(|model| model.metadata().key == key.to_lowercase())
+        .ok_or_else(|| {
+            let keys: Vec<_> = models().iter().map(|model| model.metadata().key).collect();
+            format!("Model must be one of {}, not '{}'.", keys.join(", "), key)
+        })
+}
+
+/// Scores every patient in parallel, returning NaN for patients that fail
+/// validation.
+pub fn predict_batch(model: &dyn RiskModel, patients: &[Patient]) -> Vec<f64> {
+    patients
+        .par_iter()
+        .map(|patient| model.predict(patient).unwrap_or(f64::NAN)) // Handle error by returning NaN
+        .collect()
+}
+
+/// Category of a `risk` (%) from `model`. The categories are defined for
+/// 10-year risk, so only the 10-year PREVENT models have one.
+pub fn risk_category(model: &dyn RiskModel, risk: f64) -> Option<RiskCategory> {
+    let ten_year_prevent =
+        PreventModel::from_key(model.metadata().key).is_ok_and(|model| model.is_ten_year());
+    RiskCategory::from_10_yr_risk(risk).filter(|_| ten_year_prevent)
+}
+
+#[pyfunction]
+pub fn available_models_rust() -> Vec<&'static str> {
+    models().iter().map(|model| model.metadata().key).collect()
+}
+
+#[pyfunction]
+pub fn model_metadata_rust(
+    model: String,
+) -> PyResult<(&'static str, &'static str, u32, &'static str)> {
+    let metadata = find_model(&model)
+        .map_err(PyValueError::new_err)?
+        .metadata();
+    Ok((
+        metadata.name,
+        metadata.version,
+        metadata.horizon_years,
+        metadata.outcome,
+    ))
+}
+
+#[pyfunction]
+pub fn calculate_model_risk_rust(
+    model: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> PyResult<f64> {
+    let model = find_model(&model).map_err(PyValueError::new_err)?;
+    let patient = Patient {
+        sex: Sex::parse(&sex).map_err(PyValueError::new_err)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    };
+    match model.predict(&patient) {
+        Ok(value) => Ok(value),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+#[pyfunction]
+pub fn calculate_model_risk_rust_parallel_np(
+    py: Python,
+    model: String,
+    data: PyReadonlyArrayDyn<f64>,
+) -> PyResult<PyObject> {
+    let model = find_model(&model).map_err(PyValueError::new_err)?;
+    calculate_risk_rust_parallel_np(py, data, model.as_ref())
+}
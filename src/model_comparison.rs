@@ -0,0 +1,393 @@
+use crate::validation::{bootstrap_intervals, censoring_weighted_outcomes, Observation};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Risks (%) from two models for the same patient, with the observed
+/// outcome: whether the event happened, and the years to the event or to
+/// censoring.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PairedObservation {
+    pub reference: f64,
+    pub comparison: f64,
+    pub event: bool,
+    pub time: f64,
+}
+
+/// Builds paired observations from parallel columns, skipping rows where
+/// either prediction is NaN (as the batch calculators return for invalid
+/// inputs).
+pub fn paired_observations(
+    reference: &[f64],
+    comparison: &[f64],
+    events: &[bool],
+    times: &[f64],
+) -> Result<Vec<PairedObservation>, String> {
+    let n = reference.len();
+    if comparison.len() != n || events.len() != n || times.len() != n {
+        return Err("Predictions, events and times must have the same length.".to_string());
+    }
+    let mut result = Vec::with_capacity(n);
+    for i in 0..n {
+        if reference[i].is_nan() || comparison[i].is_nan() {
+            continue;
+        }
+        if !(0.0..=100.0).contains(&reference[i]) || !(0.0..=100.0).contains(&comparison[i]) {
+            return Err(format!(
+                "Predicted risk in row {} is not a percentage.",
+                i + 1
+            ));
+        }
+        if !(times[i].is_finite() && times[i] >= 0.0) {
+            return Err(format!("Follow-up time {} must be non-negative.", times[i]));
+        }
+        result.push(PairedObservation {
+            reference: reference[i],
+            comparison: comparison[i],
+            event: events[i],
+            time: times[i],
+        });
+    }
+    Ok(result)
+}
+
+/// Improvement of the comparison model over the reference model. Event and
+/// non-event shares are weighted by the inverse probability of censoring, so
+/// patients censored before the horizon only count through the weights.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReclassificationMetrics {
+    /// Share of events moving to a higher category minus the share moving
+    /// lower.
+    pub categorical_nri_events: f64,
+    /// Share of non-events moving to a lower category minus the share moving
+    /// higher.
+    pub categorical_nri_non_events: f64,
+    pub categorical_nri: f64,
+    /// As the categorical NRI, with any increase or decrease in risk counted
+    /// as a move.
+    pub continuous_nri_events: f64,
+    pub continuous_nri_non_events: f64,
+    pub continuous_nri: f64,
+    /// Increase in mean predicted probability among events minus that among
+    /// non-events.
+    pub idi: f64,
+}
+
+/// Names of the fields of `ReclassificationMetrics`, in order.
+pub const RECLASSIFICATION_METRICS: [&str; 7] = [
+    "categorical_nri_events",
+    "categorical_nri_non_events",
+    "categorical_nri",
+    "continuous_nri_events",
+    "continuous_nri_non_events",
+    "continuous_nri",
+    "idi",
+];
+
+impl ReclassificationMetrics {
+    pub fn to_array(self) -> [f64; 7] {
+        [
+            self.categorical_nri_events,
+            self.categorical_nri_non_events,
+            self.categorical_nri,
+            self.continuous_nri_events,
+            self.continuous_nri_non_events,
+            self.continuous_nri,
+            self.idi,
+        ]
+    }
+}
+
+/// Net benefit of treating patients whose risk is at or above `threshold`
+/// (%): true positives minus false positives weighted by the odds of the
+/// threshold, per patient.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NetBenefit {
+    pub threshold: f64,
+    pub reference: f64,
+    pub comparison: f64,
+    /// Treating everyone. Treating no one has a net benefit of 0.
+    pub treat_all: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelComparison {
+    pub n: usize,
+    /// Events up to the horizon.
+    pub events: usize,
+    pub metrics: ReclassificationMetrics,
+    pub net_benefit: Vec<NetBenefit>,
+    /// Bootstrap percentile (lower, upper) intervals of the metrics, in the
+    /// order of `RECLASSIFICATION_METRICS`, then of the reference and
+    /// comparison net benefit at each threshold.
+    pub intervals: Option<Vec<(f64, f64)>>,
+}
+
+fn category(risk: f64, thresholds: &[f64]) -> usize {
+    thresholds.partition_point(|threshold| *threshold <= risk)
+}
+
+/// 1 for a move up from `from` to `to`, -1 for a move down, 0 for none.
+fn direction<T: PartialOrd>(from: T, to: T) -> f64 {
+    if to > from {
+        1.0
+    } else if to < from {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+/// Observations truncated at the horizon, with their outcomes and inverse
+/// probability of censoring weights.
+fn weighted(
+    paired: &[PairedObservation],
+    horizon_years: f64,
+) -> (Vec<Observation>, Vec<f64>, Vec<f64>) {
+    let truncated: Vec<Observation> = paired
+        .iter()
+        .map(|p| {
+            Observation {
+                risk: p.reference,
+                event: p.event,
+                time: p.time,
+            }
+            .truncate(horizon_years)
+        })
+        .collect();
+    let (outcomes, weights) = censoring_weighted_outcomes(&truncated, horizon_years);
+    (truncated, outcomes, weights)
+}
+
+fn statistics(
+    paired: &[PairedObservation],
+    horizon_years: f64,
+    category_thresholds: &[f64],
+    net_benefit_thresholds: &[f64],
+) -> (ReclassificationMetrics, Vec<NetBenefit>) {
+    let (_, outcomes, weights) = weighted(paired, horizon_years);
+    let (mut event_weight, mut non_event_weight) = (0.0, 0.0);
+    // Weighted (up - down) moves and change in probability, by outcome.
+    let mut categorical = [0.0; 2];
+    let mut continuous = [0.0; 2];
+    let mut change = [0.0; 2];
+    for ((p, y), w) in paired.iter().zip(&outcomes).zip(&weights) {
+        let outcome = if *y == 1.0 {
+            event_weight += w;
+            0
+        } else {
+            non_event_weight += w;
+            1
+        };
+        let (from, to) = (
+            category(p.reference, category_thresholds),
+            category(p.comparison, category_thresholds),
+        );
+        categorical[outcome] += w * direction(from, to);
+        continuous[outcome] += w * direction(p.reference, p.comparison);
+        change[outcome] += w * (p.comparison - p.reference) / 100.0;
+    }
+    let categorical_nri_events = categorical[0] / event_weight;
+    let categorical_nri_non_events = -categorical[1] / non_event_weight;
+    let continuous_nri_events = continuous[0] / event_weight;
+    let continuous_nri_non_events = -continuous[1] / non_event_weight;
+    let metrics = ReclassificationMetrics {
+        categorical_nri_events,
+        categorical_nri_non_events,
+        categorical_nri: categorical_nri_events + categorical_nri_non_events,
+        continuous_nri_events,
+        continuous_nri_non_events,
+        continuous_nri: continuous_nri_events + continuous_nri_non_events,
+        idi: change[0] / event_weight - change[1] / non_event_weight,
+    };
+
+    let total_weight = event_weight + non_event_weight;
+    let net_benefit = |risks: &mut dyn Iterator<Item = f64>, threshold: f64| {
+        let odds = threshold / (100.0 - threshold);
+        let (mut true_positives, mut false_positives) = (0.0, 0.0);
+        for ((risk, y), w) in risks.zip(&outcomes).zip(&weights) {
+            if risk >= threshold {
+                true_positives += w * y;
+                false_positives += w * (1.0 - y);
+            }
+        }
+        (true_positives - false_positives * odds) / total_weight
+    };
+    let curve = net_benefit_thresholds
+        .iter()
+        .map(|threshold| NetBenefit {
+            threshold: *threshold,
+            reference: net_benefit(&mut paired.iter().map(|p| p.reference), *threshold),
+            comparison: net_benefit(&mut paired.iter().map(|p| p.comparison), *threshold),
+            treat_all: net_benefit(&mut paired.iter().map(|_| 100.0), *threshold),
+        })
+        .collect();
+    (metrics, curve)
+}
+
+/// Net reclassification, integrated discrimination improvement and decision
+/// curves of the `comparison` model over the `reference` model, for outcomes
+/// at `horizon_years`. Categories are bounded by `category_thresholds` (%),
+/// ascending. With `bootstrap` above 0, percentile intervals at `level` are
+/// computed from that many resamples, drawn in parallel with seeds from
+/// `seed`.
+pub fn compare_models(
+    paired: &[PairedObservation],
+    horizon_years: f64,
+    category_thresholds: &[f64],
+    net_benefit_thresholds: &[f64],
+    bootstrap: usize,
+    level: f64,
+    seed: u64,
+) -> Result<ModelComparison, String> {
+    if paired.len() < 10 {
+        return Err("At least 10 observations with predictions are needed.".to_string());
+    }
+    if !(horizon_years.is_finite() && horizon_years > 0.0) {
+        return Err("Horizon must be a positive number of years.".to_string());
+    }
+    if !(level > 0.0 && level < 1.0) {
+        return Err("Confidence level must be between 0 and 1.".to_string());
+    }
+    if category_thresholds.is_empty()
+        || category_thresholds
+            .windows(2)
+            .any(|pair| pair[0] >= pair[1])
+        || category_thresholds
+            .iter()
+            .any(|t| !(0.0..=100.0).contains(t))
+    {
+        return Err("Category thresholds must be increasing percentages.".to_string());
+    }
+    if net_benefit_thresholds
+        .iter()
+        .any(|t| !(*t > 0.0 && *t < 100.0))
+    {
+        return Err("Net benefit thresholds must be between 0 and 100.".to_string());
+    }
+    let (truncated, _, _) = weighted(paired, horizon_years);
+    let events = truncated.iter().filter(|o| o.event).count();
+    if events == 0 || events == paired.len() {
+        return Err("Both events and non-events up to the horizon are needed.".to_string());
+    }
+
+    let flatten = |(metrics, curve): (ReclassificationMetrics, Vec<NetBenefit>)| {
+        let mut values = metrics.to_array().to_vec();
+        for point in curve {
+            values.extend([point.reference, point.comparison]);
+        }
+        values
+    };
+    let intervals = (bootstrap > 0).then(|| {
+        bootstrap_intervals(paired, bootstrap, level, seed, |sample| {
+            flatten(statistics(
+                sample,
+                horizon_years,
+                category_thresholds,
+                net_benefit_thresholds,
+            ))
+        })
+    });
+
+    let (metrics, net_benefit) = statistics(
+        paired,
+        horizon_years,
+        category_thresholds,
+        net_benefit_thresholds,
+    );
+    Ok(ModelComparison {
+        n: paired.len(),
+        events,
+        metrics,
+        net_benefit,
+        intervals,
+    })
+}
+
+/// (n, events, metric names, (7, 3) metrics, (k, 8) decision curve).
+type ComparisonSummary = (usize, usize, Vec<&'static str>, PyObject, PyObject);
+
+/// Returns the sample size and event count, the metric names, a (7, 3)
+/// array of estimates with lower and upper bounds, and a (k, 8) array with
+/// one row per net benefit threshold and columns threshold, reference,
+/// reference lower, reference upper, comparison, comparison lower,
+/// comparison upper and treat all. Bounds are NaN without bootstrap.
+#[pyfunction]
+pub fn compare_models_rust(
+    py: Python,
+    reference: PyReadonlyArrayDyn<f64>,
+    comparison: PyReadonlyArrayDyn<f64>,
+    events: PyReadonlyArrayDyn<bool>,
+    times: PyReadonlyArrayDyn<f64>,
+    horizon_years: f64,
+    category_thresholds: Vec<f64>,
+    net_benefit_thresholds: Vec<f64>,
+    bootstrap: usize,
+    level: f64,
+    seed: u64,
+) -> PyResult<ComparisonSummary> {
+    let column =
+        |array: &PyReadonlyArrayDyn<f64>| array.as_array().iter().copied().collect::<Vec<_>>();
+    let paired = paired_observations(
+        &column(&reference),
+        &column(&comparison),
+        &events.as_array().iter().copied().collect::<Vec<_>>(),
+        &column(&times),
+    )
+    .map_err(PyValueError::new_err)?;
+    let result = compare_models(
+        &paired,
+        horizon_years,
+        &category_thresholds,
+        &net_benefit_thresholds,
+        bootstrap,
+        level,
+        seed,
+    )
+    .map_err(PyValueError::new_err)?; // Convert Rust String error to Python ValueError
+
+    let intervals = result
+        .intervals
+        .unwrap_or_else(|| vec![(f64::NAN, f64::NAN); 7 + 2 * net_benefit_thresholds.len()]);
+    let metrics: Vec<Vec<f64>> = result
+        .metrics
+        .to_array()
+        .iter()
+        .zip(&intervals)
+        .map(|(estimate, (lower, upper))| vec![*estimate, *lower, *upper])
+        .collect();
+    let curve: Vec<Vec<f64>> = result
+        .net_benefit
+        .iter()
+        .zip(intervals[7..].chunks(2))
+        .map(|(point, bounds)| {
+            vec![
+                point.threshold,
+                point.reference,
+                bounds[0].0,
+                bounds[0].1,
+                point.comparison,
+                bounds[1].0,
+                bounds[1].1,
+                point.treat_all,
+            ]
+        })
+        .collect();
+    let to_array = |rows: &[Vec<f64>], columns: usize| -> PyResult<PyObject> {
+        if rows.is_empty() {
+            return Ok(PyArray::from_vec(py, Vec::<f64>::new())
+                .reshape([0, columns])?
+                .to_object(py));
+        }
+        Ok(PyArray::from_vec2(py, rows)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?
+            .to_object(py))
+    };
+    Ok((
+        result.n,
+        result.events,
+        RECLASSIFICATION_METRICS.to_vec(),
+        to_array(&metrics, 3)?,
+        to_array(&curve, 8)?,
+    ))
+}
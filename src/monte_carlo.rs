@@ -0,0 +1,263 @@
+use crate::model::{find_model, risk_category, Patient, RiskModel, Sex};
+use crate::utils::{input_ranges, patients, RiskCategory};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, Normal};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Standard deviation of the measurement error of each continuous input, in
+/// the order of `input_ranges`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeasurementSd {
+    pub fields: [f64; 6],
+}
+
+impl Default for MeasurementSd {
+    /// Typical visit-to-visit variability of a single reading: systolic BP
+    /// 8 mmHg, total cholesterol 12 mg/dL, HDL cholesterol 4 mg/dL, BMI 0.5 and
+    /// eGFR 6. Age is taken as exact.
+    fn default() -> MeasurementSd {
+        MeasurementSd {
+            fields: [0.0, 12.0, 4.0, 8.0, 0.5, 6.0],
+        }
+    }
+}
+
+impl MeasurementSd {
+    /// The defaults, with the SDs of any fields in `sds` replaced.
+    pub fn from_map(sds: &HashMap<String, f64>) -> Result<MeasurementSd, String> {
+        let mut result = MeasurementSd::default();
+        for (field, sd) in sds {
+            let index = input_ranges(true)
+                .iter()
+                .position(|range| range.field == field)
+                .ok_or_else(|| format!("Unknown input '{}' in measurement SDs.", field))?;
+            if !(sd.is_finite() && *sd >= 0.0) {
+                return Err(format!(
+                    "Measurement SD for '{}' must be a non-negative number.",
+                    field
+                ));
+            }
+            result.fields[index] = *sd;
+        }
+        Ok(result)
+    }
+}
+
+/// Draws of one input outside the model's range allowed before a sample is
+/// given up on, which only happens with an SD far wider than the range.
+const MAX_DRAWS: usize = 1000;
+
+/// Percentiles of the simulated risk reported in a `MonteCarloSummary`.
+pub const MONTE_CARLO_PERCENTILES: [f64; 5] = [2.5, 25.0, 50.0, 75.0, 97.5];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonteCarloSummary {
+    /// Risk at the measured values.
+    pub risk: f64,
+    pub mean: f64,
+    pub sd: f64,
+    /// Risk at each of `MONTE_CARLO_PERCENTILES`.
+    pub percentiles: [f64; 5],
+    /// Share of samples at or above each of `RiskCategory::THRESHOLDS`, NaN
+    /// for models without risk categories.
+    pub probability_above: [f64; 3],
+    /// Share of samples in a different risk category from `risk`, NaN for
+    /// models without risk categories.
+    pub probability_reclassified: f64,
+}
+
+/// Linear interpolation between order statistics of sorted `values`.
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    let position = percent / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// Scores `samples` copies of `patient` with each continuous input perturbed
+/// by independent normal measurement error, truncated to the model's valid
+/// range: an error that takes a value out of range is drawn again. The same
+/// `seed` always gives the same result.
+pub fn simulate_measurement_error(
+    model: &dyn RiskModel,
+    patient: &Patient,
+    sds: &MeasurementSd,
+    samples: usize,
+    seed: u64,
+) -> Result<MonteCarloSummary, String> {
+    if samples < 2 {
+        return Err("At least 2 samples are needed.".to_string());
+    }
+    let risk = model.predict(patient)?;
+    let model_ranges = model.input_ranges();
+    let errors = input_ranges(true)
+        .iter()
+        .zip(sds.fields)
+        .map(|(range, sd)| {
+            let bounds = model_ranges
+                .iter()
+                .find(|model_range| model_range.field == range.field)
+                .map_or((f64::NEG_INFINITY, f64::INFINITY), |model_range| {
+                    (model_range.min, model_range.max)
+                });
+            Normal::new(0.0, sd)
+                .map(|error| (range.field, error, bounds))
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let mut risks = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let mut sample = patient.clone();
+        for (field, error, (min, max)) in &errors {
+            if let Some(value) = sample.value_mut(field) {
+                let measured = *value;
+                *value = (0..MAX_DRAWS)
+                    .map(|_| measured + error.sample(&mut rng))
+                    .find(|perturbed| (*min..=*max).contains(perturbed))
+                    .ok_or_else(|| {
+                        format!("Measurement SD for '{}' is too wide for its range.", field)
+                    })?;
+            }
+        }
+        risks.push(model.predict(&sample)?);
+    }
+
+    let n = samples as f64;
+    let mean = risks.iter().sum::<f64>() / n;
+    let sd = (risks.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+    let (probability_above, probability_reclassified) = match risk_category(model, risk) {
+        Some(category) => (
+            RiskCategory::THRESHOLDS
+                .map(|threshold| risks.iter().filter(|r| **r >= threshold).count() as f64 / n),
+            risks
+                .iter()
+                .filter(|r| risk_category(model, **r) != Some(category))
+                .count() as f64
+                / n,
+        ),
+        None => ([f64::NAN; 3], f64::NAN),
+    };
+    risks.sort_by(f64::total_cmp);
+
+    Ok(MonteCarloSummary {
+        risk,
+        mean,
+        sd,
+        percentiles: MONTE_CARLO_PERCENTILES.map(|percent| percentile(&risks, percent)),
+        probability_above,
+        probability_reclassified,
+    })
+}
+
+/// Names of the values in a flattened `MonteCarloSummary`, in order.
+pub fn monte_carlo_columns() -> Vec<String> {
+    let percentiles = MONTE_CARLO_PERCENTILES.map(|percent| format!("p{}", percent));
+    let thresholds =
+        RiskCategory::THRESHOLDS.map(|threshold| format!("probability_at_least_{}", threshold));
+    ["risk", "mean", "sd"]
+        .map(String::from)
+        .into_iter()
+        .chain(percentiles)
+        .chain(thresholds)
+        .chain(["probability_reclassified".to_string()])
+        // No decimal points in column names, e.g. p2_5.
+        .map(|column| column.replace('.', "_"))
+        .collect()
+}
+
+impl MonteCarloSummary {
+    fn to_vec(&self) -> Vec<f64> {
+        let mut values = vec![self.risk, self.mean, self.sd];
+        values.extend(self.percentiles);
+        values.extend(self.probability_above);
+        values.push(self.probability_reclassified);
+        values
+    }
+}
+
+#[pyfunction]
+pub fn simulate_measurement_error_rust(
+    model: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    sds: HashMap<String, f64>,
+    samples: usize,
+    seed: u64,
+) -> PyResult<(Vec<String>, Vec<f64>)> {
+    let model = find_model(&model).map_err(PyValueError::new_err)?;
+    let sds = MeasurementSd::from_map(&sds).map_err(PyValueError::new_err)?;
+    let patient = Patient {
+        sex: Sex::parse(&sex).map_err(PyValueError::new_err)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    };
+    match simulate_measurement_error(model.as_ref(), &patient, &sds, samples, seed) {
+        Ok(summary) => Ok((monte_carlo_columns(), summary.to_vec())),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+/// Returns the column names and an (n, columns) array of summaries. Row `i`
+/// is simulated with seed `seed + i`, so results do not depend on the number
+/// of threads. Rows with invalid inputs are NaN.
+#[pyfunction]
+pub fn simulate_measurement_error_rust_parallel_np(
+    py: Python,
+    model: String,
+    data: PyReadonlyArrayDyn<f64>,
+    sds: HashMap<String, f64>,
+    samples: usize,
+    seed: u64,
+) -> PyResult<(Vec<String>, PyObject)> {
+    let model = find_model(&model).map_err(PyValueError::new_err)?;
+    let sds = MeasurementSd::from_map(&sds).map_err(PyValueError::new_err)?;
+    if samples < 2 {
+        return Err(PyValueError::new_err("At least 2 samples are needed."));
+    }
+    let patients = patients(&data)?;
+    let columns = monte_carlo_columns();
+
+    let results: Vec<Vec<f64>> = patients
+        .par_iter()
+        .enumerate()
+        .map(|(i, patient)| {
+            simulate_measurement_error(
+                model.as_ref(),
+                patient,
+                &sds,
+                samples,
+                seed.wrapping_add(i as u64),
+            )
+            .map(|summary| summary.to_vec())
+            .unwrap_or_else(|_| vec![f64::NAN; columns.len()]) // Handle error by returning NaN
+        })
+        .collect();
+
+    let summaries = PyArray::from_vec2(py, &results)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?
+        .to_object(py);
+    Ok((columns, summaries))
+}
@@ -0,0 +1,262 @@
+use crate::model::{find_model, Patient, RiskModel, Sex};
+use crate::utils::patients;
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::fs;
+
+const REFERENCE_HEADER: [&str; 6] = ["model", "sex", "age_min", "age_max", "percentile", "risk"];
+
+/// Quantiles of one model's predicted risk in a reference population of one
+/// sex and age band, covering ages `age_min` up to but not including
+/// `age_max`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReferenceBand {
+    pub model: String,
+    pub sex: Sex,
+    pub age_min: f64,
+    pub age_max: f64,
+    /// (percentile, risk) pairs in increasing order of percentile.
+    pub quantiles: Vec<(f64, f64)>,
+}
+
+impl ReferenceBand {
+    /// The percentile of `risk` in this band, interpolating linearly between
+    /// quantiles. Risks beyond the first or last quantile get that quantile's
+    /// percentile.
+    pub fn percentile(&self, risk: f64) -> f64 {
+        let (first, last) = (self.quantiles[0], self.quantiles[self.quantiles.len() - 1]);
+        if risk <= first.1 {
+            return first.0;
+        }
+        if risk >= last.1 {
+            return last.0;
+        }
+        for pair in self.quantiles.windows(2) {
+            let ((p0, r0), (p1, r1)) = (pair[0], pair[1]);
+            if risk < r1 {
+                return p0 + (p1 - p0) * (risk - r0) / (r1 - r0);
+            }
+        }
+        last.0
+    }
+}
+
+/// Reference distributions of predicted risk, by model, sex and age band.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReferencePopulation {
+    pub bands: Vec<ReferenceBand>,
+}
+
+impl ReferencePopulation {
+    /// Parses a CSV file with the header `model,sex,age_min,age_max,percentile,risk`
+    /// and one row per quantile, e.g. `10_yr_cvd,female,40,45,50,1.2`. Models
+    /// are keys from `models()`; percentiles are between 0 and 100.
+    pub fn from_csv(csv: &str) -> Result<ReferencePopulation, String> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let header: Vec<_> = lines
+            .next()
+            .ok_or("Reference population file is empty")?
+            .split(',')
+            .map(str::trim)
+            .collect();
+        if header != REFERENCE_HEADER {
+            return Err(format!(
+                "Reference population header must be '{}'",
+                REFERENCE_HEADER.join(",")
+            ));
+        }
+
+        let mut population = ReferencePopulation::default();
+        for (i, line) in lines.enumerate() {
+            let row_error =
+                |message: &str| format!("Reference population row {}: {}", i + 1, message);
+            let fields: Vec<_> = line.split(',').map(str::trim).collect();
+            if fields.len() != REFERENCE_HEADER.len() {
+                return Err(row_error("expected 6 fields"));
+            }
+            let model = find_model(fields[0])
+                .map_err(|e| row_error(&e))?
+                .metadata()
+                .key;
+            let sex = Sex::parse(fields[1]).map_err(|e| row_error(&e))?;
+            let mut numbers = [0.0; 4];
+            for (number, field) in numbers.iter_mut().zip(&fields[2..]) {
+                *number = field
+                    .parse::<f64>()
+                    .map_err(|_| row_error(&format!("'{}' is not a number", field)))?;
+            }
+            let [age_min, age_max, percentile, risk] = numbers;
+            if age_min.partial_cmp(&age_max) != Some(Ordering::Less)
+                || !(0.0..=100.0).contains(&percentile)
+                || risk.is_nan()
+            {
+                return Err(row_error(
+                    "age_min must be below age_max and percentile between 0 and 100",
+                ));
+            }
+
+            match population.bands.iter_mut().find(|band| {
+                band.model == model
+                    && band.sex == sex
+                    && band.age_min == age_min
+                    && band.age_max == age_max
+            }) {
+                Some(band) => band.quantiles.push((percentile, risk)),
+                None => population.bands.push(ReferenceBand {
+                    model: model.to_string(),
+                    sex,
+                    age_min,
+                    age_max,
+                    quantiles: vec![(percentile, risk)],
+                }),
+            }
+        }
+
+        for band in &mut population.bands {
+            band.quantiles.sort_by(|a, b| a.0.total_cmp(&b.0));
+            if band
+                .quantiles
+                .windows(2)
+                .any(|pair| pair[0].0 == pair[1].0 || pair[0].1 > pair[1].1)
+            {
+                return Err(format!(
+                    "Reference population for {} {} aged {} to {} must have distinct \
+                     percentiles with non-decreasing risks",
+                    band.model,
+                    band.sex.as_str(),
+                    band.age_min,
+                    band.age_max
+                ));
+            }
+        }
+        Ok(population)
+    }
+
+    pub fn load(path: &str) -> Result<ReferencePopulation, String> {
+        let csv = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read reference population '{}': {}", path, e))?;
+        ReferencePopulation::from_csv(&csv)
+    }
+
+    pub fn band(&self, model: &str, sex: Sex, age: f64) -> Option<&ReferenceBand> {
+        self.bands.iter().find(|band| {
+            band.model == model && band.sex == sex && (band.age_min..band.age_max).contains(&age)
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PeerComparison {
+    pub risk: f64,
+    /// Risk of a patient of the same sex and age with optimal risk factors
+    /// (`Patient::optimal`).
+    pub optimal_risk: f64,
+    /// Percentile among the reference population of the same sex and age
+    /// band, if one was given and covers the patient.
+    pub percentile: Option<f64>,
+}
+
+pub fn compare_to_peers(
+    model: &dyn RiskModel,
+    patient: &Patient,
+    population: Option<&ReferencePopulation>,
+) -> Result<PeerComparison, String> {
+    let risk = model.predict(patient)?;
+    let optimal_risk = model.predict(&Patient::optimal(patient.sex, patient.age))?;
+    let percentile = population
+        .and_then(|population| population.band(model.metadata().key, patient.sex, patient.age))
+        .map(|band| band.percentile(risk));
+    Ok(PeerComparison {
+        risk,
+        optimal_risk,
+        percentile,
+    })
+}
+
+fn load_population(path: Option<String>) -> PyResult<Option<ReferencePopulation>> {
+    path.map(|path| ReferencePopulation::load(&path))
+        .transpose()
+        .map_err(PyValueError::new_err)
+}
+
+#[pyfunction]
+pub fn compare_to_peers_rust(
+    model: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    reference_path: Option<String>,
+) -> PyResult<(f64, f64, Option<f64>)> {
+    let model = find_model(&model).map_err(PyValueError::new_err)?;
+    let population = load_population(reference_path)?;
+    let patient = Patient {
+        sex: Sex::parse(&sex).map_err(PyValueError::new_err)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    };
+    match compare_to_peers(model.as_ref(), &patient, population.as_ref()) {
+        Ok(comparison) => Ok((
+            comparison.risk,
+            comparison.optimal_risk,
+            comparison.percentile,
+        )),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+/// Returns arrays of risks, optimal risks and percentiles, with NaN for
+/// invalid rows and for percentiles without a matching reference band.
+#[pyfunction]
+pub fn compare_to_peers_rust_parallel_np(
+    py: Python,
+    model: String,
+    data: PyReadonlyArrayDyn<f64>,
+    reference_path: Option<String>,
+) -> PyResult<(PyObject, PyObject, PyObject)> {
+    let model = find_model(&model).map_err(PyValueError::new_err)?;
+    let population = load_population(reference_path)?;
+    let patients = patients(&data)?;
+
+    let results: Vec<(f64, f64, f64)> = patients
+        .par_iter()
+        .map(
+            |patient| match compare_to_peers(model.as_ref(), patient, population.as_ref()) {
+                Ok(comparison) => (
+                    comparison.risk,
+                    comparison.optimal_risk,
+                    comparison.percentile.unwrap_or(f64::NAN),
+                ),
+                Err(_) => (f64::NAN, f64::NAN, f64::NAN), // Handle error by returning NaN
+            },
+        )
+        .collect();
+
+    let risks: Vec<f64> = results.iter().map(|result| result.0).collect();
+    let optimal_risks: Vec<f64> = results.iter().map(|result| result.1).collect();
+    let percentiles: Vec<f64> = results.iter().map(|result| result.2).collect();
+
+    Ok((
+        PyArray::from_vec(py, risks).to_object(py),
+        PyArray::from_vec(py, optimal_risks).to_object(py),
+        PyArray::from_vec(py, percentiles).to_object(py),
+    ))
+}
@@ -0,0 +1,146 @@
+use crate::utils::patients;
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// Combinations of inputs that pass `validate_input` field by field but are
+/// unlikely to be correct together. These are warnings: the risk can still be
+/// calculated, but the record should be checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlausibilityWarning {
+    HdlNotBelowTotalCholesterol,
+    NonHdlCholesterolOutOfRange,
+    HighNonHdlCholesterolOnStatin,
+    AgeAboveThirtyYearRange,
+}
+
+/// Non-HDL cholesterol (mg/dL) outside this range is very unlikely in a
+/// real measurement.
+const PLAUSIBLE_NON_HDL_CHOLESTEROL: (f64, f64) = (30.0, 270.0);
+/// Non-HDL cholesterol (mg/dL) at or above which a patient recorded as taking
+/// cholesterol lowering medication is unlikely to be treated.
+const HIGH_NON_HDL_CHOLESTEROL_ON_STATIN: f64 = 220.0;
+
+impl PlausibilityWarning {
+    pub const ALL: [PlausibilityWarning; 4] = [
+        PlausibilityWarning::HdlNotBelowTotalCholesterol,
+        PlausibilityWarning::NonHdlCholesterolOutOfRange,
+        PlausibilityWarning::HighNonHdlCholesterolOnStatin,
+        PlausibilityWarning::AgeAboveThirtyYearRange,
+    ];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            PlausibilityWarning::HdlNotBelowTotalCholesterol => "hdl_not_below_total_cholesterol",
+            PlausibilityWarning::NonHdlCholesterolOutOfRange => "non_hdl_cholesterol_out_of_range",
+            PlausibilityWarning::HighNonHdlCholesterolOnStatin => {
+                "high_non_hdl_cholesterol_on_statin"
+            }
+            PlausibilityWarning::AgeAboveThirtyYearRange => "age_above_30_yr_range",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            PlausibilityWarning::HdlNotBelowTotalCholesterol => {
+                "HDL cholesterol should be less than total cholesterol"
+            }
+            PlausibilityWarning::NonHdlCholesterolOutOfRange => {
+                "Non-HDL cholesterol should be between 30 and 270"
+            }
+            PlausibilityWarning::HighNonHdlCholesterolOnStatin => {
+                "Non-HDL cholesterol of 220 or more is unusual on cholesterol medication"
+            }
+            PlausibilityWarning::AgeAboveThirtyYearRange => {
+                "Age is above 59, so 30 year risks cannot be calculated for this patient"
+            }
+        }
+    }
+
+    /// This warning's bit in a mask: bit `i` is `ALL[i]`.
+    fn bit(&self) -> u32 {
+        let position = PlausibilityWarning::ALL
+            .iter()
+            .position(|warning| warning == self)
+            .expect("every warning is listed in ALL");
+        1 << position
+    }
+}
+
+/// Checks a patient's inputs. `thirty_year` is whether a 30-year model will
+/// be run, the only case where an age above 59 matters.
+pub fn check_plausibility(
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    on_cholesterol_meds: bool,
+    thirty_year: bool,
+) -> Vec<PlausibilityWarning> {
+    let mut warnings = Vec::new();
+    let non_hdl_cholesterol = total_cholesterol - hdl_cholesterol;
+    if hdl_cholesterol >= total_cholesterol {
+        warnings.push(PlausibilityWarning::HdlNotBelowTotalCholesterol);
+    }
+    let (min_non_hdl, max_non_hdl) = PLAUSIBLE_NON_HDL_CHOLESTEROL;
+    if !(min_non_hdl..=max_non_hdl).contains(&non_hdl_cholesterol) {
+        warnings.push(PlausibilityWarning::NonHdlCholesterolOutOfRange);
+    }
+    if on_cholesterol_meds && non_hdl_cholesterol >= HIGH_NON_HDL_CHOLESTEROL_ON_STATIN {
+        warnings.push(PlausibilityWarning::HighNonHdlCholesterolOnStatin);
+    }
+    if thirty_year && age > 59.0 {
+        warnings.push(PlausibilityWarning::AgeAboveThirtyYearRange);
+    }
+    warnings
+}
+
+/// Packs warnings into a bit mask, where bit `i` is `PlausibilityWarning::ALL[i]`.
+pub fn warnings_to_bits(warnings: &[PlausibilityWarning]) -> u32 {
+    warnings
+        .iter()
+        .fold(0, |bits, warning| bits | warning.bit())
+}
+
+#[pyfunction]
+pub fn check_plausibility_rust(
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    on_cholesterol_meds: bool,
+    thirty_year: bool,
+) -> Vec<(&'static str, &'static str)> {
+    check_plausibility(
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        on_cholesterol_meds,
+        thirty_year,
+    )
+    .iter()
+    .map(|warning| (warning.code(), warning.message()))
+    .collect()
+}
+
+#[pyfunction]
+pub fn check_plausibility_rust_parallel_np(
+    py: Python,
+    data: PyReadonlyArrayDyn<f64>,
+    thirty_year: bool,
+) -> PyResult<PyObject> {
+    let patients = patients(&data)?;
+
+    let results: Vec<u32> = patients
+        .into_par_iter()
+        .map(|patient| {
+            warnings_to_bits(&check_plausibility(
+                patient.age,
+                patient.total_cholesterol,
+                patient.hdl_cholesterol,
+                patient.on_cholesterol_meds,
+                thirty_year,
+            ))
+        })
+        .collect();
+
+    Ok(PyArray::from_vec(py, results).to_object(py))
+}
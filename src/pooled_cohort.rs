@@ -0,0 +1,215 @@
+use crate::covariates::PooledCohortCoefficients;
+use crate::utils::{input_ranges, patients, RiskCategory};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// The Pooled Cohort Equations were derived on ages 40 to 79; the remaining
+/// continuous inputs share their ranges with PREVENT.
+pub fn validate_pce_input(
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+) -> Result<(), String> {
+    if !(40.0..=79.0).contains(&age) {
+        return Err("Age must be between 40 and 79 for the Pooled Cohort Equations".to_string());
+    }
+    let ranges = input_ranges(true);
+    for (value, range) in [total_cholesterol, hdl_cholesterol, systolic_bp]
+        .iter()
+        .zip(&ranges[1..4])
+    {
+        if !(range.min..=range.max).contains(value) {
+            return Err(range.message.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Race groups with their own Pooled Cohort Equations. Other races use the
+/// white equations, as recommended by the 2013 guideline.
+fn is_african_american(race: &str) -> Result<bool, String> {
+    match race.to_lowercase().as_str() {
+        "white" | "other" => Ok(false),
+        "black" | "african american" | "african_american" => Ok(true),
+        _ => {
+            Err("Race must be one of 'white', 'african american', 'black' or 'other'.".to_string())
+        }
+    }
+}
+
+pub fn calculate_10_yr_pce_ascvd_risk(
+    sex: &str,
+    race: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    on_htn_meds: bool,
+) -> Result<f64, String> {
+    validate_pce_input(age, total_cholesterol, hdl_cholesterol, systolic_bp)?;
+
+    let coefficients = match (sex.to_lowercase().as_str(), is_african_american(race)?) {
+        ("female", false) => PooledCohortCoefficients::white_female(),
+        ("female", true) => PooledCohortCoefficients::african_american_female(),
+        ("male", false) => PooledCohortCoefficients::white_male(),
+        ("male", true) => PooledCohortCoefficients::african_american_male(),
+        _ => return Err("Sex must be either 'male' or 'female'.".to_string()),
+    };
+
+    let ln_age = age.ln();
+    let ln_total_cholesterol = total_cholesterol.ln();
+    let ln_hdl_cholesterol = hdl_cholesterol.ln();
+    let ln_systolic_bp = systolic_bp.ln();
+
+    let systolic_bp_term = if on_htn_meds {
+        coefficients.ln_treated_systolic_bp * ln_systolic_bp
+            + coefficients.ln_age_ln_treated_systolic_bp * ln_age * ln_systolic_bp
+    } else {
+        coefficients.ln_untreated_systolic_bp * ln_systolic_bp
+            + coefficients.ln_age_ln_untreated_systolic_bp * ln_age * ln_systolic_bp
+    };
+    let smoker_term = if current_smoker {
+        coefficients.smoker + coefficients.ln_age_smoker * ln_age
+    } else {
+        0.0
+    };
+    let diabetes_term = if has_diabetes {
+        coefficients.diabetes
+    } else {
+        0.0
+    };
+
+    let individual_sum: f64 = [
+        coefficients.ln_age * ln_age,
+        coefficients.ln_age_squared * ln_age.powi(2),
+        coefficients.ln_total_cholesterol * ln_total_cholesterol,
+        coefficients.ln_age_ln_total_cholesterol * ln_age * ln_total_cholesterol,
+        coefficients.ln_hdl_cholesterol * ln_hdl_cholesterol,
+        coefficients.ln_age_ln_hdl_cholesterol * ln_age * ln_hdl_cholesterol,
+        systolic_bp_term,
+        smoker_term,
+        diabetes_term,
+    ]
+    .iter()
+    .sum();
+
+    let risk = 1.0
+        - coefficients
+            .baseline_survival
+            .powf((individual_sum - coefficients.mean_sum).exp());
+    Ok(risk * 100.0)
+}
+
+/// Cross-tabulates the risk categories of two sets of 10-year predictions for
+/// the same patients. Rows are the `reference` model's category and columns
+/// the `comparison` model's, both in `RiskCategory::ALL` order. Patients with
+/// a NaN prediction from either model are left out.
+pub fn reclassification_table(
+    reference: &[f64],
+    comparison: &[f64],
+) -> Result<[[usize; 4]; 4], String> {
+    if reference.len() != comparison.len() {
+        return Err("Both sets of predictions must be for the same patients".to_string());
+    }
+    let mut table = [[0; 4]; 4];
+    for (&reference, &comparison) in reference.iter().zip(comparison) {
+        if let (Some(from), Some(to)) = (
+            RiskCategory::from_10_yr_risk(reference),
+            RiskCategory::from_10_yr_risk(comparison),
+        ) {
+            table[from.index()][to.index()] += 1;
+        }
+    }
+    Ok(table)
+}
+
+#[pyfunction]
+pub fn calculate_10_yr_pce_ascvd_rust(
+    sex: String,
+    race: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    on_htn_meds: bool,
+) -> PyResult<f64> {
+    match calculate_10_yr_pce_ascvd_risk(
+        &sex,
+        &race,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        on_htn_meds,
+    ) {
+        Ok(value) => Ok(value),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+/// Scores the same (n, 11) array as the PREVENT batch functions, with a
+/// separate race column where 1 is African American and 0 is white or other.
+#[pyfunction]
+pub fn calculate_10_yr_pce_ascvd_rust_parallel_np(
+    py: Python,
+    data: PyReadonlyArrayDyn<f64>,
+    race: PyReadonlyArrayDyn<f64>,
+) -> PyResult<PyObject> {
+    let patients = patients(&data)?;
+    let race = race.as_array();
+    if race.ndim() != 1 || race.len() != patients.len() {
+        return Err(PyValueError::new_err(
+            "Race must be a 1 dimensional array with one value per row",
+        ));
+    }
+    let races: Vec<&str> = race
+        .iter()
+        .map(|&code| if code == 1.0 { "black" } else { "white" })
+        .collect();
+
+    let results: Vec<_> = patients
+        .into_par_iter()
+        .zip(races)
+        .map(|(patient, race)| {
+            calculate_10_yr_pce_ascvd_risk(
+                patient.sex.as_str(),
+                race,
+                patient.age,
+                patient.total_cholesterol,
+                patient.hdl_cholesterol,
+                patient.systolic_bp,
+                patient.has_diabetes,
+                patient.current_smoker,
+                patient.on_htn_meds,
+            )
+            .unwrap_or(f64::NAN) // Handle error by returning NaN
+        })
+        .collect();
+
+    Ok(PyArray::from_vec(py, results).to_object(py))
+}
+
+#[pyfunction]
+pub fn reclassification_table_rust(
+    reference: PyReadonlyArrayDyn<f64>,
+    comparison: PyReadonlyArrayDyn<f64>,
+) -> PyResult<(Vec<&'static str>, Vec<Vec<usize>>)> {
+    let reference: Vec<f64> = reference.as_array().iter().copied().collect();
+    let comparison: Vec<f64> = comparison.as_array().iter().copied().collect();
+    match reclassification_table(&reference, &comparison) {
+        Ok(table) => Ok((
+            RiskCategory::ALL.iter().map(RiskCategory::label).collect(),
+            table.iter().map(|row| row.to_vec()).collect(),
+        )),
+        Err(e) => Err(PyValueError::new_err(e)),
+    }
+}
@@ -0,0 +1,215 @@
+use crate::model::PreventModel;
+use crate::utils::{input_ranges, patients, risk_from_log_odds};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// What to do with an input that falls outside the range the PREVENT
+/// equations were derived on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutOfRange {
+    /// Return the validation error (the behaviour of `validate_input`).
+    #[default]
+    Reject,
+    /// Replace the value with the nearest bound of the valid range.
+    Clamp,
+    /// Use the value as given, extending the equations beyond their range.
+    Extrapolate,
+}
+
+impl OutOfRange {
+    fn parse(policy: &str) -> Result<OutOfRange, String> {
+        match policy.to_lowercase().as_str() {
+            "reject" => Ok(OutOfRange::Reject),
+            "clamp" => Ok(OutOfRange::Clamp),
+            "extrapolate" => Ok(OutOfRange::Extrapolate),
+            _ => Err(format!(
+                "Out of range policy must be 'reject', 'clamp' or 'extrapolate', not '{}'.",
+                policy
+            )),
+        }
+    }
+}
+
+/// Out of range handling for each continuous input, in the order of
+/// `input_ranges`. Fields that are not mentioned are rejected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RangePolicy {
+    pub fields: [OutOfRange; 6],
+}
+
+impl RangePolicy {
+    pub fn from_map(policy: &HashMap<String, String>) -> Result<RangePolicy, String> {
+        let mut range_policy = RangePolicy::default();
+        for (field, value) in policy {
+            let index = input_ranges(true)
+                .iter()
+                .position(|range| range.field == field)
+                .ok_or_else(|| format!("Unknown input '{}' in out of range policy.", field))?;
+            range_policy.fields[index] = OutOfRange::parse(value)?;
+        }
+        Ok(range_policy)
+    }
+}
+
+/// Applies `policy` to the continuous inputs (age, total cholesterol, HDL
+/// cholesterol, systolic BP, BMI, eGFR). Returns the values to score with and a
+/// bit mask of the fields that were clamped or extrapolated, where bit `i`
+/// corresponds to field `i`.
+pub fn apply_range_policy(
+    policy: &RangePolicy,
+    values: [f64; 6],
+    ten_year: bool,
+) -> Result<([f64; 6], u32), String> {
+    let mut adjusted_values = values;
+    let mut adjusted = 0;
+    for (i, range) in input_ranges(ten_year).iter().enumerate() {
+        let value = values[i];
+        if value.is_nan() {
+            return Err(range.message.to_string());
+        }
+        if (range.min..=range.max).contains(&value) {
+            continue;
+        }
+        match policy.fields[i] {
+            OutOfRange::Reject => return Err(range.message.to_string()),
+            OutOfRange::Clamp => adjusted_values[i] = value.clamp(range.min, range.max),
+            OutOfRange::Extrapolate => {}
+        }
+        adjusted |= 1 << i;
+    }
+    Ok((adjusted_values, adjusted))
+}
+
+/// Names of the fields set in an adjusted-field bit mask.
+pub fn adjusted_fields(adjusted: u32) -> Vec<&'static str> {
+    input_ranges(true)
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| adjusted & (1 << i) != 0)
+        .map(|(_, range)| range.field)
+        .collect()
+}
+
+pub fn calculate_risk_with_policy(
+    outcome: &str,
+    policy: &RangePolicy,
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+) -> Result<(f64, u32), String> {
+    let model = PreventModel::from_key(outcome)?;
+    let ([age, total_cholesterol, hdl_cholesterol, systolic_bp, bmi, egfr], adjusted) =
+        apply_range_policy(
+            policy,
+            [
+                age,
+                total_cholesterol,
+                hdl_cholesterol,
+                systolic_bp,
+                bmi,
+                egfr,
+            ],
+            model.is_ten_year(),
+        )?;
+    let log_odds = model.unchecked_log_odds_fn()(
+        sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    )?;
+    Ok((risk_from_log_odds(log_odds), adjusted))
+}
+
+#[pyfunction]
+pub fn calculate_risk_with_policy_rust(
+    outcome: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    policy: HashMap<String, String>,
+) -> PyResult<(f64, Vec<&'static str>)> {
+    let policy = RangePolicy::from_map(&policy).map_err(PyValueError::new_err)?;
+    match calculate_risk_with_policy(
+        &outcome,
+        &policy,
+        &sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    ) {
+        Ok((risk, adjusted)) => Ok((risk, adjusted_fields(adjusted))),
+        Err(e) => Err(PyValueError::new_err(e)),
+    }
+}
+
+#[pyfunction]
+pub fn calculate_risk_with_policy_rust_parallel_np(
+    py: Python,
+    outcome: String,
+    data: PyReadonlyArrayDyn<f64>,
+    policy: HashMap<String, String>,
+) -> PyResult<(PyObject, PyObject)> {
+    let policy = RangePolicy::from_map(&policy).map_err(PyValueError::new_err)?;
+    PreventModel::from_key(&outcome).map_err(PyValueError::new_err)?;
+    let patients = patients(&data)?;
+
+    let (risks, adjusted): (Vec<f64>, Vec<u32>) = patients
+        .into_par_iter()
+        .map(|patient| {
+            calculate_risk_with_policy(
+                &outcome,
+                &policy,
+                patient.sex.as_str(),
+                patient.age,
+                patient.total_cholesterol,
+                patient.hdl_cholesterol,
+                patient.systolic_bp,
+                patient.has_diabetes,
+                patient.current_smoker,
+                patient.bmi,
+                patient.egfr,
+                patient.on_htn_meds,
+                patient.on_cholesterol_meds,
+            )
+            .unwrap_or((f64::NAN, 0)) // Handle error by returning NaN
+        })
+        .unzip();
+
+    Ok((
+        PyArray::from_vec(py, risks).to_object(py),
+        PyArray::from_vec(py, adjusted).to_object(py),
+    ))
+}
@@ -0,0 +1,329 @@
+use crate::model::{find_model, models, risk_category, Patient, RiskModel, Sex, PATIENT_FIELDS};
+use rayon::prelude::*;
+use serde_json::{json, Map, Value};
+
+/// Largest number of patients accepted in one batch request.
+pub const MAX_BATCH_SIZE: usize = 10_000;
+
+/// A patient from a JSON object keyed by `PATIENT_FIELDS`, with the fields it
+/// gave. Sex is "male" or "female", flags are booleans or 0/1 and the rest
+/// are numbers in the units of the batch input array. Fields left out take
+/// the values of `Patient::optimal`, and only models that need them fail.
+pub fn patient_from_json(value: &Value) -> Result<(Patient, Vec<&'static str>), String> {
+    let object = value.as_object().ok_or("Patient must be a JSON object.")?;
+    if let Some(unknown) = object
+        .keys()
+        .find(|key| !PATIENT_FIELDS.contains(&key.as_str()))
+    {
+        return Err(format!("Unknown patient field '{}'.", unknown));
+    }
+
+    let mut patient = Patient::optimal(Sex::Female, 0.0);
+    let mut given = Vec::new();
+    for field in PATIENT_FIELDS {
+        let Some(value) = object.get(field) else {
+            continue;
+        };
+        match field {
+            "sex" => {
+                patient.sex = Sex::parse(value.as_str().unwrap_or_default())?;
+            }
+            "has_diabetes" | "current_smoker" | "on_htn_meds" | "on_cholesterol_meds" => {
+                let flag = match value {
+                    Value::Bool(flag) => *flag,
+                    value if value.as_f64() == Some(0.0) => false,
+                    value if value.as_f64() == Some(1.0) => true,
+                    _ => return Err(format!("'{}' must be true, false, 0 or 1.", field)),
+                };
+                match field {
+                    "has_diabetes" => patient.has_diabetes = flag,
+                    "current_smoker" => patient.current_smoker = flag,
+                    "on_htn_meds" => patient.on_htn_meds = flag,
+                    _ => patient.on_cholesterol_meds = flag,
+                }
+            }
+            field => {
+                let number = value
+                    .as_f64()
+                    .ok_or_else(|| format!("'{}' must be a number.", field))?;
+                if let Some(slot) = patient.value_mut(field) {
+                    *slot = number;
+                }
+            }
+        }
+        given.push(field);
+    }
+    Ok((patient, given))
+}
+
+/// The models named in a request's `models` array, or every model.
+fn selected_models(request: &Value) -> Result<Vec<Box<dyn RiskModel>>, String> {
+    match &request["models"] {
+        Value::Null => Ok(models()),
+        Value::Array(keys) => keys
+            .iter()
+            .map(|key| find_model(key.as_str().ok_or("Model keys must be strings.")?))
+            .collect(),
+        _ => Err("'models' must be an array of model keys.".to_string()),
+    }
+}
+
+/// One model's result for a patient: the risk (%) and, for 10-year PREVENT
+/// models, its category, or the validation error.
+fn score(model: &dyn RiskModel, patient: &Patient, given: &[&str]) -> Value {
+    let metadata = model.metadata();
+    let missing: Vec<&str> = model
+        .required_inputs()
+        .iter()
+        .filter(|field| !given.contains(field))
+        .copied()
+        .collect();
+    let prediction = if missing.is_empty() {
+        model.predict(patient)
+    } else {
+        Err(format!("Missing inputs: {}.", missing.join(", ")))
+    };
+    let (risk, category, error) = match prediction {
+        Ok(risk) => (
+            Some(risk),
+            risk_category(model, risk).map(|category| category.label()),
+            None,
+        ),
+        Err(e) => (None, None, Some(e)),
+    };
+    json!({
+        "model": metadata.key,
+        "name": metadata.name,
+        "version": metadata.version,
+        "horizon_years": metadata.horizon_years,
+        "outcome": metadata.outcome,
+        "risk": risk,
+        "category": category,
+        "error": error,
+    })
+}
+
+fn score_patient(patient: &Value, models: &[Box<dyn RiskModel>]) -> Value {
+    match patient_from_json(patient) {
+        Ok((patient, given)) => {
+            let results: Vec<Value> = models
+                .iter()
+                .map(|model| score(model.as_ref(), &patient, &given))
+                .collect();
+            json!({ "results": results })
+        }
+        Err(e) => json!({ "results": [], "error": e }),
+    }
+}
+
+fn model_list() -> Value {
+    let models: Vec<Value> = models()
+        .iter()
+        .map(|model| {
+            let metadata = model.metadata();
+            let ranges: Map<String, Value> = model
+                .input_ranges()
+                .iter()
+                .map(|range| {
+                    (
+                        range.field.to_string(),
+                        json!({"min": range.min, "max": range.max}),
+                    )
+                })
+                .collect();
+            json!({
+                "model": metadata.key,
+                "name": metadata.name,
+                "version": metadata.version,
+                "horizon_years": metadata.horizon_years,
+                "outcome": metadata.outcome,
+                "required_inputs": model.required_inputs(),
+                "input_ranges": ranges,
+            })
+        })
+        .collect();
+    json!({ "models": models })
+}
+
+/// The OpenAPI 3 description of the service.
+pub fn openapi() -> Value {
+    let patient_properties: Map<String, Value> = PATIENT_FIELDS
+        .iter()
+        .map(|field| {
+            let schema = match *field {
+                "sex" => json!({"type": "string", "enum": ["male", "female"]}),
+                "has_diabetes" | "current_smoker" | "on_htn_meds" | "on_cholesterol_meds" => {
+                    json!({"type": "boolean"})
+                }
+                _ => json!({"type": "number"}),
+            };
+            (field.to_string(), schema)
+        })
+        .collect();
+    let keys: Vec<&str> = models().iter().map(|model| model.metadata().key).collect();
+    let error = json!({"$ref": "#/components/schemas/Error"});
+    let body = |schema: &str| {
+        let schema = json!({ "$ref": format!("#/components/schemas/{}", schema) });
+        json!({"required": true, "content": {"application/json": {"schema": schema}}})
+    };
+    let response = |description: &str, schema: &Value| {
+        let content = json!({"application/json": {"schema": schema}});
+        json!({"description": description, "content": content})
+    };
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "PyPREVENT scoring service",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Risk (%) from the AHA PREVENT and ESC SCORE2 equations. Inputs use \
+                mg/dL for cholesterol, mmHg for systolic blood pressure, kg/m2 for BMI and \
+                mL/min/1.73 m2 for eGFR.",
+        },
+        "paths": {
+            "/health": {"get": {
+                "summary": "Liveness check",
+                "responses": {"200": response("The service is up.", &json!({"$ref": "#/components/schemas/Health"}))},
+            }},
+            "/models": {"get": {
+                "summary": "Available models with their inputs and valid ranges",
+                "responses": {"200": response("Model metadata.", &json!({"type": "object"}))},
+            }},
+            "/score": {"post": {
+                "summary": "Score one patient",
+                "requestBody": body("ScoreRequest"),
+                "responses": {
+                    "200": response("A result per model.", &json!({"$ref": "#/components/schemas/PatientResults"})),
+                    "400": response("Malformed request or patient.", &error),
+                    "413": response("Request body too large.", &error),
+                },
+            }},
+            "/score/batch": {"post": {
+                "summary": format!("Score up to {} patients", MAX_BATCH_SIZE),
+                "requestBody": body("BatchRequest"),
+                "responses": {
+                    "200": response("Results per patient, in request order.", &json!({
+                        "type": "object",
+                        "properties": {"patients": {"type": "array", "items": {"$ref": "#/components/schemas/PatientResults"}}},
+                    })),
+                    "400": response("Malformed request.", &error),
+                    "413": response("Request body or batch too large.", &error),
+                },
+            }},
+        },
+        "components": {"schemas": {
+            "Patient": {"type": "object", "properties": patient_properties, "additionalProperties": false},
+            "ModelKeys": {
+                "type": "array",
+                "items": {"type": "string", "enum": keys},
+                "description": "Models to run; every model if omitted.",
+            },
+            "ScoreRequest": {
+                "type": "object",
+                "required": ["patient"],
+                "properties": {
+                    "patient": {"$ref": "#/components/schemas/Patient"},
+                    "models": {"$ref": "#/components/schemas/ModelKeys"},
+                },
+            },
+            "BatchRequest": {
+                "type": "object",
+                "required": ["patients"],
+                "properties": {
+                    "patients": {"type": "array", "maxItems": MAX_BATCH_SIZE, "items": {"$ref": "#/components/schemas/Patient"}},
+                    "models": {"$ref": "#/components/schemas/ModelKeys"},
+                },
+            },
+            "Result": {
+                "type": "object",
+                "properties": {
+                    "model": {"type": "string"},
+                    "name": {"type": "string"},
+                    "version": {"type": "string"},
+                    "horizon_years": {"type": "integer"},
+                    "outcome": {"type": "string"},
+                    "risk": {"type": "number", "nullable": true},
+                    "category": {"type": "string", "nullable": true, "enum": ["low", "borderline", "intermediate", "high", null]},
+                    "error": {"type": "string", "nullable": true},
+                },
+            },
+            "PatientResults": {
+                "type": "object",
+                "properties": {
+                    "results": {"type": "array", "items": {"$ref": "#/components/schemas/Result"}},
+                    "error": {"type": "string", "description": "Why the patient could not be read."},
+                },
+            },
+            "Health": {
+                "type": "object",
+                "properties": {"status": {"type": "string"}, "version": {"type": "string"}},
+            },
+            "Error": {"type": "object", "properties": {"error": {"type": "string"}}},
+        }},
+    })
+}
+
+fn bad_request(e: String) -> (u16, Value) {
+    (400, json!({ "error": e }))
+}
+
+/// Routes a request to the scoring service, returning the status code and
+/// JSON body. Request size limits are left to the server.
+pub fn handle_request(method: &str, path: &str, body: &str) -> (u16, Value) {
+    let request =
+        || serde_json::from_str::<Value>(body).map_err(|e| format!("Invalid JSON: {}", e));
+    match (method, path.trim_end_matches('/')) {
+        ("GET", "/health") => (
+            200,
+            json!({"status": "ok", "version": env!("CARGO_PKG_VERSION")}),
+        ),
+        ("GET", "/openapi.json") => (200, openapi()),
+        ("GET", "/models") => (200, model_list()),
+        ("POST", "/score") => {
+            let request = match request() {
+                Ok(request) => request,
+                Err(e) => return bad_request(e),
+            };
+            let models = match selected_models(&request) {
+                Ok(models) => models,
+                Err(e) => return bad_request(e),
+            };
+            let scored = score_patient(&request["patient"], &models);
+            match scored.get("error") {
+                Some(e) => bad_request(e.as_str().unwrap_or_default().to_string()),
+                None => (200, scored),
+            }
+        }
+        ("POST", "/score/batch") => {
+            let request = match request() {
+                Ok(request) => request,
+                Err(e) => return bad_request(e),
+            };
+            let models = match selected_models(&request) {
+                Ok(models) => models,
+                Err(e) => return bad_request(e),
+            };
+            let Some(patients) = request["patients"].as_array() else {
+                return bad_request("'patients' must be an array.".to_string());
+            };
+            if patients.len() > MAX_BATCH_SIZE {
+                return (
+                    413,
+                    json!({"error": format!("At most {} patients per batch, not {}.", MAX_BATCH_SIZE, patients.len())}),
+                );
+            }
+            let scored: Vec<Value> = patients
+                .par_iter()
+                .map(|patient| score_patient(patient, &models))
+                .collect();
+            (200, json!({ "patients": scored }))
+        }
+        (_, "/health" | "/openapi.json" | "/models" | "/score" | "/score/batch") => (
+            405,
+            json!({ "error": format!("{} is not allowed on {}", method, path) }),
+        ),
+        _ => (
+            404,
+            json!({ "error": format!("No route for {} {}", method, path) }),
+        ),
+    }
+}
@@ -0,0 +1,353 @@
+use crate::covariates::Score2Coefficients;
+use crate::model::{ModelMetadata, Patient, RiskModel, Sex};
+use crate::utils::{calculate_risk_rust_parallel_np, input_ranges, InputRange};
+use numpy::PyReadonlyArrayDyn;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// mg/dL to mmol/L, as used for cholesterol in the PREVENT equations.
+pub(crate) const CHOLESTEROL_MMOL_PER_MG: f64 = 0.02586;
+
+/// ESC risk regions, each with its own recalibration of SCORE2 and SCORE2-OP
+/// to the region's CVD incidence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Score2Region {
+    Low,
+    Moderate,
+    High,
+    VeryHigh,
+}
+
+impl Score2Region {
+    pub const ALL: [Score2Region; 4] = [
+        Score2Region::Low,
+        Score2Region::Moderate,
+        Score2Region::High,
+        Score2Region::VeryHigh,
+    ];
+
+    pub fn parse(region: &str) -> Result<Score2Region, String> {
+        match region.to_lowercase().replace(' ', "_").as_str() {
+            "low" => Ok(Score2Region::Low),
+            "moderate" => Ok(Score2Region::Moderate),
+            "high" => Ok(Score2Region::High),
+            "very_high" => Ok(Score2Region::VeryHigh),
+            _ => Err(format!(
+                "Region must be 'low', 'moderate', 'high' or 'very_high', not '{}'.",
+                region
+            )),
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        Score2Region::ALL
+            .iter()
+            .position(|region| region == self)
+            .unwrap_or_default()
+    }
+}
+
+/// SCORE2 for ages 40 to 69, or SCORE2-OP (older persons) for ages 70 to 89,
+/// recalibrated to one risk region. Both predict 10-year fatal and non-fatal
+/// CVD from the same inputs as PREVENT, ignoring BMI, eGFR and medication.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Score2Model {
+    pub older_persons: bool,
+    pub region: Score2Region,
+}
+
+const SCORE2_KEYS: [&str; 4] = [
+    "score2_low",
+    "score2_moderate",
+    "score2_high",
+    "score2_very_high",
+];
+const SCORE2_OP_KEYS: [&str; 4] = [
+    "score2_op_low",
+    "score2_op_moderate",
+    "score2_op_high",
+    "score2_op_very_high",
+];
+const SCORE2_NAMES: [&str; 4] = [
+    "SCORE2 (low risk region)",
+    "SCORE2 (moderate risk region)",
+    "SCORE2 (high risk region)",
+    "SCORE2 (very high risk region)",
+];
+const SCORE2_OP_NAMES: [&str; 4] = [
+    "SCORE2-OP (low risk region)",
+    "SCORE2-OP (moderate risk region)",
+    "SCORE2-OP (high risk region)",
+    "SCORE2-OP (very high risk region)",
+];
+const SCORE2_INPUTS: [&str; 7] = [
+    "sex",
+    "age",
+    "total_cholesterol",
+    "hdl_cholesterol",
+    "systolic_bp",
+    "has_diabetes",
+    "current_smoker",
+];
+
+impl Score2Model {
+    pub fn all() -> Vec<Score2Model> {
+        [false, true]
+            .into_iter()
+            .flat_map(|older_persons| {
+                Score2Region::ALL.map(|region| Score2Model {
+                    older_persons,
+                    region,
+                })
+            })
+            .collect()
+    }
+}
+
+impl RiskModel for Score2Model {
+    fn metadata(&self) -> ModelMetadata {
+        let (keys, names) = if self.older_persons {
+            (SCORE2_OP_KEYS, SCORE2_OP_NAMES)
+        } else {
+            (SCORE2_KEYS, SCORE2_NAMES)
+        };
+        ModelMetadata {
+            key: keys[self.region.index()],
+            name: names[self.region.index()],
+            version: "2021",
+            horizon_years: 10,
+            outcome: "cvd",
+        }
+    }
+
+    fn required_inputs(&self) -> &'static [&'static str] {
+        &SCORE2_INPUTS
+    }
+
+    fn input_ranges(&self) -> Vec<InputRange> {
+        score2_input_ranges(self.older_persons).into()
+    }
+
+    fn predict(&self, patient: &Patient) -> Result<f64, String> {
+        score2_risk(
+            self.older_persons,
+            self.region,
+            patient.sex,
+            patient.age,
+            patient.total_cholesterol,
+            patient.hdl_cholesterol,
+            patient.systolic_bp,
+            patient.has_diabetes,
+            patient.current_smoker,
+        )
+    }
+}
+
+/// Valid range of age, total cholesterol, HDL cholesterol and systolic blood
+/// pressure. SCORE2 covers ages 40 to 69 and SCORE2-OP ages 70 to 89; the
+/// remaining inputs share their ranges with PREVENT.
+pub fn score2_input_ranges(older_persons: bool) -> [InputRange; 4] {
+    let age = if older_persons {
+        InputRange {
+            field: "age",
+            min: 70.0,
+            max: 89.0,
+            message: "Age must be between 70 and 89 for SCORE2-OP",
+        }
+    } else {
+        InputRange {
+            field: "age",
+            min: 40.0,
+            max: 69.0,
+            message: "Age must be between 40 and 69 for SCORE2",
+        }
+    };
+    let [_, total_cholesterol, hdl_cholesterol, systolic_bp, _, _] = input_ranges(true);
+    [age, total_cholesterol, hdl_cholesterol, systolic_bp]
+}
+
+fn score2_risk(
+    older_persons: bool,
+    region: Score2Region,
+    sex: Sex,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+) -> Result<f64, String> {
+    let values = [age, total_cholesterol, hdl_cholesterol, systolic_bp];
+    for (value, range) in values.iter().zip(score2_input_ranges(older_persons)) {
+        if !(range.min..=range.max).contains(value) {
+            return Err(range.message.to_string());
+        }
+    }
+
+    let coefficients = match (sex, older_persons) {
+        (Sex::Female, false) => Score2Coefficients::female(),
+        (Sex::Male, false) => Score2Coefficients::male(),
+        (Sex::Female, true) => Score2Coefficients::older_persons_female(),
+        (Sex::Male, true) => Score2Coefficients::older_persons_male(),
+    };
+
+    let age = (age - coefficients.age_centre) / coefficients.age_scale;
+    let systolic_bp =
+        (systolic_bp - coefficients.systolic_bp_centre) / coefficients.systolic_bp_scale;
+    let total_cholesterol =
+        total_cholesterol * CHOLESTEROL_MMOL_PER_MG - coefficients.total_cholesterol_centre;
+    let hdl_cholesterol = (hdl_cholesterol * CHOLESTEROL_MMOL_PER_MG
+        - coefficients.hdl_cholesterol_centre)
+        / coefficients.hdl_cholesterol_scale;
+    let smoker = if current_smoker { 1.0 } else { 0.0 };
+    let diabetes = if has_diabetes { 1.0 } else { 0.0 };
+
+    let linear_predictor: f64 = [
+        coefficients.age * age,
+        coefficients.smoker * smoker,
+        coefficients.systolic_bp * systolic_bp,
+        coefficients.diabetes * diabetes,
+        coefficients.total_cholesterol * total_cholesterol,
+        coefficients.hdl_cholesterol * hdl_cholesterol,
+        coefficients.age_smoker * age * smoker,
+        coefficients.age_systolic_bp * age * systolic_bp,
+        coefficients.age_total_cholesterol * age * total_cholesterol,
+        coefficients.age_hdl_cholesterol * age * hdl_cholesterol,
+        coefficients.age_diabetes * age * diabetes,
+    ]
+    .iter()
+    .sum();
+
+    // The uncalibrated risk is 1 - S0^exp(lp - mean); the regional
+    // recalibration is applied on its complementary log-log scale, which is
+    // ln(-ln S0) + lp - mean.
+    let uncalibrated_cloglog = (-coefficients.baseline_survival.ln()).ln() + linear_predictor
+        - coefficients.mean_linear_predictor;
+    let (scale1, scale2) = coefficients.region_scales[region.index()];
+    let risk = -(-(scale1 + scale2 * uncalibrated_cloglog).exp()).exp_m1();
+    Ok(risk * 100.0)
+}
+
+pub fn calculate_score2_risk(
+    region: &str,
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+) -> Result<f64, String> {
+    score2_risk(
+        false,
+        Score2Region::parse(region)?,
+        Sex::parse(sex)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+    )
+}
+
+pub fn calculate_score2_op_risk(
+    region: &str,
+    sex: &str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+) -> Result<f64, String> {
+    score2_risk(
+        true,
+        Score2Region::parse(region)?,
+        Sex::parse(sex)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+    )
+}
+
+#[pyfunction]
+pub fn calculate_score2_rust(
+    region: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+) -> PyResult<f64> {
+    match calculate_score2_risk(
+        &region,
+        &sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+    ) {
+        Ok(value) => Ok(value),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+#[pyfunction]
+pub fn calculate_score2_op_rust(
+    region: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+) -> PyResult<f64> {
+    match calculate_score2_op_risk(
+        &region,
+        &sex,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+    ) {
+        Ok(value) => Ok(value),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+#[pyfunction]
+pub fn calculate_score2_rust_parallel_np(
+    py: Python,
+    region: String,
+    data: PyReadonlyArrayDyn<f64>,
+) -> PyResult<PyObject> {
+    let model = Score2Model {
+        older_persons: false,
+        region: Score2Region::parse(&region).map_err(PyValueError::new_err)?,
+    };
+    calculate_risk_rust_parallel_np(py, data, &model)
+}
+
+#[pyfunction]
+pub fn calculate_score2_op_rust_parallel_np(
+    py: Python,
+    region: String,
+    data: PyReadonlyArrayDyn<f64>,
+) -> PyResult<PyObject> {
+    let model = Score2Model {
+        older_persons: true,
+        region: Score2Region::parse(&region).map_err(PyValueError::new_err)?,
+    };
+    calculate_risk_rust_parallel_np(py, data, &model)
+}
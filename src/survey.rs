@@ -0,0 +1,380 @@
+use crate::model::{find_model, risk_category, RiskModel};
+use crate::utils::RiskCategory;
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+/// One respondent of a complex survey: a predicted risk (%, NaN if it could
+/// not be calculated), the sampling weight, the codes of the stratum and of
+/// the primary sampling unit (PSU) within it, and a subgroup code.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurveyRecord {
+    pub risk: f64,
+    pub weight: f64,
+    pub stratum: i64,
+    pub psu: i64,
+    pub group: i64,
+}
+
+/// A weighted estimate with its design-based standard error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    pub value: f64,
+    pub se: f64,
+}
+
+/// Survey-weighted estimates for the population represented by one domain
+/// (everyone, or one subgroup) of the respondents with a predicted risk. A
+/// domain without any has a population of 0 and NaN means and proportions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DomainSummary {
+    /// Respondents in the domain with a predicted risk.
+    pub n: usize,
+    /// Sum of their weights.
+    pub population: f64,
+    pub mean_risk: Estimate,
+    /// Share of the population in each of `RiskCategory::ALL`, if the model's
+    /// risks have a category.
+    pub category_proportions: Option<[Estimate; 4]>,
+    /// Expected number of events over the model horizon: the weighted sum of
+    /// the predicted probabilities.
+    pub expected_events: Estimate,
+}
+
+/// Names of the values in a flattened `DomainSummary`, in order.
+pub fn survey_columns() -> Vec<String> {
+    let mut columns = vec![
+        "n".to_string(),
+        "population".to_string(),
+        "mean_risk".to_string(),
+        "mean_risk_se".to_string(),
+    ];
+    for category in RiskCategory::ALL {
+        let name = format!("proportion_{}", category.label());
+        columns.push(name.clone());
+        columns.push(format!("{}_se", name));
+    }
+    columns.push("expected_events".to_string());
+    columns.push("expected_events_se".to_string());
+    columns
+}
+
+impl DomainSummary {
+    pub fn to_vec(&self) -> Vec<f64> {
+        let mut values = vec![
+            self.n as f64,
+            self.population,
+            self.mean_risk.value,
+            self.mean_risk.se,
+        ];
+        let missing = Estimate {
+            value: f64::NAN,
+            se: f64::NAN,
+        };
+        for proportion in self.category_proportions.unwrap_or([missing; 4]) {
+            values.extend([proportion.value, proportion.se]);
+        }
+        values.extend([self.expected_events.value, self.expected_events.se]);
+        values
+    }
+}
+
+/// The survey design: the PSU of each record, as an index into the PSUs of
+/// its stratum.
+struct Design {
+    /// (stratum index, PSU index within the stratum) of each record.
+    units: Vec<(usize, usize)>,
+    psus_per_stratum: Vec<usize>,
+}
+
+impl Design {
+    fn new(records: &[SurveyRecord]) -> Result<Design, String> {
+        let mut strata: BTreeMap<i64, BTreeMap<i64, usize>> = BTreeMap::new();
+        for record in records {
+            strata
+                .entry(record.stratum)
+                .or_default()
+                .insert(record.psu, 0);
+        }
+        let mut stratum_indices = BTreeMap::new();
+        let mut psus_per_stratum = Vec::new();
+        for (i, (stratum, psus)) in strata.iter_mut().enumerate() {
+            if psus.len() < 2 {
+                return Err(format!(
+                    "Stratum {} has a single PSU, so its variance cannot be estimated.",
+                    stratum
+                ));
+            }
+            for (j, index) in psus.values_mut().enumerate() {
+                *index = j;
+            }
+            stratum_indices.insert(*stratum, i);
+            psus_per_stratum.push(psus.len());
+        }
+        let units = records
+            .iter()
+            .map(|record| {
+                (
+                    stratum_indices[&record.stratum],
+                    strata[&record.stratum][&record.psu],
+                )
+            })
+            .collect();
+        Ok(Design {
+            units,
+            psus_per_stratum,
+        })
+    }
+
+    /// Variance of a total of `scores` under with-replacement sampling of
+    /// PSUs within strata: sum over strata of n_h / (n_h - 1) times the sum
+    /// of squared deviations of the PSU totals from their stratum mean.
+    fn variance(&self, scores: &[f64]) -> f64 {
+        let mut totals: Vec<Vec<f64>> = self
+            .psus_per_stratum
+            .iter()
+            .map(|count| vec![0.0; *count])
+            .collect();
+        for ((stratum, psu), score) in self.units.iter().zip(scores) {
+            totals[*stratum][*psu] += score;
+        }
+        totals
+            .iter()
+            .map(|psus| {
+                let n = psus.len() as f64;
+                let mean = psus.iter().sum::<f64>() / n;
+                n / (n - 1.0) * psus.iter().map(|total| (total - mean).powi(2)).sum::<f64>()
+            })
+            .sum()
+    }
+}
+
+/// Estimates for the records in `domain`, by Taylor linearization over the
+/// full design so that subgroup standard errors account for the subgroup
+/// size being random.
+fn summarize_domain(
+    records: &[SurveyRecord],
+    design: &Design,
+    category_model: Option<&dyn RiskModel>,
+    domain: impl Fn(&SurveyRecord) -> bool,
+) -> DomainSummary {
+    let included: Vec<bool> = records
+        .iter()
+        .map(|record| !record.risk.is_nan() && domain(record))
+        .collect();
+    let n = included.iter().filter(|included| **included).count();
+    let population: f64 = records
+        .iter()
+        .zip(&included)
+        .filter(|(_, included)| **included)
+        .map(|(record, _)| record.weight)
+        .sum();
+    if n == 0 {
+        let missing = Estimate {
+            value: f64::NAN,
+            se: f64::NAN,
+        };
+        return DomainSummary {
+            n,
+            population,
+            mean_risk: missing,
+            category_proportions: category_model.map(|_| [missing; 4]),
+            expected_events: Estimate {
+                value: 0.0,
+                se: 0.0,
+            },
+        };
+    }
+
+    // Ratio of weighted totals of `value` and of the domain indicator.
+    let ratio = |value: &dyn Fn(&SurveyRecord) -> f64| {
+        let values: Vec<f64> = records
+            .iter()
+            .zip(&included)
+            .map(|(record, included)| if *included { value(record) } else { 0.0 })
+            .collect();
+        let total: f64 = records.iter().zip(&values).map(|(r, v)| r.weight * v).sum();
+        let estimate = total / population;
+        let scores: Vec<f64> = records
+            .iter()
+            .zip(&values)
+            .zip(&included)
+            .map(|((record, value), included)| {
+                let indicator = if *included { 1.0 } else { 0.0 };
+                record.weight * (value - estimate * indicator) / population
+            })
+            .collect();
+        Estimate {
+            value: estimate,
+            se: design.variance(&scores).sqrt(),
+        }
+    };
+
+    let category_proportions = category_model.map(|model| {
+        RiskCategory::ALL.map(|category| {
+            ratio(&|record: &SurveyRecord| {
+                if risk_category(model, record.risk) == Some(category) {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+        })
+    });
+    let event_scores: Vec<f64> = records
+        .iter()
+        .zip(&included)
+        .map(|(record, included)| {
+            if *included {
+                record.weight * record.risk / 100.0
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    DomainSummary {
+        n,
+        population,
+        mean_risk: ratio(&|record: &SurveyRecord| record.risk),
+        category_proportions,
+        expected_events: Estimate {
+            value: event_scores.iter().sum(),
+            se: design.variance(&event_scores).sqrt(),
+        },
+    }
+}
+
+/// Survey-weighted summaries of predicted risk for everyone and for each
+/// subgroup, in increasing order of group code. Records with a NaN risk are
+/// left out of every estimate but kept in the design. Category proportions
+/// are only estimated for a `model` whose risks have a `risk_category`.
+pub fn survey_summary(
+    records: &[SurveyRecord],
+    model: Option<&dyn RiskModel>,
+) -> Result<(DomainSummary, Vec<(i64, DomainSummary)>), String> {
+    if let Some(record) = records
+        .iter()
+        .find(|record| !(record.weight.is_finite() && record.weight > 0.0))
+    {
+        return Err(format!("Weight {} must be positive.", record.weight));
+    }
+    if records.iter().all(|record| record.risk.is_nan()) {
+        return Err("At least one record needs a predicted risk.".to_string());
+    }
+    let design = Design::new(records)?;
+
+    let mut groups: Vec<i64> = records.iter().map(|record| record.group).collect();
+    groups.sort_unstable();
+    groups.dedup();
+    let category_model = model.filter(|model| risk_category(*model, 0.0).is_some());
+    let overall = summarize_domain(records, &design, category_model, |_| true);
+    let by_group = groups
+        .par_iter()
+        .map(|group| {
+            (
+                *group,
+                summarize_domain(records, &design, category_model, |record| {
+                    record.group == *group
+                }),
+            )
+        })
+        .collect();
+    Ok((overall, by_group))
+}
+
+fn one_dimensional(array: &PyReadonlyArrayDyn<f64>, name: &str) -> PyResult<()> {
+    if array.ndim() != 1 {
+        return Err(PyValueError::new_err(format!(
+            "{} must be one-dimensional, not {}-dimensional.",
+            name,
+            array.ndim()
+        )));
+    }
+    Ok(())
+}
+
+/// Converts an array of integer codes, as Python's `pd.factorize` returns.
+fn codes(array: &PyReadonlyArrayDyn<f64>, name: &str) -> PyResult<Vec<i64>> {
+    one_dimensional(array, name)?;
+    array
+        .as_array()
+        .iter()
+        .map(|code| {
+            if code.is_finite() && code.fract() == 0.0 {
+                Ok(*code as i64)
+            } else {
+                Err(PyValueError::new_err(format!(
+                    "{} codes must be integers, not {}",
+                    name, code
+                )))
+            }
+        })
+        .collect()
+}
+
+/// (column names, overall estimates, group codes, (groups, columns) estimates).
+type SurveySummary = (Vec<String>, Vec<f64>, Vec<i64>, PyObject);
+
+#[pyfunction]
+pub fn survey_summary_rust(
+    py: Python,
+    risks: PyReadonlyArrayDyn<f64>,
+    weights: PyReadonlyArrayDyn<f64>,
+    strata: PyReadonlyArrayDyn<f64>,
+    psus: PyReadonlyArrayDyn<f64>,
+    groups: PyReadonlyArrayDyn<f64>,
+    model: Option<String>,
+) -> PyResult<SurveySummary> {
+    let model = model
+        .map(|model| find_model(&model))
+        .transpose()
+        .map_err(PyValueError::new_err)?;
+    one_dimensional(&risks, "Risks")?;
+    one_dimensional(&weights, "Weights")?;
+    let (strata, psus, groups) = (
+        codes(&strata, "Stratum")?,
+        codes(&psus, "PSU")?,
+        codes(&groups, "Group")?,
+    );
+    let (risks, weights) = (risks.as_array(), weights.as_array());
+    let n = risks.len();
+    if [weights.len(), strata.len(), psus.len(), groups.len()]
+        .iter()
+        .any(|len| *len != n)
+    {
+        return Err(PyValueError::new_err(
+            "Risks and design variables must have the same length.",
+        ));
+    }
+    let records: Vec<SurveyRecord> = (0..n)
+        .map(|i| SurveyRecord {
+            risk: risks[i],
+            weight: weights[i],
+            stratum: strata[i],
+            psu: psus[i],
+            group: groups[i],
+        })
+        .collect();
+
+    match survey_summary(&records, model.as_deref()) {
+        Ok((overall, by_group)) => {
+            let rows: Vec<Vec<f64>> = by_group
+                .iter()
+                .map(|(_, summary)| summary.to_vec())
+                .collect();
+            let by_group_array = PyArray::from_vec2(py, &rows)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?
+                .to_object(py);
+            Ok((
+                survey_columns(),
+                overall.to_vec(),
+                by_group.iter().map(|(group, _)| *group).collect(),
+                by_group_array,
+            ))
+        }
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
@@ -0,0 +1,207 @@
+use super::test_patient;
+use crate::cds_hooks::{handle_request, FhirServer, PREFETCH, SERVICE_ID};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+
+fn observation(id: &str, loinc: &str, value: f64, unit: &str) -> Value {
+    json!({
+        "resourceType": "Observation",
+        "id": id,
+        "status": "final",
+        "subject": {"reference": "Patient/p1"},
+        "code": {"coding": [{"system": "http://loinc.org", "code": loinc}]},
+        "effectiveDateTime": "2024-03-01",
+        "valueQuantity": {"value": value, "unit": unit},
+    })
+}
+
+fn searchset(resources: Vec<Value>) -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "searchset",
+        "entry": resources.into_iter().map(|resource| json!({"resource": resource})).collect::<Vec<_>>(),
+    })
+}
+
+/// `test_patient`, 50 on the 2024-06-01 the tests use as today.
+fn patient() -> Value {
+    json!({"resourceType": "Patient", "id": "p1", "gender": "female", "birthDate": "1974-01-01"})
+}
+
+/// The measurements of `test_patient`, with a never-smoker status.
+fn observations(with_egfr: bool) -> Value {
+    let patient = test_patient();
+    let mut resources = vec![
+        observation("tc", "2093-3", patient.total_cholesterol, "mg/dL"),
+        observation("hdl", "2085-9", patient.hdl_cholesterol, "mg/dL"),
+        observation("sbp", "8480-6", patient.systolic_bp, "mm[Hg]"),
+        observation("bmi", "39156-5", patient.bmi, "kg/m2"),
+        json!({
+            "resourceType": "Observation",
+            "id": "smoking",
+            "status": "final",
+            "subject": {"reference": "Patient/p1"},
+            "code": {"coding": [{"system": "http://loinc.org", "code": "72166-2"}]},
+            "effectiveDateTime": "2024-03-01",
+            "valueCodeableConcept": {"coding": [{"system": "http://snomed.info/sct", "code": "266919005"}]},
+        }),
+    ];
+    if with_egfr {
+        resources.push(observation(
+            "egfr",
+            "98979-8",
+            patient.egfr,
+            "mL/min/{1.73_m2}",
+        ));
+    }
+    searchset(resources)
+}
+
+fn conditions() -> Value {
+    searchset(vec![json!({
+        "resourceType": "Condition",
+        "id": "dm",
+        "subject": {"reference": "Patient/p1"},
+        "clinicalStatus": {"coding": [{"code": "active"}]},
+        "code": {"coding": [{"system": "http://hl7.org/fhir/sid/icd-10-cm", "code": "E11.9"}]},
+    })])
+}
+
+fn request(prefetch: Value, server: Option<&str>) -> String {
+    let mut request = json!({
+        "hook": "patient-view",
+        "hookInstance": "d1577c69-dfbe-44ad-ba6d-3e05e953b2ea",
+        "context": {"userId": "Practitioner/1", "patientId": "p1"},
+        "prefetch": prefetch,
+    });
+    if let Some(server) = server {
+        request["fhirServer"] = json!(server);
+        request["fhirAuthorization"] = json!({"access_token": "secret", "token_type": "Bearer"});
+    }
+    request.to_string()
+}
+
+fn no_server(_: &FhirServer, query: &str) -> Result<Value, String> {
+    panic!("Unexpected fetch of {}", query)
+}
+
+fn post(body: &str, fetch: &dyn Fn(&FhirServer, &str) -> Result<Value, String>) -> (u16, Value) {
+    let path = format!("/cds-services/{}", SERVICE_ID);
+    handle_request("POST", &path, body, "2024-06-01", fetch)
+}
+
+#[test]
+fn discovery_lists_patient_view_service() {
+    let (status, body) = handle_request("GET", "/cds-services", "", "2024-06-01", &no_server);
+    assert_eq!(status, 200);
+    let service = &body["services"][0];
+    assert_eq!(service["hook"], "patient-view");
+    assert_eq!(service["id"], SERVICE_ID);
+    for (key, query) in PREFETCH {
+        assert_eq!(service["prefetch"][key], query);
+    }
+}
+
+#[test]
+fn prefetched_record_gives_risk_card() {
+    let prefetch = json!({
+        "patient": patient(),
+        "observations": observations(true),
+        "conditions": conditions(),
+        "medications": searchset(vec![]),
+    });
+    let (status, body) = post(&request(prefetch, None), &no_server);
+    assert_eq!(status, 200);
+    let cards = body["cards"].as_array().unwrap();
+    assert_eq!(cards.len(), 2);
+
+    let summary = cards[0]["summary"].as_str().unwrap();
+    assert!(
+        summary.starts_with("PREVENT 10-year CVD risk "),
+        "{}",
+        summary
+    );
+    assert!(summary.len() <= 140);
+    assert!(["info", "warning"].contains(&cards[0]["indicator"].as_str().unwrap()));
+    assert!(!cards[0]["source"]["label"].as_str().unwrap().is_empty());
+    let detail = cards[0]["detail"].as_str().unwrap();
+    for outcome in ["| CVD |", "| ASCVD |", "| HF |"] {
+        assert!(detail.contains(outcome), "{}", detail);
+    }
+    assert!(!detail.contains("not available"), "{}", detail);
+
+    let assumed = cards[1]["summary"].as_str().unwrap();
+    assert!(assumed.contains("antihypertensive") && assumed.contains("statin"));
+    assert!(!assumed.contains("diabetes"));
+}
+
+#[test]
+fn missing_prefetch_is_fetched_from_fhir_server() {
+    let queries = RefCell::new(Vec::new());
+    let fetch = |server: &FhirServer, query: &str| {
+        assert_eq!(server.url, "https://ehr.example/fhir");
+        assert_eq!(server.access_token.as_deref(), Some("secret"));
+        queries.borrow_mut().push(query.to_string());
+        Ok(match query.split('?').next().unwrap() {
+            "Observation" => observations(true),
+            "Condition" => conditions(),
+            _ => searchset(vec![]),
+        })
+    };
+    let prefetch = json!({"patient": patient(), "observations": null});
+    let (status, body) = post(
+        &request(prefetch, Some("https://ehr.example/fhir/")),
+        &fetch,
+    );
+    assert_eq!(status, 200, "{}", body);
+    assert!(body["cards"][0]["summary"]
+        .as_str()
+        .unwrap()
+        .starts_with("PREVENT 10-year CVD risk"));
+
+    let queries = queries.into_inner();
+    assert_eq!(queries.len(), 3);
+    assert!(queries
+        .iter()
+        .all(|query| query.contains("patient=p1") && !query.contains("{{")));
+}
+
+#[test]
+fn missing_observation_gives_warning_card() {
+    let prefetch = json!({"patient": patient(), "observations": observations(false)});
+    let (status, body) = post(&request(prefetch, None), &no_server);
+    assert_eq!(status, 200);
+    let cards = body["cards"].as_array().unwrap();
+    assert_eq!(cards.len(), 1);
+    assert_eq!(cards[0]["indicator"], "warning");
+    assert_eq!(cards[0]["summary"], "PREVENT risk needs eGFR");
+}
+
+#[test]
+fn out_of_range_models_are_marked_not_available() {
+    let mut older = patient();
+    older["birthDate"] = json!("1954-01-01");
+    let prefetch = json!({"patient": older, "observations": observations(true)});
+    let (status, body) = post(&request(prefetch, None), &no_server);
+    assert_eq!(status, 200);
+    let detail = body["cards"][0]["detail"].as_str().unwrap();
+    assert_eq!(detail.matches("not available").count(), 3, "{}", detail);
+}
+
+#[test]
+fn bad_requests_are_rejected() {
+    let (status, _) = post(&request(json!({}), None), &no_server);
+    assert_eq!(status, 400);
+
+    let mut other_hook: Value = serde_json::from_str(&request(json!({}), None)).unwrap();
+    other_hook["hook"] = json!("order-select");
+    let (status, body) = post(&other_hook.to_string(), &no_server);
+    assert_eq!(status, 400);
+    assert!(body["error"].is_string());
+
+    let (status, _) = post("not json", &no_server);
+    assert_eq!(status, 400);
+
+    let (status, _) = handle_request("GET", "/cds-services/other", "", "2024-06-01", &no_server);
+    assert_eq!(status, 404);
+}
@@ -0,0 +1,166 @@
+use super::test_patient;
+use crate::cohort_summary::{
+    age_band, ckd_stage, patients_from_csv, summarize_cohort, summarize_cohort_file, summary_csv,
+    summary_json, SUMMARY_COLUMNS,
+};
+use crate::model::{find_model, predict_batch, Patient, Sex};
+
+fn patient(sex: Sex, age: f64, has_diabetes: bool, egfr: f64) -> Patient {
+    Patient {
+        sex,
+        age,
+        has_diabetes,
+        egfr,
+        ..test_patient()
+    }
+}
+
+fn cohort() -> Vec<Patient> {
+    vec![
+        patient(Sex::Female, 45.0, false, 95.0),
+        patient(Sex::Female, 52.0, true, 70.0),
+        patient(Sex::Male, 58.0, false, 50.0),
+        patient(Sex::Male, 61.0, true, 20.0),
+        patient(Sex::Male, 20.0, false, 90.0),
+    ]
+}
+
+#[test]
+fn strata_follow_the_usual_cut_points() {
+    assert_eq!(age_band(45.0), "40-49");
+    assert_eq!(age_band(79.9), "70-79");
+    assert_eq!(age_band(f64::NAN), "missing");
+    let stages: Vec<&str> = [95.0, 60.0, 59.9, 30.0, 15.0, 5.0, f64::NAN]
+        .into_iter()
+        .map(ckd_stage)
+        .collect();
+    assert_eq!(stages, ["G1", "G2", "G3a", "G3b", "G4", "G5", "missing"]);
+}
+
+#[test]
+fn statistics_match_hand_calculation() {
+    let risks = [2.0, 6.0, 10.0, 30.0, f64::NAN];
+    let rows = summarize_cohort(&cohort(), &[("10_yr_cvd", &risks)]).unwrap();
+    let overall = &rows[0];
+    assert_eq!(
+        (overall.stratifier, overall.level.as_str()),
+        ("overall", "all")
+    );
+    assert_eq!((overall.n, overall.invalid), (5, 1));
+    assert_eq!(overall.mean, 12.0);
+    assert_eq!(overall.median, 8.0);
+    assert_eq!((overall.q1, overall.q3), (5.0, 15.0));
+    assert_eq!(overall.category_proportions, [0.25, 0.25, 0.25, 0.25]);
+
+    let male = rows
+        .iter()
+        .find(|row| row.stratifier == "sex" && row.level == "male")
+        .unwrap();
+    assert_eq!((male.n, male.invalid, male.mean), (3, 1, 20.0));
+    let young = rows.iter().find(|row| row.level == "20-29").unwrap();
+    assert!(young.mean.is_nan() && young.median.is_nan());
+
+    for stratifier in ["sex", "age_band", "diabetes", "smoking", "ckd_stage"] {
+        let n: usize = rows
+            .iter()
+            .filter(|row| row.stratifier == stratifier)
+            .map(|row| row.n)
+            .sum();
+        assert_eq!(n, 5);
+    }
+}
+
+#[test]
+fn only_ten_year_prevent_models_have_categories() {
+    let patients = cohort();
+    let risks = predict_batch(find_model("30_yr_cvd").unwrap().as_ref(), &patients);
+    let rows = summarize_cohort(&patients, &[("30_yr_cvd", &risks)]).unwrap();
+    // The 30-year models cover ages 30 to 59.
+    assert_eq!(rows[0].invalid, 2);
+    assert!(rows[0].mean > 0.0);
+    assert!(rows[0].category_proportions.iter().all(|p| p.is_nan()));
+
+    // SCORE2 is a 10-year model, but its risks use other thresholds.
+    let risks = [2.0, 6.0, 10.0, 30.0, f64::NAN];
+    let rows = summarize_cohort(&patients, &[("score2_low", &risks)]).unwrap();
+    assert!(rows[0].category_proportions.iter().all(|p| p.is_nan()));
+}
+
+#[test]
+fn exports_have_one_record_per_row() {
+    let risks = [2.0, 6.0, 10.0, 30.0, f64::NAN];
+    let rows = summarize_cohort(&cohort(), &[("10_yr_cvd", &risks)]).unwrap();
+    let csv = summary_csv(&rows);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), rows.len() + 1);
+    assert_eq!(lines[1].split(',').count(), 3 + SUMMARY_COLUMNS.len());
+    assert!(lines[1].starts_with("10_yr_cvd,overall,all,5,1,12,8,5,15,10,"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("10_yr_cvd,age_band,20-29,1,1,,")));
+
+    let json = summary_json(&rows);
+    assert_eq!(json.matches("\"model\"").count(), rows.len());
+    assert!(json.contains("\"mean\": null"));
+    assert!(json.trim_start().starts_with('[') && json.trim_end().ends_with(']'));
+}
+
+#[test]
+fn cohort_files_are_read_by_column_name() {
+    let csv = "age,sex,total_cholesterol,hdl_cholesterol,systolic_bp,has_diabetes,\
+current_smoker,bmi,egfr,on_htn_meds,on_cholesterol_meds,10_yr_cvd\n\
+50,female,200,45,140,1,false,28,,0,0,7.5\n";
+    let (patients, unreadable, columns) = patients_from_csv(csv).unwrap();
+    assert_eq!(patients.len(), 1);
+    assert!(unreadable[0].is_empty());
+    assert_eq!(patients[0].sex, Sex::Female);
+    assert!(patients[0].has_diabetes);
+    assert!(patients[0].egfr.is_nan());
+    assert_eq!(columns["10_yr_cvd"], vec![7.5]);
+
+    assert!(patients_from_csv("sex,age\nmale,50\n").is_err());
+    let bad_flag = csv.replace("1,false", "maybe,false");
+    let (_, unreadable, _) = patients_from_csv(&bad_flag).unwrap();
+    assert_eq!(unreadable[0], vec!["has_diabetes"]);
+}
+
+#[test]
+fn unreadable_rows_count_as_invalid() {
+    let csv = "sex,age,total_cholesterol,hdl_cholesterol,systolic_bp,has_diabetes,\
+current_smoker,bmi,egfr,on_htn_meds,on_cholesterol_meds,10_yr_cvd\n\
+female,50,200,45,140,0,0,28,90,0,0,5.0\n\
+unknown,50,200,45,140,0,0,28,90,0,0,6.0\n\
+male,50,200,45,140,maybe,0,28,90,0,0,\n";
+    let path = std::env::temp_dir().join("pyprevent_unreadable_rows.csv");
+    std::fs::write(&path, csv).unwrap();
+    let rows = summarize_cohort_file(path.to_str().unwrap(), &["10_yr_cvd", "10_yr_ascvd"]);
+    std::fs::remove_file(&path).unwrap();
+    let rows = rows.unwrap();
+
+    for model in ["10_yr_cvd", "10_yr_ascvd"] {
+        let overall = rows
+            .iter()
+            .find(|row| row.model == model && row.stratifier == "overall")
+            .unwrap();
+        assert_eq!((overall.n, overall.invalid), (3, 2), "{}", model);
+    }
+    let level = |stratifier: &str, level: &str| {
+        rows.iter()
+            .find(|row| {
+                row.model == "10_yr_cvd" && row.stratifier == stratifier && row.level == level
+            })
+            .map(|row| (row.n, row.invalid))
+    };
+    assert_eq!(level("sex", "missing"), Some((1, 1)));
+    assert_eq!(level("sex", "male"), Some((1, 1)));
+    assert_eq!(level("diabetes", "missing"), Some((1, 1)));
+    assert_eq!(level("diabetes", "no"), Some((2, 1)));
+}
+
+#[test]
+fn invalid_inputs_are_rejected() {
+    let patients = cohort();
+    assert!(summarize_cohort(&patients, &[("10_yr_cvd", &[1.0, 2.0])]).is_err());
+    assert!(summarize_cohort(&patients, &[("no_such_model", &[1.0; 5])]).is_err());
+    assert!(summarize_cohort(&[], &[]).is_err());
+}
@@ -0,0 +1,162 @@
+use super::test_patient;
+use crate::confidence_interval::{design, risk_confidence_interval, CoefficientCovariance};
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+use crate::utils::risk_from_log_odds;
+
+fn patient(sex: Sex) -> Patient {
+    Patient {
+        sex,
+        age: 58.0,
+        current_smoker: true,
+        egfr: 52.0,
+        on_cholesterol_meds: true,
+        ..test_patient()
+    }
+}
+
+fn below_knots(sex: Sex) -> Patient {
+    Patient {
+        systolic_bp: 100.0,
+        bmi: 24.0,
+        egfr: 95.0,
+        has_diabetes: false,
+        current_smoker: false,
+        ..patient(sex)
+    }
+}
+
+fn log_odds(model: PreventModel, patient: &Patient) -> f64 {
+    patient.score(model.unchecked_log_odds_fn()).unwrap()
+}
+
+const COVARIANCE: &str = "model,sex,row,column,covariance
+10_yr_cvd,female,constant,constant,0.0025
+10_yr_cvd,female,age_adjustment_factor,age_adjustment_factor,0.0004
+10_yr_cvd,female,constant,age_adjustment_factor,-0.0002
+10_yr_cvd,female,smoker_factor,smoker_factor,0.0016
+";
+
+#[test]
+fn design_reproduces_log_odds() {
+    for model in PreventModel::ALL {
+        for sex in [Sex::Female, Sex::Male] {
+            for patient in [patient(sex), below_knots(sex)] {
+                let covariates = model.covariates(sex);
+                let design = design(model, &patient);
+                let dot: f64 = design
+                    .iter()
+                    .zip(covariates.coefficients())
+                    .map(|(x, b)| x * b)
+                    .sum();
+                assert!((dot - log_odds(model, &patient)).abs() < 1e-12);
+            }
+        }
+    }
+}
+
+#[test]
+fn delta_interval_uses_design_variance() {
+    let covariance = CoefficientCovariance::from_csv(COVARIANCE).unwrap();
+    let model = PreventModel::Cvd10Yr;
+    let patient = patient(Sex::Female);
+    let interval = risk_confidence_interval(model, &patient, &covariance, 0.95, 4000, 1).unwrap();
+
+    // Smoker indicator 1, constant 1, adjusted age 0.3.
+    let variance: f64 = 0.0025 + 0.3 * 0.3 * 0.0004 - 2.0 * 0.3 * 0.0002 + 0.0016;
+    assert!((interval.log_odds_se - variance.sqrt()).abs() < 1e-12);
+    let eta = log_odds(model, &patient);
+    let z = 1.959963985;
+    assert!((interval.delta_lower - risk_from_log_odds(eta - z * variance.sqrt())).abs() < 1e-6);
+    assert!((interval.delta_upper - risk_from_log_odds(eta + z * variance.sqrt())).abs() < 1e-6);
+    assert_eq!(interval.risk, model.predict(&patient).unwrap());
+    assert!(interval.delta_lower < interval.risk && interval.risk < interval.delta_upper);
+
+    // Linear in the coefficients, so simulation matches the delta method.
+    let width = interval.delta_upper - interval.delta_lower;
+    assert!((interval.simulation_lower - interval.delta_lower).abs() < 0.05 * width);
+    assert!((interval.simulation_upper - interval.delta_upper).abs() < 0.05 * width);
+
+    let again = risk_confidence_interval(model, &patient, &covariance, 0.95, 4000, 1).unwrap();
+    assert_eq!(interval, again);
+    let narrower = risk_confidence_interval(model, &patient, &covariance, 0.5, 4000, 1).unwrap();
+    assert!(narrower.delta_upper - narrower.delta_lower < width);
+}
+
+#[test]
+fn terms_a_patient_lacks_add_no_uncertainty() {
+    let covariance = CoefficientCovariance::from_csv(COVARIANCE).unwrap();
+    let non_smoker = Patient {
+        current_smoker: false,
+        age: 55.0,
+        ..patient(Sex::Female)
+    };
+    let interval =
+        risk_confidence_interval(PreventModel::Cvd10Yr, &non_smoker, &covariance, 0.95, 10, 0)
+            .unwrap();
+    assert!((interval.log_odds_se - 0.05).abs() < 1e-12);
+}
+
+#[test]
+fn design_uses_the_point_estimate_bmi() {
+    let covariance = CoefficientCovariance::from_csv(
+        "model,sex,row,column,covariance
+10_yr_cvd,male,bmi_min_factor,bmi_min_factor,0.01
+10_yr_hf,male,bmi_min_factor,bmi_min_factor,0.01
+",
+    )
+    .unwrap();
+    let interval = |model, bmi| {
+        let patient = Patient {
+            bmi,
+            ..patient(Sex::Male)
+        };
+        risk_confidence_interval(model, &patient, &covariance, 0.95, 10, 0)
+            .unwrap()
+            .log_odds_se
+    };
+    // The CVD equation is given a BMI of 0.0 whatever the patient's BMI.
+    assert_eq!(
+        interval(PreventModel::Cvd10Yr, 20.0),
+        interval(PreventModel::Cvd10Yr, 28.0)
+    );
+    assert!((interval(PreventModel::Cvd10Yr, 28.0) - 0.5).abs() < 1e-12);
+    assert!((interval(PreventModel::HeartFailure10Yr, 20.0) - 0.1).abs() < 1e-12);
+}
+
+#[test]
+fn invalid_covariance_and_arguments_are_rejected() {
+    assert!(CoefficientCovariance::from_csv("model,sex,row,column\n").is_err());
+    let bad_rows = [
+        "30_yr_cvd,female,constant,slope,0.1",
+        "score2_low,female,constant,constant,0.1",
+        "10_yr_cvd,female,constant,constant,abc",
+        // Correlation above one.
+        "10_yr_cvd,male,constant,constant,1\n10_yr_cvd,male,smoker_factor,smoker_factor,1\n\
+         10_yr_cvd,male,constant,smoker_factor,2",
+        "10_yr_cvd,male,constant,smoker_factor,0.1\n10_yr_cvd,male,smoker_factor,constant,0.2",
+        // Covariance with a coefficient that has no variance.
+        "10_yr_cvd,male,constant,constant,1\n10_yr_cvd,male,constant,smoker_factor,0.1",
+    ];
+    for rows in bad_rows {
+        let csv = format!("model,sex,row,column,covariance\n{}\n", rows);
+        assert!(CoefficientCovariance::from_csv(&csv).is_err(), "{}", rows);
+    }
+
+    let covariance = CoefficientCovariance::from_csv(COVARIANCE).unwrap();
+    let female = patient(Sex::Female);
+    let model = PreventModel::Cvd10Yr;
+    assert!(
+        risk_confidence_interval(model, &patient(Sex::Male), &covariance, 0.95, 10, 0).is_err()
+    );
+    assert!(risk_confidence_interval(
+        PreventModel::HeartFailure10Yr,
+        &female,
+        &covariance,
+        0.95,
+        10,
+        0
+    )
+    .is_err());
+    assert!(risk_confidence_interval(model, &female, &covariance, 1.0, 10, 0).is_err());
+    assert!(risk_confidence_interval(model, &female, &covariance, 0.95, 1, 0).is_err());
+}
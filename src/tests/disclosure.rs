@@ -0,0 +1,186 @@
+use crate::cohort_summary::SummaryRow;
+use crate::disclosure::{protect_summary, protected_csv, DisclosurePolicy, Suppression};
+
+fn row(stratifier: &'static str, level: &str, n: usize, invalid: usize) -> SummaryRow {
+    SummaryRow {
+        model: "10_yr_cvd".to_string(),
+        stratifier,
+        level: level.to_string(),
+        n,
+        invalid,
+        mean: 8.123,
+        median: 7.456,
+        q1: 4.0,
+        q3: 12.0,
+        category_proportions: [0.5, 0.25, 0.25, 0.0],
+    }
+}
+
+/// A cohort of 200, with one small age band and one small sex.
+fn rows() -> Vec<SummaryRow> {
+    vec![
+        row("overall", "all", 200, 20),
+        row("sex", "female", 8, 0),
+        row("sex", "male", 192, 20),
+        row("age_band", "40-49", 60, 0),
+        row("age_band", "50-59", 40, 5),
+        row("age_band", "60-69", 100, 15),
+    ]
+}
+
+fn suppression(protected: &[crate::disclosure::ProtectedRow]) -> Vec<Option<Suppression>> {
+    protected.iter().map(|row| row.suppression).collect()
+}
+
+#[test]
+fn small_strata_and_their_complements_are_withheld() {
+    let protected = protect_summary(&rows(), &DisclosurePolicy::default()).unwrap();
+    assert_eq!(
+        suppression(&protected),
+        [
+            None,
+            Some(Suppression::Primary),
+            Some(Suppression::Complementary),
+            None,
+            None,
+            None,
+        ]
+    );
+    assert!(protected[1].values.iter().all(|v| v.is_nan()));
+    assert!(protected[2].values.iter().all(|v| v.is_nan()));
+
+    // Too few valid predictions also counts as a small cell, and the
+    // smallest other level is withheld with it.
+    let mut data = rows();
+    data[4].invalid = 35;
+    let protected = protect_summary(&data, &DisclosurePolicy::default()).unwrap();
+    assert_eq!(protected[4].suppression, Some(Suppression::Primary));
+    assert_eq!(protected[3].suppression, Some(Suppression::Complementary));
+    assert_eq!(protected[5].suppression, None);
+}
+
+#[test]
+fn small_cells_within_a_row_are_withheld() {
+    let protected = protect_summary(&rows(), &DisclosurePolicy::default()).unwrap();
+    // 40 patients, 5 invalid: the invalid count is small, and of the 35
+    // valid, 9 are borderline and 9 intermediate, so both go.
+    let band = &protected[4].values;
+    assert!(band[1].is_nan());
+    assert_eq!(band[7], 0.5);
+    assert!(band[8].is_nan() && band[9].is_nan());
+    assert_eq!(band[10], 0.0);
+    // 60 patients: no invalid predictions, and 15 in each of the smaller
+    // categories, which are only withheld as the complements of 50-59's.
+    assert_eq!(protected[3].values[1], 0.0);
+    assert_eq!(protected[3].values[7], 0.5);
+    assert!(protected[3].values[8].is_nan() && protected[3].values[9].is_nan());
+
+    // A single small category is withheld with the next smallest.
+    let mut data = rows();
+    data[3].category_proportions = [0.7, 0.1, 0.2, 0.0];
+    let protected = protect_summary(&data, &DisclosurePolicy::default()).unwrap();
+    let values = &protected[3].values;
+    assert!(!values[7].is_nan() && values[8].is_nan() && values[9].is_nan());
+}
+
+#[test]
+fn withheld_cells_cannot_be_derived_from_the_overall_row() {
+    let mut data = rows();
+    data[3].category_proportions = [0.7, 0.1, 0.2, 0.0];
+    for policy in [
+        DisclosurePolicy::default(),
+        DisclosurePolicy {
+            min_cell_size: 30,
+            ..DisclosurePolicy::default()
+        },
+    ] {
+        let protected = protect_summary(&data, &policy).unwrap();
+        for stratifier in ["sex", "age_band"] {
+            let levels: Vec<&[f64; 11]> = protected
+                .iter()
+                .filter(|row| row.stratifier == stratifier)
+                .map(|row| &row.values)
+                .collect();
+            for column in 1..11 {
+                let withheld = levels
+                    .iter()
+                    .filter(|values| values[column].is_nan())
+                    .count();
+                assert_ne!(withheld, 1, "{} column {}", stratifier, column);
+            }
+        }
+        for row in &protected {
+            let withheld = row.values[7..].iter().filter(|v| v.is_nan()).count();
+            assert!(withheld != 1 || row.suppression.is_some());
+        }
+    }
+    // 50-59 withholds its 5 invalid, so the 15 of 60-69 go with it.
+    let protected = protect_summary(&data, &DisclosurePolicy::default()).unwrap();
+    assert!(protected[4].values[1].is_nan() && protected[5].values[1].is_nan());
+    assert_eq!(protected[3].values[1], 0.0);
+}
+
+#[test]
+fn rounding_applies_to_published_values() {
+    let policy = DisclosurePolicy {
+        count_base: 5,
+        decimals: Some(1),
+        ..DisclosurePolicy::default()
+    };
+    let mut data = rows();
+    data[0].n = 203;
+    data[0].invalid = 12;
+    data[0].category_proportions = [0.51234, 0.25, 0.23766, 0.0];
+    let values = protect_summary(&data, &policy).unwrap()[0].values;
+    assert_eq!(&values[..4], &[205.0, 10.0, 8.1, 7.5]);
+    assert_eq!(values[7], 0.512);
+}
+
+#[test]
+fn laplace_noise_is_seeded_and_centred() {
+    let policy = DisclosurePolicy {
+        epsilon: Some(0.5),
+        seed: 7,
+        ..DisclosurePolicy::default()
+    };
+    let data: Vec<SummaryRow> = (0..2000).map(|_| row("overall", "all", 200, 20)).collect();
+    let protected = protect_summary(&data, &policy).unwrap();
+    assert_eq!(protected, protect_summary(&data, &policy).unwrap());
+    let n: Vec<f64> = protected.iter().map(|row| row.values[0]).collect();
+    let mean = n.iter().sum::<f64>() / n.len() as f64;
+    // Laplace(0, 2) has variance 8.
+    let variance = n.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n.len() as f64;
+    assert!((mean - 200.0).abs() < 0.3);
+    assert!((variance - 8.0).abs() < 1.5);
+    assert!(n.iter().all(|v| v.fract() == 0.0));
+    // Quantiles are not perturbed.
+    assert!(protected.iter().all(|row| row.values[3] == 7.456));
+}
+
+#[test]
+fn exports_label_suppressed_rows() {
+    let protected = protect_summary(&rows(), &DisclosurePolicy::default()).unwrap();
+    let csv = protected_csv(&protected);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[0].starts_with("model,stratifier,level,suppression,n,"));
+    assert!(lines[2].starts_with("10_yr_cvd,sex,female,primary,,,"));
+    assert!(lines[3].starts_with("10_yr_cvd,sex,male,complementary,,"));
+    assert!(lines[1].starts_with("10_yr_cvd,overall,all,,200,20,"));
+}
+
+#[test]
+fn invalid_policies_are_rejected() {
+    let data = rows();
+    for policy in [
+        DisclosurePolicy {
+            count_base: 0,
+            ..DisclosurePolicy::default()
+        },
+        DisclosurePolicy {
+            epsilon: Some(0.0),
+            ..DisclosurePolicy::default()
+        },
+    ] {
+        assert!(protect_summary(&data, &policy).is_err());
+    }
+}
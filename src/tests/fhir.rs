@@ -0,0 +1,277 @@
+use crate::fhir::{assess_bundle, inputs_from_bundle};
+use crate::model::{find_model, Sex};
+use serde_json::{json, Value};
+
+fn observation(id: &str, loinc: &str, date: &str, value: f64, unit: &str) -> Value {
+    json!({
+        "resourceType": "Observation",
+        "id": id,
+        "status": "final",
+        "subject": {"reference": "Patient/p1"},
+        "code": {"coding": [{"system": "http://loinc.org", "code": loinc}]},
+        "effectiveDateTime": date,
+        "valueQuantity": {"value": value, "unit": unit, "system": "http://unitsofmeasure.org", "code": unit},
+    })
+}
+
+fn bundle() -> Value {
+    let resources = vec![
+        json!({"resourceType": "Patient", "id": "p1", "gender": "female", "birthDate": "1974-06-15"}),
+        observation("tc-old", "2093-3", "2022-01-10", 240.0, "mg/dL"),
+        observation("tc", "2093-3", "2024-03-01", 200.0, "mg/dL"),
+        observation("tc-future", "2093-3", "2025-03-01", 150.0, "mg/dL"),
+        observation("hdl", "2085-9", "2024-03-01", 1.2, "mmol/L"),
+        json!({
+            "resourceType": "Observation",
+            "id": "bp",
+            "status": "final",
+            "subject": {"reference": "Patient/p1"},
+            "code": {"coding": [{"system": "http://loinc.org", "code": "85354-9"}]},
+            "effectiveDateTime": "2024-04-02T09:30:00Z",
+            "component": [
+                {
+                    "code": {"coding": [{"system": "http://loinc.org", "code": "8480-6"}]},
+                    "valueQuantity": {"value": 142, "unit": "mm[Hg]"},
+                },
+                {
+                    "code": {"coding": [{"system": "http://loinc.org", "code": "8462-4"}]},
+                    "valueQuantity": {"value": 88, "unit": "mm[Hg]"},
+                },
+            ],
+        }),
+        observation("bmi", "39156-5", "2024-04-02", 31.5, "kg/m2"),
+        observation("egfr", "98979-8", "2024-03-01", 72.0, "mL/min/{1.73_m2}"),
+        json!({
+            "resourceType": "Observation",
+            "id": "smoking",
+            "status": "final",
+            "subject": {"reference": "Patient/p1"},
+            "code": {"coding": [{"system": "http://loinc.org", "code": "72166-2"}]},
+            "effectiveDateTime": "2024-04-02",
+            "valueCodeableConcept": {"coding": [{"system": "http://snomed.info/sct", "code": "449868002"}]},
+        }),
+        json!({
+            "resourceType": "Condition",
+            "id": "dm",
+            "subject": {"reference": "Patient/p1"},
+            "clinicalStatus": {"coding": [{"code": "active"}]},
+            "code": {"coding": [{"system": "http://hl7.org/fhir/sid/icd-10-cm", "code": "E11.9"}]},
+            "onsetDateTime": "2019-05-20",
+        }),
+        json!({
+            "resourceType": "Medication",
+            "id": "amlodipine",
+            "code": {"coding": [{"system": "http://www.whocc.no/atc", "code": "C08CA01"}]},
+        }),
+        json!({
+            "resourceType": "MedicationStatement",
+            "id": "ms-htn",
+            "status": "active",
+            "subject": {"reference": "Patient/p1"},
+            "medicationReference": {"reference": "Medication/amlodipine"},
+            "dateAsserted": "2023-11-02",
+        }),
+        json!({
+            "resourceType": "MedicationStatement",
+            "id": "ms-statin",
+            "status": "stopped",
+            "subject": {"reference": "Patient/p1"},
+            "medicationCodeableConcept": {"coding": [{"system": "http://www.nlm.nih.gov/research/umls/rxnorm", "code": "83367"}]},
+        }),
+    ];
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": resources.into_iter().map(|resource| json!({"resource": resource})).collect::<Vec<_>>(),
+    })
+}
+
+#[test]
+fn bundle_maps_to_inputs_with_provenance() {
+    let inputs = inputs_from_bundle(&bundle(), "2024-06-01").unwrap();
+    let patient = &inputs.patient;
+    assert_eq!(patient.sex, Sex::Female);
+    assert_eq!(patient.age, 49.0);
+    assert_eq!(patient.total_cholesterol, 200.0);
+    assert!((patient.hdl_cholesterol - 1.2 / 0.02586).abs() < 1e-9);
+    assert_eq!(
+        (patient.systolic_bp, patient.bmi, patient.egfr),
+        (142.0, 31.5, 72.0)
+    );
+    assert!(patient.has_diabetes && patient.current_smoker && patient.on_htn_meds);
+    assert!(!patient.on_cholesterol_meds);
+
+    let provenance: Vec<Option<&str>> = inputs.provenance.iter().map(|p| p.as_deref()).collect();
+    assert_eq!(
+        provenance,
+        [
+            Some("Patient/p1"),
+            Some("Patient/p1"),
+            Some("Observation/tc"),
+            Some("Observation/hdl"),
+            Some("Observation/bp"),
+            Some("Condition/dm"),
+            Some("Observation/smoking"),
+            Some("Observation/bmi"),
+            Some("Observation/egfr"),
+            Some("MedicationStatement/ms-htn"),
+            None,
+        ]
+    );
+    assert_eq!(inputs.subject, "Patient/p1");
+
+    // Her birthday; the cholesterol measured later is still ignored.
+    let later = inputs_from_bundle(&bundle(), "2024-06-15").unwrap();
+    assert_eq!(later.patient.age, 50.0);
+    assert_eq!(later.patient.total_cholesterol, 200.0);
+}
+
+#[test]
+fn conditions_and_medications_after_the_date_are_ignored() {
+    let mut later = bundle();
+    later["entry"][9]["resource"]["onsetDateTime"] = json!("2024-08-01");
+    later["entry"][11]["resource"]["effectivePeriod"] = json!({"start": "2024-06-02"});
+    let inputs = inputs_from_bundle(&later, "2024-06-01").unwrap();
+    assert!(!inputs.patient.has_diabetes && !inputs.patient.on_htn_meds);
+    assert_eq!(
+        (&inputs.provenance[5], &inputs.provenance[9]),
+        (&None, &None)
+    );
+
+    let inputs = inputs_from_bundle(&later, "2024-08-01").unwrap();
+    assert!(inputs.patient.has_diabetes && inputs.patient.on_htn_meds);
+    assert_eq!(inputs.provenance[5].as_deref(), Some("Condition/dm"));
+
+    // Undated resources still count.
+    let mut undated = bundle();
+    let condition = undated["entry"][9]["resource"].as_object_mut().unwrap();
+    condition.remove("onsetDateTime");
+    assert!(
+        inputs_from_bundle(&undated, "2024-06-01")
+            .unwrap()
+            .patient
+            .has_diabetes
+    );
+}
+
+#[test]
+fn only_final_amended_or_corrected_observations_are_read() {
+    let mut preliminary = bundle();
+    preliminary["entry"][2]["resource"]["status"] = json!("preliminary");
+    let inputs = inputs_from_bundle(&preliminary, "2024-06-01").unwrap();
+    assert_eq!(inputs.patient.total_cholesterol, 240.0);
+    assert_eq!(inputs.provenance[2].as_deref(), Some("Observation/tc-old"));
+
+    preliminary["entry"][2]["resource"]["status"] = json!("corrected");
+    let inputs = inputs_from_bundle(&preliminary, "2024-06-01").unwrap();
+    assert_eq!(inputs.patient.total_cholesterol, 200.0);
+}
+
+#[test]
+fn statins_match_by_rxnorm_ingredient() {
+    // Atorvastatin 20 MG Oral Tablet (SCD), with its ingredient atorvastatin.
+    let scd = json!({"coding": [{"system": "http://www.nlm.nih.gov/research/umls/rxnorm", "code": "617310"}]});
+    let mut statin = bundle();
+    statin["entry"][12]["resource"]["status"] = json!("active");
+    statin["entry"][12]["resource"]["medicationCodeableConcept"] = scd.clone();
+    // An SCD code alone has no ingredient to match.
+    assert!(
+        !inputs_from_bundle(&statin, "2024-06-01")
+            .unwrap()
+            .patient
+            .on_cholesterol_meds
+    );
+
+    let statement = statin["entry"][12]["resource"].as_object_mut().unwrap();
+    statement.remove("medicationCodeableConcept");
+    statement.insert(
+        "medicationReference".to_string(),
+        json!({"reference": "Medication/atorvastatin"}),
+    );
+    statin["entry"].as_array_mut().unwrap().push(json!({"resource": {
+        "resourceType": "Medication",
+        "id": "atorvastatin",
+        "code": scd,
+        "ingredient": [{"itemCodeableConcept": {"coding": [{"system": "http://www.nlm.nih.gov/research/umls/rxnorm", "code": "83367"}]}}],
+    }}));
+    let inputs = inputs_from_bundle(&statin, "2024-06-01").unwrap();
+    assert!(inputs.patient.on_cholesterol_meds);
+    assert_eq!(
+        inputs.provenance[10].as_deref(),
+        Some("MedicationStatement/ms-statin")
+    );
+}
+
+#[test]
+fn risk_assessments_carry_predictions_and_basis() {
+    let bundle = bundle().to_string();
+    let assessment = assess_bundle(&bundle, &["10_yr_cvd", "30_yr_cvd"], "2024-06-01").unwrap();
+    assert_eq!(assessment.errors, vec![]);
+    let entries = assessment.bundle["entry"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+
+    let resource = &entries[0]["resource"];
+    let expected = find_model("10_yr_cvd")
+        .unwrap()
+        .predict(&assessment.inputs.patient)
+        .unwrap();
+    assert_eq!(resource["resourceType"], "RiskAssessment");
+    assert_eq!(resource["subject"]["reference"], "Patient/p1");
+    let prediction = &resource["prediction"][0];
+    assert!((prediction["probabilityDecimal"].as_f64().unwrap() - expected / 100.0).abs() < 1e-12);
+    assert_eq!(prediction["whenRange"]["high"]["value"], 10);
+    assert!(prediction["qualitativeRisk"]["text"].is_string());
+    let basis: Vec<&str> = resource["basis"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["reference"].as_str().unwrap())
+        .collect();
+    assert_eq!(basis.len(), 9);
+    assert!(!basis.contains(&"MedicationStatement/ms-statin"));
+    assert!(entries[1]["resource"]["prediction"][0]["qualitativeRisk"].is_null());
+}
+
+#[test]
+fn models_that_cannot_score_are_reported() {
+    // Aged 61 on this date, past the 30-year models' range.
+    let bundle = bundle().to_string();
+    let assessment = assess_bundle(&bundle, &["10_yr_cvd", "30_yr_cvd"], "2035-07-01").unwrap();
+    assert_eq!(assessment.bundle["entry"].as_array().unwrap().len(), 1);
+    assert_eq!(assessment.errors.len(), 1);
+    assert_eq!(assessment.errors[0].0, "30_yr_cvd");
+}
+
+#[test]
+fn incomplete_bundles_are_rejected() {
+    let mut missing_bmi = bundle();
+    missing_bmi["entry"]
+        .as_array_mut()
+        .unwrap()
+        .retain(|entry| entry["resource"]["id"] != "bmi");
+    let error = inputs_from_bundle(&missing_bmi, "2024-06-01").unwrap_err();
+    assert!(error.contains("bmi"));
+
+    let mut bad_unit = bundle();
+    bad_unit["entry"][1]["resource"]["valueQuantity"]["code"] = json!("g/L");
+    bad_unit["entry"][2]["resource"]["valueQuantity"]["code"] = json!("g/L");
+    assert!(inputs_from_bundle(&bad_unit, "2024-06-01").is_err());
+    for (i, unit) in [(6, "kg/m^2"), (7, "mL/min"), (5, "mmHg")] {
+        let mut bad_unit = bundle();
+        let resource = &mut bad_unit["entry"][i]["resource"];
+        let quantity = if i == 5 {
+            &mut resource["component"][0]["valueQuantity"]
+        } else {
+            &mut resource["valueQuantity"]
+        };
+        quantity["unit"] = json!(unit);
+        quantity["code"] = json!(unit);
+        let error = inputs_from_bundle(&bad_unit, "2024-06-01").unwrap_err();
+        assert!(error.contains(unit), "{}", error);
+    }
+
+    assert!(inputs_from_bundle(&bundle(), "June 2024").is_err());
+    assert!(inputs_from_bundle(&json!({"resourceType": "Patient"}), "2024-06-01").is_err());
+    assert!(assess_bundle("{not json", &["10_yr_cvd"], "2024-06-01").is_err());
+    assert!(assess_bundle(&bundle().to_string(), &["framingham"], "2024-06-01").is_err());
+}
@@ -0,0 +1,104 @@
+use crate::gradient::{gradient_fields, risk_gradient};
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+
+const STEP: f64 = 1e-5;
+
+fn with_field(patient: &Patient, field: &str, value: f64) -> Patient {
+    let mut patient = patient.clone();
+    match field {
+        "age" => patient.age = value,
+        "total_cholesterol" => patient.total_cholesterol = value,
+        "hdl_cholesterol" => patient.hdl_cholesterol = value,
+        "systolic_bp" => patient.systolic_bp = value,
+        "bmi" => patient.bmi = value,
+        "egfr" => patient.egfr = value,
+        _ => unreachable!(),
+    }
+    patient
+}
+
+fn log_odds(model: PreventModel, patient: &Patient) -> f64 {
+    patient.score(model.unchecked_log_odds_fn()).unwrap()
+}
+
+/// Central differences, away from the spline knots.
+fn assert_matches_finite_differences(patient: &Patient) {
+    for model in PreventModel::ALL {
+        let gradient = risk_gradient(model, patient).unwrap();
+        for (i, field) in gradient_fields().iter().enumerate() {
+            let value = patient.value(field).unwrap();
+            let above = with_field(patient, field, value + STEP);
+            let below = with_field(patient, field, value - STEP);
+            let log_odds_slope = (log_odds(model, &above) - log_odds(model, &below)) / (2.0 * STEP);
+            let risk_slope =
+                (model.predict(&above).unwrap() - model.predict(&below).unwrap()) / (2.0 * STEP);
+            assert!(
+                (gradient.log_odds_gradient[i] - log_odds_slope).abs() < 1e-6,
+                "{} {}: {} vs {}",
+                model.metadata().key,
+                field,
+                gradient.log_odds_gradient[i],
+                log_odds_slope
+            );
+            assert!(
+                (gradient.risk_gradient[i] - risk_slope).abs() < 1e-6,
+                "{} {}",
+                model.metadata().key,
+                field
+            );
+        }
+    }
+}
+
+#[test]
+fn gradient_above_knots() {
+    assert_matches_finite_differences(&Patient {
+        sex: Sex::Female,
+        age: 50.0,
+        total_cholesterol: 220.0,
+        hdl_cholesterol: 45.0,
+        systolic_bp: 145.0,
+        has_diabetes: true,
+        current_smoker: true,
+        bmi: 33.0,
+        egfr: 75.0,
+        on_htn_meds: true,
+        on_cholesterol_meds: true,
+    });
+}
+
+#[test]
+fn gradient_below_knots() {
+    assert_matches_finite_differences(&Patient {
+        sex: Sex::Male,
+        age: 42.0,
+        total_cholesterol: 180.0,
+        hdl_cholesterol: 60.0,
+        systolic_bp: 100.0,
+        has_diabetes: false,
+        current_smoker: false,
+        bmi: 24.0,
+        egfr: 40.0,
+        on_htn_meds: false,
+        on_cholesterol_meds: false,
+    });
+}
+
+#[test]
+fn gradient_uses_slope_above_knot() {
+    let patient = Patient {
+        systolic_bp: 110.0,
+        ..Patient::optimal(Sex::Male, 50.0)
+    };
+    let at_knot = risk_gradient(PreventModel::Cvd10Yr, &patient).unwrap();
+    let above = risk_gradient(
+        PreventModel::Cvd10Yr,
+        &Patient {
+            systolic_bp: 111.0,
+            ..patient.clone()
+        },
+    )
+    .unwrap();
+    assert_eq!(at_knot.log_odds_gradient[3], above.log_odds_gradient[3]);
+    assert!(risk_gradient(PreventModel::Cvd30Yr, &Patient::optimal(Sex::Male, 70.0)).is_err());
+}
@@ -0,0 +1,120 @@
+use super::test_patient;
+use crate::grpc::proto::risk_scoring_client::RiskScoringClient;
+use crate::grpc::proto::risk_scoring_server::RiskScoringServer;
+use crate::grpc::proto::{self, ScoreRequest};
+use crate::grpc::{score_requests, ScoringService, CHUNK_SIZE};
+use crate::model::{models, Patient, PreventModel, RiskModel};
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_stream::StreamExt;
+use tonic::transport::Server;
+
+/// `test_patient` at `age`, as a message.
+fn patient(age: f64) -> proto::Patient {
+    let patient = test_patient();
+    proto::Patient {
+        sex: proto::Sex::Female as i32,
+        age,
+        total_cholesterol: patient.total_cholesterol,
+        hdl_cholesterol: patient.hdl_cholesterol,
+        systolic_bp: patient.systolic_bp,
+        has_diabetes: patient.has_diabetes,
+        current_smoker: patient.current_smoker,
+        bmi: patient.bmi,
+        egfr: patient.egfr,
+        on_htn_meds: patient.on_htn_meds,
+        on_cholesterol_meds: patient.on_cholesterol_meds,
+    }
+}
+
+fn request(id: usize, age: f64, models: &[&str]) -> ScoreRequest {
+    ScoreRequest {
+        id: id.to_string(),
+        patient: Some(patient(age)),
+        models: models.iter().map(|key| key.to_string()).collect(),
+    }
+}
+
+#[test]
+fn requests_match_single_patient_predictions() {
+    let responses = score_requests(&[request(0, 55.0, &[]), request(1, 65.0, &["30_yr_cvd"])]);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].results.len(), models().len());
+
+    let expected = Patient {
+        age: 55.0,
+        ..test_patient()
+    };
+    for (model, result) in PreventModel::ALL.iter().zip(&responses[0].results) {
+        assert_eq!(result.model, model.metadata().key);
+        assert_eq!(result.risk, Some(model.predict(&expected).unwrap()));
+        assert_eq!(result.category.is_empty(), !model.is_ten_year());
+        assert!(result.error.is_empty());
+    }
+
+    let older = &responses[1];
+    assert_eq!(older.id, "1");
+    assert_eq!(older.results.len(), 1);
+    assert_eq!(older.results[0].risk, None);
+    assert_eq!(older.results[0].error, "Age must be between 30 and 59");
+}
+
+#[test]
+fn bad_requests_report_errors() {
+    let mut unspecified = request(0, 55.0, &[]);
+    unspecified.patient.as_mut().unwrap().sex = proto::Sex::Unspecified as i32;
+    let missing = ScoreRequest {
+        patient: None,
+        ..request(1, 55.0, &[])
+    };
+    let responses = score_requests(&[
+        unspecified,
+        missing,
+        request(2, 55.0, &["5_yr_cvd"]),
+        request(3, 55.0, &["10_yr_hf"]),
+    ]);
+    assert!(responses[..3]
+        .iter()
+        .all(|response| !response.error.is_empty() && response.results.is_empty()));
+    assert!(responses[3].error.is_empty());
+}
+
+#[tokio::test]
+async fn unary_and_streaming_calls() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(RiskScoringServer::new(ScoringService))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    let mut client = RiskScoringClient::connect(format!("http://{}", address))
+        .await
+        .unwrap();
+
+    let single = client
+        .score(request(7, 50.0, &["10_yr_ascvd"]))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(single.id, "7");
+    assert!(single.results[0].risk.is_some());
+
+    // More than one chunk, so responses span several parallel batches.
+    let n = CHUNK_SIZE + 100;
+    let requests: Vec<ScoreRequest> = (0..n)
+        .map(|i| request(i, 30.0 + (i % 50) as f64, &["10_yr_cvd", "30_yr_cvd"]))
+        .collect();
+    let expected = score_requests(&requests);
+    let responses: Vec<_> = client
+        .score_stream(tokio_stream::iter(requests))
+        .await
+        .unwrap()
+        .into_inner()
+        .map(|response| response.unwrap())
+        .collect()
+        .await;
+    assert_eq!(responses, expected);
+    assert!(responses
+        .iter()
+        .any(|response| response.results[1].risk.is_none()));
+}
@@ -0,0 +1,67 @@
+use crate::heart_age::{calculate_heart_age, heart_age, HeartAgeBound};
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+
+#[test]
+fn optimal_profile_has_chronological_heart_age() {
+    for sex in [Sex::Female, Sex::Male] {
+        for age in [35.0, 50.0, 72.5] {
+            let result = heart_age(&Patient::optimal(sex, age)).unwrap();
+            assert_eq!(result.bound, HeartAgeBound::Within);
+            assert!(result.difference.abs() < 1e-5, "{:?}", result);
+        }
+    }
+}
+
+#[test]
+fn heart_age_matches_patient_risk() {
+    let result = calculate_heart_age(
+        "male", 50.0, 240.0, 40.0, 150.0, false, true, 30.0, 80.0, true, false,
+    )
+    .unwrap();
+    assert_eq!(result.bound, HeartAgeBound::Within);
+    assert!(result.heart_age > 50.0);
+    assert!((result.difference - (result.heart_age - 50.0)).abs() < 1e-12);
+
+    let patient_risk = PreventModel::Cvd10Yr
+        .predict(&Patient {
+            sex: Sex::Male,
+            age: 50.0,
+            total_cholesterol: 240.0,
+            hdl_cholesterol: 40.0,
+            systolic_bp: 150.0,
+            has_diabetes: false,
+            current_smoker: true,
+            bmi: 30.0,
+            egfr: 80.0,
+            on_htn_meds: true,
+            on_cholesterol_meds: false,
+        })
+        .unwrap();
+    let reference_risk = PreventModel::Cvd10Yr
+        .predict(&Patient::optimal(Sex::Male, result.heart_age))
+        .unwrap();
+    assert!((patient_risk - reference_risk).abs() < 1e-4);
+}
+
+#[test]
+fn heart_age_is_capped_at_age_bounds() {
+    let result = calculate_heart_age(
+        "female", 30.0, 130.0, 100.0, 90.0, false, false, 25.0, 90.0, false, false,
+    )
+    .unwrap();
+    assert_eq!(result.bound, HeartAgeBound::BelowMinimum);
+    assert_eq!(result.heart_age, 30.0);
+
+    let result = calculate_heart_age(
+        "male", 75.0, 320.0, 20.0, 200.0, true, true, 39.0, 15.0, true, false,
+    )
+    .unwrap();
+    assert_eq!(result.bound, HeartAgeBound::AboveMaximum);
+    assert_eq!(result.heart_age, 79.0);
+    assert_eq!(result.difference, 4.0);
+
+    assert!(calculate_heart_age(
+        "male", 80.0, 200.0, 50.0, 120.0, false, false, 25.0, 90.0, false, false
+    )
+    .is_err());
+}
@@ -0,0 +1,172 @@
+use crate::hl7::{ckd_epi_2021, inputs_from_oru};
+use crate::model::{find_model, Sex};
+use crate::score2::CHOLESTEROL_MMOL_PER_MG;
+
+fn message(obx: &[&str]) -> String {
+    let mut segments = vec![
+        "MSH|^~\\&|LAB|HOSP|EHR|HOSP|20240601120000||ORU^R01^ORU_R01|MSG0001|P|2.5.1",
+        "PID|1||12345^^^HOSP^MR||DOE^JANE||19740615|F",
+        "OBR|1|||57698-3^Lipid panel^LN|||20240530081500",
+    ];
+    segments.extend(obx);
+    segments.join("\r")
+}
+
+const LIPIDS: [&str; 2] = [
+    "OBX|1|NM|2093-3^Cholesterol^LN||5.2|mmol/L|||||F",
+    "OBX|2|NM|2085-9^HDL^LN||55|mg/dL|||||F",
+];
+
+fn value(inputs: &crate::hl7::Hl7Inputs, field: &str) -> f64 {
+    let i = crate::model::PATIENT_FIELDS
+        .iter()
+        .position(|f| *f == field)
+        .unwrap();
+    inputs.values[i]
+}
+
+#[test]
+fn oru_maps_to_patient_inputs() {
+    let mut obx = LIPIDS.to_vec();
+    obx.extend([
+        "OBX|3|NM|8480-6^Systolic BP^LN||138|mm[Hg]|||||F|||20240529",
+        "OBX|4|NM|39156-5^BMI^LN||27.5|kg/m2|||||F",
+        "OBX|5|NM|98979-8^eGFR^LN||>90|mL/min/{1.73_m2}|||||F",
+        "OBX|6|CWE|72166-2^Smoking status^LN||449868002^Current every day smoker^SCT||||||F",
+    ]);
+    let inputs = inputs_from_oru(&message(&obx), &[], None).unwrap();
+    assert!(inputs.missing.is_empty());
+
+    let patient = inputs.patient().unwrap();
+    assert_eq!((patient.sex, patient.age), (Sex::Female, 49.0));
+    assert!((patient.total_cholesterol - 5.2 / CHOLESTEROL_MMOL_PER_MG).abs() < 1e-9);
+    assert_eq!(
+        (
+            patient.hdl_cholesterol,
+            patient.systolic_bp,
+            patient.bmi,
+            patient.egfr
+        ),
+        (55.0, 138.0, 27.5, 90.0)
+    );
+    assert!(patient.current_smoker);
+    assert!(!patient.has_diabetes && !patient.on_htn_meds && !patient.on_cholesterol_meds);
+    assert!(find_model("10_yr_cvd").unwrap().predict(&patient).is_ok());
+
+    let provenance: Vec<Option<&str>> = inputs.provenance.iter().map(|p| p.as_deref()).collect();
+    assert_eq!(
+        provenance,
+        [
+            Some("PID"),
+            Some("PID"),
+            Some("OBX-1"),
+            Some("OBX-2"),
+            Some("OBX-3"),
+            None,
+            Some("OBX-6"),
+            Some("OBX-4"),
+            Some("OBX-5"),
+            None,
+            None,
+        ]
+    );
+    assert_eq!(inputs.warnings.len(), 1);
+    assert!(inputs.warnings[0].contains(">90"), "{:?}", inputs.warnings);
+}
+
+#[test]
+fn missing_values_are_reported() {
+    let inputs = inputs_from_oru(&message(&LIPIDS), &[], None).unwrap();
+    assert_eq!(inputs.missing, ["systolic_bp", "bmi", "egfr"]);
+    assert!(inputs.patient().is_none());
+    assert!(value(&inputs, "egfr").is_nan());
+    assert_eq!(value(&inputs, "current_smoker"), 0.0);
+}
+
+#[test]
+fn egfr_and_bmi_are_derived() {
+    let mut obx = LIPIDS.to_vec();
+    obx.extend([
+        "OBX|3|NM|8480-6^Systolic BP^LN||138|mm[Hg]|||||F",
+        "OBX|4|NM|14682-9^Creatinine^LN||79.6|umol/L|||||F",
+        "OBX|5|NM|29463-7^Weight^LN||154|[lb_av]|||||F",
+        "OBX|6|NM|8302-2^Height^LN||1.65|m|||||F",
+    ]);
+    let inputs = inputs_from_oru(&message(&obx), &[], None).unwrap();
+    assert!(inputs.missing.is_empty(), "{:?}", inputs.missing);
+
+    let egfr = ckd_epi_2021(79.6 / 88.42, 49.0, Sex::Female);
+    assert!((value(&inputs, "egfr") - egfr).abs() < 1e-9);
+    // 0.9 mg/dL at 49: 142 * (0.9 / 0.7)^-1.2 * 0.9938^49 * 1.012.
+    assert!((egfr - 78.34).abs() < 0.01, "{}", egfr);
+    let bmi = 154.0 * 0.453_592_37 / 1.65f64.powi(2);
+    assert!((value(&inputs, "bmi") - bmi).abs() < 1e-9);
+    assert_eq!(inputs.provenance[7].as_deref(), Some("OBX-5, OBX-6"));
+    assert_eq!(
+        inputs.provenance[8].as_deref(),
+        Some("OBX-4 (CKD-EPI 2021)")
+    );
+}
+
+#[test]
+fn measurements_in_other_units_are_skipped() {
+    let mut obx = LIPIDS.to_vec();
+    obx.extend([
+        "OBX|3|NM|8480-6^Systolic BP^LN||18.4|kPa|||||F",
+        "OBX|4|NM|39156-5^BMI^LN||27.5||||||F",
+        "OBX|5|NM|98979-8^eGFR^LN||90|mL/min|||||F",
+    ]);
+    let inputs = inputs_from_oru(&message(&obx), &[], None).unwrap();
+    assert_eq!(inputs.missing, ["systolic_bp", "bmi", "egfr"]);
+    assert_eq!(inputs.warnings.len(), 3);
+    assert!(inputs.warnings[0].contains("unknown unit 'kPa'"));
+    assert!(inputs.warnings[2].contains("unknown unit 'mL/min'"));
+}
+
+#[test]
+fn local_codes_and_result_status() {
+    let obx = [
+        "OBX|1|NM|CHOL^Cholesterol^L||200|mg/dL|||||F",
+        "OBX|2|NM|HDLC^HDL^L||40|mg/dL|||||F",
+        "OBX|3|NM|HDLC^HDL^L||99|mg/dL|||||D",
+        "OBX|4|NM|SBP^Systolic^L||150|mmHg|||||C|||20240531",
+        "OBX|5|NM|SBP^Systolic^L||170|mmHg|||||F|||20240602",
+        "OBX|6|NM|2093-3^Cholesterol^LN||210|g/L|||||F",
+        "OBX|7|NM|SBP^Systolic^L||160|mmHg|||||P|||20240601",
+    ];
+    let local = [
+        ("CHOL", "total_cholesterol"),
+        ("HDLC", "hdl_cholesterol"),
+        ("SBP", "systolic_bp"),
+    ];
+    let inputs = inputs_from_oru(&message(&obx), &local, None).unwrap();
+    assert_eq!(value(&inputs, "total_cholesterol"), 200.0);
+    assert_eq!(value(&inputs, "hdl_cholesterol"), 40.0);
+    // The later readings are preliminary or after the message date.
+    assert_eq!(value(&inputs, "systolic_bp"), 150.0);
+    assert!(inputs.warnings[0].contains("unknown unit 'g/L'"));
+
+    let later = inputs_from_oru(&message(&obx), &local, Some("2024-06-30")).unwrap();
+    assert_eq!(value(&later, "systolic_bp"), 170.0);
+
+    assert!(inputs_from_oru(&message(&obx), &[("X", "ldl")], None).is_err());
+}
+
+#[test]
+fn invalid_messages_are_rejected() {
+    assert!(inputs_from_oru("PID|1", &[], None).is_err());
+    let adt = message(&LIPIDS).replace("ORU^R01^ORU_R01", "ADT^A01");
+    assert!(inputs_from_oru(&adt, &[], None).is_err());
+    let two = format!("{}\rPID|2||999||DOE^JOHN||19600101|M", message(&LIPIDS));
+    assert!(inputs_from_oru(&two, &[], None).is_err());
+    assert!(inputs_from_oru(&message(&LIPIDS), &[], Some("June 1")).is_err());
+
+    // Newline separators and custom delimiters.
+    let custom = message(&LIPIDS)
+        .replace("MSH|^~\\&|", "MSH#$~\\&#")
+        .replace('|', "#")
+        .replace('^', "$")
+        .replace('\r', "\n");
+    let inputs = inputs_from_oru(&custom, &[], None).unwrap();
+    assert_eq!(value(&inputs, "hdl_cholesterol"), 55.0);
+}
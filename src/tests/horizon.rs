@@ -0,0 +1,87 @@
+use super::test_patient;
+use crate::horizon::{calculate_risk_at_horizon, risk_at_horizon, HORIZON_METHOD};
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+
+fn patient(age: f64) -> Patient {
+    Patient {
+        age,
+        ..test_patient()
+    }
+}
+
+#[test]
+fn reproduces_model_horizons() {
+    assert!((risk_at_horizon(8.0, Some(25.0), 10.0).unwrap() - 8.0).abs() < 1e-12);
+    assert!((risk_at_horizon(8.0, Some(25.0), 30.0).unwrap() - 25.0).abs() < 1e-12);
+    assert!((risk_at_horizon(8.0, None, 10.0).unwrap() - 8.0).abs() < 1e-12);
+}
+
+#[test]
+fn constant_hazard_within_intervals() {
+    // Half the cumulative hazard of the 10-year risk at 5 years.
+    let five_year = risk_at_horizon(19.0, None, 5.0).unwrap();
+    assert!((five_year - 100.0 * (1.0 - 0.81_f64.sqrt())).abs() < 1e-12);
+    assert!((five_year - 10.0).abs() < 1e-12);
+
+    let risks: Vec<f64> = (1..=30)
+        .map(|year| risk_at_horizon(8.0, Some(25.0), year as f64).unwrap())
+        .collect();
+    assert!(risks.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn rejects_invalid_horizons_and_inputs() {
+    assert!(risk_at_horizon(8.0, Some(25.0), 0.5).is_err());
+    assert!(risk_at_horizon(8.0, Some(25.0), 31.0).is_err());
+    assert!(risk_at_horizon(8.0, None, 20.0).is_err());
+}
+
+#[test]
+fn clamps_thirty_year_hazard_below_ten_year_hazard() {
+    assert!((risk_at_horizon(8.0, Some(5.0), 20.0).unwrap() - 8.0).abs() < 1e-12);
+    assert!((risk_at_horizon(8.0, Some(5.0), 30.0).unwrap() - 8.0).abs() < 1e-12);
+
+    // Untreated severe hypertension and kidney failure in a lean, diabetic
+    // smoker give a 10-year heart failure risk (68.7%) above the 30-year one
+    // (66.1%).
+    let extreme = Patient {
+        sex: Sex::Male,
+        age: 56.0,
+        systolic_bp: 194.0,
+        egfr: 15.0,
+        bmi: 18.5,
+        current_smoker: true,
+        on_htn_meds: false,
+        ..test_patient()
+    };
+    let ten_year = PreventModel::HeartFailure10Yr.predict(&extreme).unwrap();
+    let thirty_year = PreventModel::HeartFailure30Yr.predict(&extreme).unwrap();
+    assert!(thirty_year < ten_year);
+
+    let derived = calculate_risk_at_horizon("hf", &extreme, 20.0).unwrap();
+    assert!(derived.hazard_clamped);
+    assert!((derived.risk - ten_year).abs() < 1e-9);
+    assert!(
+        !calculate_risk_at_horizon("hf", &extreme, 5.0)
+            .unwrap()
+            .hazard_clamped
+    );
+    assert!(
+        !calculate_risk_at_horizon("hf", &patient(45.0), 20.0)
+            .unwrap()
+            .hazard_clamped
+    );
+}
+
+#[test]
+fn derives_from_prevent_predictions() {
+    let derived = calculate_risk_at_horizon("ascvd", &patient(45.0), 30.0).unwrap();
+    assert_eq!(derived.method, HORIZON_METHOD);
+    let thirty_year = PreventModel::Ascvd30Yr.predict(&patient(45.0)).unwrap();
+    assert!((derived.risk - thirty_year).abs() < 1e-9);
+
+    // Only the 10-year model, and its age range, is needed up to 10 years.
+    assert!(calculate_risk_at_horizon("hf", &patient(70.0), 5.0).is_ok());
+    assert!(calculate_risk_at_horizon("hf", &patient(70.0), 20.0).is_err());
+    assert!(calculate_risk_at_horizon("stroke", &patient(45.0), 5.0).is_err());
+}
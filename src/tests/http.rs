@@ -0,0 +1,53 @@
+use crate::http::{listen, serve};
+use serde_json::json;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+/// Sends a raw HTTP/1.0 request and returns the status line and body.
+fn send(address: &str, request: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response.lines().next().unwrap_or_default().to_string();
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body.to_string())
+        .unwrap_or_default();
+    (status, body)
+}
+
+fn post(path: &str, body: &str) -> String {
+    format!(
+        "POST {} HTTP/1.0\r\nContent-Length: {}\r\n\r\n{}",
+        path,
+        body.len(),
+        body
+    )
+}
+
+#[test]
+fn requests_are_limited_and_routed() {
+    let server = listen("127.0.0.1:0").unwrap();
+    let address = server.server_addr().to_ip().unwrap().to_string();
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            serve(&server, 8, 2, &[("X-Test", "yes")], |method, path, body| {
+                (200, json!({"method": method, "path": path, "body": body}))
+            })
+        });
+
+        let (status, body) = send(&address, &post("/echo?x=1", "12345678"));
+        assert!(status.contains("200"), "{}", status);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            json!({"method": "POST", "path": "/echo", "body": "12345678"})
+        );
+        let (status, _) = send(&address, &post("/echo", "123456789"));
+        assert!(status.contains("413"), "{}", status);
+
+        for _ in 0..2 {
+            server.unblock();
+        }
+    });
+}
@@ -0,0 +1,55 @@
+use crate::cvd::{calculate_10_yr_cvd_log_odds, calculate_10_yr_cvd_risk};
+use crate::utils::{log_risk_from_log_odds, risk_from_log_odds};
+
+#[test]
+fn risk_at_extreme_log_odds() {
+    for log_odds in [-1e6, -800.0, -40.0, 0.0, 40.0, 800.0, 1e6] {
+        let risk = risk_from_log_odds(log_odds);
+        assert!((0.0..=100.0).contains(&risk), "{log_odds}: {risk}");
+    }
+    assert_eq!(risk_from_log_odds(0.0), 50.0);
+    assert_eq!(risk_from_log_odds(800.0), 100.0);
+    assert_eq!(risk_from_log_odds(f64::INFINITY), 100.0);
+    assert_eq!(risk_from_log_odds(f64::NEG_INFINITY), 0.0);
+    assert!((risk_from_log_odds(-40.0) - 100.0 * (-40.0f64).exp()).abs() < 1e-25);
+}
+
+#[test]
+fn risk_is_symmetric_in_log_odds() {
+    for log_odds in [0.1, 1.0, 5.0, 20.0] {
+        let sum = risk_from_log_odds(log_odds) + risk_from_log_odds(-log_odds);
+        assert!((sum - 100.0).abs() < 1e-12, "{log_odds}: {sum}");
+    }
+}
+
+#[test]
+fn log_risk_at_extreme_log_odds() {
+    // ln(sigmoid(x)) ~ x for very negative x, where the risk itself underflows
+    assert_eq!(risk_from_log_odds(-800.0), 0.0);
+    assert_eq!(log_risk_from_log_odds(-800.0), -800.0);
+    assert_eq!(log_risk_from_log_odds(800.0), 0.0);
+    assert_eq!(log_risk_from_log_odds(f64::NEG_INFINITY), f64::NEG_INFINITY);
+    assert_eq!(log_risk_from_log_odds(f64::INFINITY), 0.0);
+    assert!((log_risk_from_log_odds(0.0) - 0.5f64.ln()).abs() < 1e-15);
+    for log_odds in [-30.0, -3.0, 0.5, 3.0, 30.0] {
+        let via_risk = (risk_from_log_odds(log_odds) / 100.0).ln();
+        assert!((log_risk_from_log_odds(log_odds) - via_risk).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn risk_matches_log_odds() {
+    let args = (
+        "male", 55.0, 213.0, 50.0, 120.0, false, false, 27.0, 90.0, false, false,
+    );
+    let log_odds = calculate_10_yr_cvd_log_odds(
+        args.0, args.1, args.2, args.3, args.4, args.5, args.6, args.7, args.8, args.9, args.10,
+    )
+    .unwrap();
+    let risk = calculate_10_yr_cvd_risk(
+        args.0, args.1, args.2, args.3, args.4, args.5, args.6, args.7, args.8, args.9, args.10,
+    )
+    .unwrap();
+    assert_eq!(risk, risk_from_log_odds(log_odds));
+    assert!((log_risk_from_log_odds(log_odds) - (risk / 100.0).ln()).abs() < 1e-12);
+}
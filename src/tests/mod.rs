@@ -0,0 +1,50 @@
+use crate::model::{Patient, Sex};
+
+#[cfg(feature = "cds-hooks")]
+mod cds_hooks;
+mod cohort_summary;
+mod confidence_interval;
+mod disclosure;
+mod fhir;
+mod golden;
+mod gradient;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod heart_age;
+mod hl7;
+mod horizon;
+#[cfg(any(feature = "rest", feature = "cds-hooks"))]
+mod http;
+mod logistic;
+mod model;
+mod model_comparison;
+mod monte_carlo;
+mod peer_comparison;
+mod plausibility;
+mod pooled_cohort;
+mod properties;
+mod range_policy;
+#[cfg(feature = "rest")]
+mod rest;
+mod score2;
+mod survey;
+mod trajectory;
+mod validation;
+
+/// The patient most tests start from, like `TEST_PATIENT` in
+/// `tests/fixtures.py`. Tests that depend on particular inputs override them.
+fn test_patient() -> Patient {
+    Patient {
+        sex: Sex::Female,
+        age: 50.0,
+        total_cholesterol: 200.0,
+        hdl_cholesterol: 45.0,
+        systolic_bp: 160.0,
+        has_diabetes: true,
+        current_smoker: false,
+        bmi: 35.0,
+        egfr: 90.0,
+        on_htn_meds: true,
+        on_cholesterol_meds: false,
+    }
+}
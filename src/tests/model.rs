@@ -0,0 +1,87 @@
+use super::test_patient;
+use crate::model::{find_model, models, predict_batch, risk_category, PreventModel, RiskModel};
+use crate::utils::{validate_input, RiskCategory};
+
+#[test]
+fn prevent_models_match_outcome_functions() {
+    let patient = test_patient();
+    for model in PreventModel::ALL {
+        assert_eq!(
+            model.predict(&patient),
+            patient.score(model.risk_calc_fn()),
+            "{}",
+            model.metadata().key
+        );
+    }
+}
+
+#[test]
+fn registry_keys_are_unique_and_selectable() {
+    let keys: Vec<_> = models().iter().map(|model| model.metadata().key).collect();
+    assert_eq!(
+        keys[..6],
+        [
+            "10_yr_cvd",
+            "30_yr_cvd",
+            "10_yr_ascvd",
+            "30_yr_ascvd",
+            "10_yr_hf",
+            "30_yr_hf"
+        ]
+    );
+    for (i, key) in keys.iter().enumerate() {
+        assert!(!keys[..i].contains(key), "duplicate key {}", key);
+        assert_eq!(find_model(key).unwrap().metadata().key, *key);
+    }
+    for model in PreventModel::ALL {
+        let key = model.metadata().key;
+        assert_eq!(PreventModel::from_key(key), Ok(model));
+    }
+    assert_eq!(
+        find_model("10_YR_CVD").unwrap().metadata().horizon_years,
+        10
+    );
+    assert!(find_model("framingham").is_err());
+    assert!(PreventModel::from_key("framingham").is_err());
+}
+
+#[test]
+fn default_validation_matches_validate_input() {
+    let mut patient = test_patient();
+    for age in [29.0, 30.0, 59.0, 60.0, 79.0, 80.0] {
+        patient.age = age;
+        for model in PreventModel::ALL {
+            let expected = validate_input(
+                patient.age,
+                patient.total_cholesterol,
+                patient.hdl_cholesterol,
+                patient.systolic_bp,
+                patient.bmi,
+                patient.egfr,
+                model.is_ten_year(),
+            );
+            assert_eq!(model.validate(&patient), expected);
+        }
+    }
+}
+
+#[test]
+fn batch_returns_nan_for_invalid_patients() {
+    let mut invalid = test_patient();
+    invalid.egfr = 10.0;
+    let risks = predict_batch(&PreventModel::Cvd10Yr, &[test_patient(), invalid]);
+    assert_eq!(
+        risks[0],
+        PreventModel::Cvd10Yr.predict(&test_patient()).unwrap()
+    );
+    assert!(risks[1].is_nan());
+}
+
+#[test]
+fn only_ten_year_prevent_risks_have_a_category() {
+    for model in PreventModel::ALL {
+        let category = risk_category(&model, 12.0);
+        let expected = model.is_ten_year().then_some(RiskCategory::Intermediate);
+        assert_eq!(category, expected, "{}", model.metadata().key);
+    }
+}
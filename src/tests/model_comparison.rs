@@ -0,0 +1,128 @@
+use crate::model_comparison::{compare_models, paired_observations, PairedObservation};
+use crate::utils::RiskCategory;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Four events and six non-events, all at 6% on the reference model. The
+/// comparison model moves three events and one non-event up to 25% and the
+/// rest down to 2%.
+fn example() -> Vec<PairedObservation> {
+    (0..10)
+        .map(|i| {
+            let event = i < 4;
+            PairedObservation {
+                reference: 6.0,
+                comparison: if i < 3 || i == 4 { 25.0 } else { 2.0 },
+                event,
+                time: if event { 2.0 } else { 10.0 },
+            }
+        })
+        .collect()
+}
+
+fn noisy(n: usize, seed: u64) -> Vec<PairedObservation> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| {
+            let true_risk: f64 = rng.gen_range(1.0..40.0);
+            let event = rng.gen::<f64>() < true_risk / 100.0;
+            PairedObservation {
+                reference: (true_risk + rng.gen_range(-15.0..15.0)).clamp(0.5, 60.0),
+                comparison: (true_risk + rng.gen_range(-5.0..5.0)).clamp(0.5, 60.0),
+                event,
+                time: if event {
+                    rng.gen_range(0.0..10.0)
+                } else {
+                    10.0
+                },
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn metrics_match_hand_calculation() {
+    let result = compare_models(
+        &example(),
+        10.0,
+        &RiskCategory::THRESHOLDS,
+        &[10.0],
+        0,
+        0.95,
+        0,
+    )
+    .unwrap();
+    let metrics = result.metrics;
+    assert_eq!((result.n, result.events), (10, 4));
+    assert!((metrics.categorical_nri_events - 0.5).abs() < 1e-12);
+    assert!((metrics.categorical_nri_non_events - 4.0 / 6.0).abs() < 1e-12);
+    assert!((metrics.categorical_nri - (0.5 + 4.0 / 6.0)).abs() < 1e-12);
+    assert_eq!(metrics.continuous_nri, metrics.categorical_nri);
+    let idi = (3.0 * 19.0 - 4.0) / 4.0 / 100.0 - (19.0 - 5.0 * 4.0) / 6.0 / 100.0;
+    assert!((metrics.idi - idi).abs() < 1e-12);
+
+    let point = result.net_benefit[0];
+    assert_eq!(point.threshold, 10.0);
+    assert_eq!(point.reference, 0.0);
+    assert!((point.comparison - (3.0 - 1.0 / 9.0) / 10.0).abs() < 1e-12);
+    assert!((point.treat_all - (4.0 - 6.0 / 9.0) / 10.0).abs() < 1e-12);
+    assert!(result.intervals.is_none());
+}
+
+#[test]
+fn identical_models_show_no_improvement() {
+    let data: Vec<PairedObservation> = noisy(500, 1)
+        .into_iter()
+        .map(|p| PairedObservation {
+            comparison: p.reference,
+            ..p
+        })
+        .collect();
+    let result = compare_models(&data, 10.0, &[7.5], &[5.0, 20.0], 0, 0.95, 0).unwrap();
+    assert!(result.metrics.to_array().iter().all(|value| *value == 0.0));
+    for point in result.net_benefit {
+        assert_eq!(point.reference, point.comparison);
+    }
+}
+
+#[test]
+fn better_model_improves_with_bootstrap_intervals() {
+    let data = noisy(3000, 2);
+    let thresholds = [5.0, 7.5, 20.0];
+    let result = compare_models(&data, 10.0, &thresholds, &thresholds, 100, 0.95, 4).unwrap();
+    assert!(result.metrics.continuous_nri > 0.0);
+    assert!(result.metrics.idi > 0.0);
+
+    let intervals = result.intervals.as_ref().unwrap();
+    assert_eq!(intervals.len(), 7 + 2 * thresholds.len());
+    for (estimate, (lower, upper)) in result.metrics.to_array().iter().zip(intervals) {
+        assert!(lower <= estimate && estimate <= upper);
+    }
+    assert!(intervals[6].0 > 0.0);
+    assert_eq!(
+        result,
+        compare_models(&data, 10.0, &thresholds, &thresholds, 100, 0.95, 4).unwrap()
+    );
+}
+
+#[test]
+fn invalid_inputs_are_rejected() {
+    assert!(paired_observations(&[1.0], &[1.0, 2.0], &[true], &[1.0]).is_err());
+    assert!(paired_observations(&[1.0], &[101.0], &[true], &[1.0]).is_err());
+    let kept = paired_observations(
+        &[1.0, f64::NAN, 3.0],
+        &[1.0, 2.0, f64::NAN],
+        &[true, false, false],
+        &[1.0, 2.0, 3.0],
+    )
+    .unwrap();
+    assert_eq!(kept.len(), 1);
+
+    let data = example();
+    assert!(compare_models(&data[..9], 10.0, &[5.0], &[], 0, 0.95, 0).is_err());
+    assert!(compare_models(&data, 10.0, &[7.5, 5.0], &[], 0, 0.95, 0).is_err());
+    assert!(compare_models(&data, 10.0, &[], &[], 0, 0.95, 0).is_err());
+    assert!(compare_models(&data, 10.0, &[5.0], &[0.0], 0, 0.95, 0).is_err());
+    assert!(compare_models(&data, 10.0, &[5.0], &[100.0], 0, 0.95, 0).is_err());
+    assert!(compare_models(&data, 1.0, &[5.0], &[], 0, 0.95, 0).is_err());
+}
@@ -0,0 +1,153 @@
+use super::test_patient;
+use crate::model::{Patient, PreventModel, RiskModel};
+use crate::monte_carlo::{
+    monte_carlo_columns, simulate_measurement_error, MeasurementSd, MONTE_CARLO_PERCENTILES,
+};
+use crate::score2::{Score2Model, Score2Region};
+use crate::utils::RiskCategory;
+use std::collections::HashMap;
+
+#[test]
+fn same_seed_gives_same_summary() {
+    let sds = MeasurementSd::default();
+    let model = PreventModel::Cvd10Yr;
+    let first = simulate_measurement_error(&model, &test_patient(), &sds, 500, 7).unwrap();
+    let second = simulate_measurement_error(&model, &test_patient(), &sds, 500, 7).unwrap();
+    let other = simulate_measurement_error(&model, &test_patient(), &sds, 500, 8).unwrap();
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+#[test]
+fn zero_sds_give_a_point_mass() {
+    let sds = MeasurementSd { fields: [0.0; 6] };
+    let model = PreventModel::Ascvd10Yr;
+    let summary = simulate_measurement_error(&model, &test_patient(), &sds, 50, 1).unwrap();
+    let risk = model.predict(&test_patient()).unwrap();
+    assert_eq!(summary.risk, risk);
+    assert!((summary.mean - risk).abs() < 1e-12);
+    assert!(summary.sd < 1e-12);
+    assert_eq!(summary.percentiles, [risk; 5]);
+    assert_eq!(summary.probability_reclassified, 0.0);
+    let expected_above = RiskCategory::THRESHOLDS.map(|t| if risk >= t { 1.0 } else { 0.0 });
+    assert_eq!(summary.probability_above, expected_above);
+}
+
+#[test]
+fn borderline_patient_is_sometimes_reclassified() {
+    let model = PreventModel::Cvd10Yr;
+    let summary =
+        simulate_measurement_error(&model, &test_patient(), &MeasurementSd::default(), 2000, 3)
+            .unwrap();
+    assert!(summary.sd > 0.0);
+    assert!(summary.probability_reclassified > 0.0 && summary.probability_reclassified < 1.0);
+    assert!(summary
+        .percentiles
+        .windows(2)
+        .all(|pair| pair[0] <= pair[1]));
+    assert!(summary.percentiles[0] < summary.risk && summary.risk < summary.percentiles[4]);
+    assert!(summary
+        .probability_above
+        .windows(2)
+        .all(|pair| pair[0] >= pair[1]));
+}
+
+#[test]
+fn samples_stay_in_the_models_range() {
+    // SCORE2 has no BMI or eGFR limits, and a wide SD at the edge of the
+    // PREVENT age range is redrawn instead of failing.
+    let sds = MeasurementSd {
+        fields: [5.0, 40.0, 15.0, 30.0, 5.0, 30.0],
+    };
+    let edge = Patient {
+        age: 79.0,
+        systolic_bp: 195.0,
+        ..test_patient()
+    };
+    simulate_measurement_error(&PreventModel::HeartFailure10Yr, &edge, &sds, 200, 0).unwrap();
+    let score2 = Patient { age: 60.0, ..edge };
+    let model = Score2Model {
+        older_persons: false,
+        region: Score2Region::Moderate,
+    };
+    simulate_measurement_error(&model, &score2, &sds, 200, 0).unwrap();
+}
+
+#[test]
+fn errors_are_truncated_not_clamped() {
+    // Only systolic BP varies, from the top of its range. Clamping would put
+    // about half the samples at 200 mmHg, and the upper percentiles with them.
+    let sds = MeasurementSd {
+        fields: [0.0, 0.0, 0.0, 10.0, 0.0, 0.0],
+    };
+    let at_bound = Patient {
+        systolic_bp: 200.0,
+        ..test_patient()
+    };
+    let model = PreventModel::Cvd10Yr;
+    let summary = simulate_measurement_error(&model, &at_bound, &sds, 1000, 2).unwrap();
+    assert!(summary.percentiles[4] < summary.risk);
+    assert!(summary.mean < summary.risk);
+
+    let too_wide = MeasurementSd {
+        fields: [0.0, 0.0, 0.0, 1e6, 0.0, 0.0],
+    };
+    assert!(simulate_measurement_error(&model, &at_bound, &too_wide, 10, 2).is_err());
+}
+
+#[test]
+fn categories_are_only_for_ten_year_prevent_models() {
+    let sds = MeasurementSd::default();
+    for model in [PreventModel::Cvd30Yr, PreventModel::HeartFailure30Yr] {
+        let summary = simulate_measurement_error(&model, &test_patient(), &sds, 100, 0).unwrap();
+        assert!(summary.probability_above.iter().all(|p| p.is_nan()));
+        assert!(summary.probability_reclassified.is_nan());
+        assert!(!summary.mean.is_nan());
+    }
+    let score2 = Score2Model {
+        older_persons: false,
+        region: Score2Region::Moderate,
+    };
+    let summary = simulate_measurement_error(&score2, &test_patient(), &sds, 100, 0).unwrap();
+    assert!(summary.probability_reclassified.is_nan());
+    let ten_year = simulate_measurement_error(
+        &PreventModel::HeartFailure10Yr,
+        &test_patient(),
+        &sds,
+        100,
+        0,
+    )
+    .unwrap();
+    assert!(!ten_year.probability_reclassified.is_nan());
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let sds = MeasurementSd::default();
+    let model = PreventModel::Cvd10Yr;
+    assert!(simulate_measurement_error(&model, &test_patient(), &sds, 1, 0).is_err());
+    let too_old = Patient {
+        age: 85.0,
+        ..test_patient()
+    };
+    assert!(simulate_measurement_error(&model, &too_old, &sds, 100, 0).is_err());
+
+    let unknown = HashMap::from([("weight".to_string(), 1.0)]);
+    assert!(MeasurementSd::from_map(&unknown).is_err());
+    let negative = HashMap::from([("systolic_bp".to_string(), -1.0)]);
+    assert!(MeasurementSd::from_map(&negative).is_err());
+    let custom = HashMap::from([("systolic_bp".to_string(), 15.0)]);
+    let custom = MeasurementSd::from_map(&custom).unwrap();
+    assert_eq!(custom.fields[3], 15.0);
+    assert_eq!(custom.fields[1], sds.fields[1]);
+}
+
+#[test]
+fn columns_match_summary_layout() {
+    let columns = monte_carlo_columns();
+    assert_eq!(columns.len(), 3 + MONTE_CARLO_PERCENTILES.len() + 3 + 1);
+    assert_eq!(columns[3], "p2_5");
+    assert_eq!(columns[8], "probability_at_least_5");
+    assert_eq!(columns[9], "probability_at_least_7_5");
+    assert_eq!(columns[11], "probability_reclassified");
+}
@@ -0,0 +1,62 @@
+use super::test_patient;
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+use crate::peer_comparison::{compare_to_peers, ReferencePopulation};
+
+const REFERENCE: &str = "model,sex,age_min,age_max,percentile,risk
+10_yr_cvd,female,50,60,50,2.0
+10_yr_cvd,female,50,60,0,0.5
+10_yr_cvd,female,50,60,100,20.0
+10_yr_cvd,female,50,60,90,8.0
+10_yr_cvd,male,50,60,50,3.0
+";
+
+#[test]
+fn parses_and_interpolates_quantiles() {
+    let population = ReferencePopulation::from_csv(REFERENCE).unwrap();
+    assert_eq!(population.bands.len(), 2);
+    let band = population.band("10_yr_cvd", Sex::Female, 50.0).unwrap();
+    assert_eq!(band.quantiles[0], (0.0, 0.5));
+    assert_eq!(band.percentile(2.0), 50.0);
+    assert_eq!(band.percentile(5.0), 70.0);
+    assert_eq!(band.percentile(0.1), 0.0);
+    assert_eq!(band.percentile(50.0), 100.0);
+    assert!(population.band("10_yr_cvd", Sex::Female, 60.0).is_none());
+    assert!(population.band("10_yr_hf", Sex::Female, 55.0).is_none());
+}
+
+#[test]
+fn rejects_malformed_reference_files() {
+    assert!(ReferencePopulation::from_csv("").is_err());
+    assert!(ReferencePopulation::from_csv("model,sex,age\n").is_err());
+    let unknown_model = "model,sex,age_min,age_max,percentile,risk\nqrisk3,female,50,60,50,2\n";
+    assert!(ReferencePopulation::from_csv(unknown_model).is_err());
+    let decreasing = "model,sex,age_min,age_max,percentile,risk
+10_yr_cvd,female,50,60,10,3
+10_yr_cvd,female,50,60,90,2
+";
+    assert!(ReferencePopulation::from_csv(decreasing).is_err());
+}
+
+#[test]
+fn optimal_risk_is_below_patient_risk() {
+    let population = ReferencePopulation::from_csv(REFERENCE).unwrap();
+    for model in PreventModel::ALL {
+        let comparison = compare_to_peers(&model, &test_patient(), Some(&population)).unwrap();
+        assert_eq!(comparison.risk, model.predict(&test_patient()).unwrap());
+        assert_eq!(
+            comparison.optimal_risk,
+            model.predict(&Patient::optimal(Sex::Female, 50.0)).unwrap()
+        );
+        assert!(comparison.optimal_risk < comparison.risk);
+        assert_eq!(
+            comparison.percentile.is_some(),
+            model == PreventModel::Cvd10Yr
+        );
+    }
+    assert_eq!(
+        compare_to_peers(&PreventModel::Cvd10Yr, &test_patient(), None)
+            .unwrap()
+            .percentile,
+        None
+    );
+}
@@ -0,0 +1,53 @@
+use crate::plausibility::{check_plausibility, warnings_to_bits, PlausibilityWarning};
+
+#[test]
+fn plausible_patient_has_no_warnings() {
+    assert!(check_plausibility(50.0, 200.0, 50.0, true, true).is_empty());
+}
+
+#[test]
+fn hdl_at_or_above_total_cholesterol() {
+    let warnings = check_plausibility(50.0, 130.0, 110.0, false, true);
+    assert_eq!(
+        warnings,
+        vec![PlausibilityWarning::NonHdlCholesterolOutOfRange]
+    );
+    let warnings = check_plausibility(50.0, 130.0, 130.0, false, true);
+    assert_eq!(
+        warnings,
+        vec![
+            PlausibilityWarning::HdlNotBelowTotalCholesterol,
+            PlausibilityWarning::NonHdlCholesterolOutOfRange,
+        ]
+    );
+}
+
+#[test]
+fn high_non_hdl_on_cholesterol_meds() {
+    assert!(check_plausibility(50.0, 290.0, 40.0, false, true).is_empty());
+    assert_eq!(
+        check_plausibility(50.0, 290.0, 40.0, true, true),
+        vec![PlausibilityWarning::HighNonHdlCholesterolOnStatin]
+    );
+}
+
+#[test]
+fn age_above_thirty_year_range() {
+    assert!(check_plausibility(59.0, 200.0, 50.0, false, true).is_empty());
+    assert_eq!(
+        check_plausibility(79.0, 200.0, 50.0, false, true),
+        vec![PlausibilityWarning::AgeAboveThirtyYearRange]
+    );
+    // Only 10-year models are being run.
+    assert!(check_plausibility(79.0, 200.0, 50.0, false, false).is_empty());
+}
+
+#[test]
+fn warning_bits_follow_declaration_order() {
+    assert_eq!(warnings_to_bits(&[]), 0);
+    assert_eq!(warnings_to_bits(&PlausibilityWarning::ALL), 0b1111);
+    assert_eq!(
+        warnings_to_bits(&[PlausibilityWarning::AgeAboveThirtyYearRange]),
+        0b1000
+    );
+}
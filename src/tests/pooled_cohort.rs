@@ -0,0 +1,55 @@
+use crate::pooled_cohort::{calculate_10_yr_pce_ascvd_risk, reclassification_table};
+
+#[test]
+fn published_example_patient() {
+    // Goff et al. 2014, Table A: 55 years old, total cholesterol 213, HDL 50,
+    // untreated SBP 120, non-smoker without diabetes
+    let expected = [
+        ("female", "white", 2.1),
+        ("female", "african american", 3.0),
+        ("male", "white", 5.4),
+        ("male", "african american", 6.1),
+    ];
+    for (sex, race, expected) in expected {
+        let risk = calculate_10_yr_pce_ascvd_risk(
+            sex, race, 55.0, 213.0, 50.0, 120.0, false, false, false,
+        )
+        .unwrap();
+        assert!((risk - expected).abs() < 0.05, "{sex} {race}: {risk}");
+    }
+}
+
+#[test]
+fn other_races_use_the_white_equations() {
+    let score = |race| {
+        calculate_10_yr_pce_ascvd_risk("male", race, 60.0, 200.0, 45.0, 140.0, true, true, true)
+    };
+    assert_eq!(score("other"), score("WHITE"));
+    assert_eq!(score("Black"), score("african american"));
+    assert!(score("asian").is_err());
+}
+
+#[test]
+fn pce_age_range() {
+    let score = |age| {
+        calculate_10_yr_pce_ascvd_risk(
+            "female", "white", age, 200.0, 50.0, 120.0, false, false, false,
+        )
+    };
+    assert!(score(40.0).is_ok());
+    assert!(score(79.0).is_ok());
+    assert!(score(39.0).is_err());
+    assert!(score(80.0).is_err());
+}
+
+#[test]
+fn reclassification_counts_category_moves() {
+    let pce = [3.0, 6.0, 10.0, 25.0, 12.0, f64::NAN];
+    let prevent = [2.0, 4.0, 6.0, 15.0, 12.0, 5.0];
+    let table = reclassification_table(&pce, &prevent).unwrap();
+    assert_eq!(
+        table,
+        [[1, 0, 0, 0], [1, 0, 0, 0], [0, 1, 1, 0], [0, 0, 1, 0]]
+    );
+    assert!(reclassification_table(&pce, &prevent[1..]).is_err());
+}
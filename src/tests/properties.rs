@@ -0,0 +1,227 @@
+//! Clinical invariants of the PREVENT equations, checked across the valid
+//! input domain enforced by `validate_input`.
+//!
+//! Age is only required to increase risk for non-smokers without diabetes
+//! with total cholesterol <= 240 mg/dL, SBP <= 160 mmHg and eGFR >= 60. Outside
+//! that region the published age interaction terms (age x non-HDL, SBP, eGFR,
+//! diabetes and smoking) can outweigh the main age effect, so risk falling
+//! with age there is a property of the model rather than a coefficient error.
+
+use crate::ascvd::{calculate_10_yr_ascvd_risk, calculate_30_yr_ascvd_risk};
+use crate::cvd::{calculate_10_yr_cvd_risk, calculate_30_yr_cvd_risk};
+use crate::heart_failure::{
+    calculate_10_yr_heart_failure_risk, calculate_30_yr_heart_failure_risk,
+};
+use crate::utils::RiskCalcFn;
+use proptest::prelude::*;
+
+const TEN_YR: [(&str, RiskCalcFn); 3] = [
+    ("10 yr CVD", calculate_10_yr_cvd_risk),
+    ("10 yr ASCVD", calculate_10_yr_ascvd_risk),
+    ("10 yr HF", calculate_10_yr_heart_failure_risk),
+];
+
+const THIRTY_YR: [(&str, RiskCalcFn); 3] = [
+    ("30 yr CVD", calculate_30_yr_cvd_risk),
+    ("30 yr ASCVD", calculate_30_yr_ascvd_risk),
+    ("30 yr HF", calculate_30_yr_heart_failure_risk),
+];
+
+#[derive(Debug, Clone)]
+struct Patient {
+    sex: &'static str,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+}
+
+impl Patient {
+    fn score(&self, risk_calc_fn: RiskCalcFn) -> f64 {
+        risk_calc_fn(
+            self.sex,
+            self.age,
+            self.total_cholesterol,
+            self.hdl_cholesterol,
+            self.systolic_bp,
+            self.has_diabetes,
+            self.current_smoker,
+            self.bmi,
+            self.egfr,
+            self.on_htn_meds,
+            self.on_cholesterol_meds,
+        )
+        .unwrap_or_else(|e| panic!("{self:?}: {e}"))
+    }
+}
+
+fn patient(max_age: f64) -> impl Strategy<Value = Patient> {
+    (
+        prop_oneof![Just("female"), Just("male")],
+        30.0..=max_age,
+        130.0..=320.0,
+        20.0..=100.0,
+        90.0..=200.0,
+        any::<[bool; 4]>(),
+        18.5..=39.9,
+        15.0..=140.0,
+    )
+        .prop_map(
+            |(sex, age, total_cholesterol, hdl_cholesterol, systolic_bp, flags, bmi, egfr)| {
+                Patient {
+                    sex,
+                    age,
+                    total_cholesterol,
+                    hdl_cholesterol,
+                    systolic_bp,
+                    has_diabetes: flags[0],
+                    current_smoker: flags[1],
+                    bmi,
+                    egfr,
+                    on_htn_meds: flags[2],
+                    on_cholesterol_meds: flags[3],
+                }
+            },
+        )
+}
+
+/// Spline knot name, location and the field it applies to.
+type Knot = (&'static str, f64, fn(&mut Patient, f64));
+
+/// Scores `patient` just below and just above `knot` for the field set by `set`.
+fn either_side_of_knot(
+    patient: &Patient,
+    knot: f64,
+    risk_calc_fn: RiskCalcFn,
+    set: fn(&mut Patient, f64),
+) -> (f64, f64) {
+    let mut below = patient.clone();
+    set(&mut below, knot - 1e-7);
+    let mut above = patient.clone();
+    set(&mut above, knot + 1e-7);
+    (below.score(risk_calc_fn), above.score(risk_calc_fn))
+}
+
+proptest! {
+    #[test]
+    fn risk_is_a_percentage(ten_yr in patient(79.0), thirty_yr in patient(59.0)) {
+        for (outcome, risk_calc_fn) in TEN_YR {
+            let risk = ten_yr.score(risk_calc_fn);
+            prop_assert!(risk > 0.0 && risk < 100.0, "{outcome}: {risk}");
+        }
+        for (outcome, risk_calc_fn) in THIRTY_YR {
+            let risk = thirty_yr.score(risk_calc_fn);
+            prop_assert!(risk > 0.0 && risk < 100.0, "{outcome}: {risk}");
+        }
+    }
+
+    #[test]
+    fn risk_increases_with_systolic_bp_above_130(
+        patient in patient(79.0),
+        low in 130.0..=200.0f64,
+        high in 130.0..=200.0f64,
+    ) {
+        let (low, high) = (low.min(high), low.max(high));
+        let mut lower_bp = patient.clone();
+        lower_bp.systolic_bp = low;
+        let mut higher_bp = patient.clone();
+        higher_bp.systolic_bp = high;
+        for (outcome, risk_calc_fn) in TEN_YR {
+            prop_assert!(higher_bp.score(risk_calc_fn) >= lower_bp.score(risk_calc_fn), "{outcome}");
+        }
+        if patient.age <= 59.0 {
+            for (outcome, risk_calc_fn) in THIRTY_YR {
+                prop_assert!(higher_bp.score(risk_calc_fn) >= lower_bp.score(risk_calc_fn), "{outcome}");
+            }
+        }
+    }
+
+    #[test]
+    fn risk_increases_with_age(
+        patient in patient(79.0),
+        total_cholesterol in 130.0..=240.0f64,
+        systolic_bp in 90.0..=160.0f64,
+        egfr in 60.0..=140.0f64,
+        younger in 30.0..=79.0f64,
+        older in 30.0..=79.0f64,
+    ) {
+        let patient = Patient {
+            total_cholesterol,
+            systolic_bp,
+            egfr,
+            has_diabetes: false,
+            current_smoker: false,
+            ..patient
+        };
+        let (younger, older) = (younger.min(older), younger.max(older));
+        let at_age = |age: f64| Patient { age, ..patient.clone() };
+        for (outcome, risk_calc_fn) in TEN_YR {
+            prop_assert!(at_age(older).score(risk_calc_fn) >= at_age(younger).score(risk_calc_fn), "{outcome}");
+        }
+        let (younger, older) = (younger.min(59.0), older.min(59.0));
+        for (outcome, risk_calc_fn) in THIRTY_YR {
+            prop_assert!(at_age(older).score(risk_calc_fn) >= at_age(younger).score(risk_calc_fn), "{outcome}");
+        }
+    }
+
+    #[test]
+    fn smoking_and_diabetes_never_lower_risk(patient in patient(79.0)) {
+        let without = Patient { has_diabetes: false, current_smoker: false, ..patient.clone() };
+        let smoker = Patient { current_smoker: true, ..without.clone() };
+        let diabetic = Patient { has_diabetes: true, ..without.clone() };
+        let both = Patient { has_diabetes: true, current_smoker: true, ..without.clone() };
+        let mut models = TEN_YR.to_vec();
+        if patient.age <= 59.0 {
+            models.extend(THIRTY_YR);
+        }
+        for (outcome, risk_calc_fn) in models {
+            let baseline = without.score(risk_calc_fn);
+            prop_assert!(smoker.score(risk_calc_fn) >= baseline, "{outcome}: smoking");
+            prop_assert!(diabetic.score(risk_calc_fn) >= baseline, "{outcome}: diabetes");
+            prop_assert!(both.score(risk_calc_fn) >= smoker.score(risk_calc_fn), "{outcome}: diabetes and smoking");
+            prop_assert!(both.score(risk_calc_fn) >= diabetic.score(risk_calc_fn), "{outcome}: diabetes and smoking");
+        }
+    }
+
+    // The 30-year equations are fitted separately, and at the extremes of
+    // blood pressure and kidney function a 10-year heart failure risk near
+    // 70% can exceed the 30-year one.
+    #[test]
+    fn thirty_year_risk_is_at_least_ten_year_risk(
+        patient in patient(59.0),
+        systolic_bp in 90.0..=160.0f64,
+        egfr in 45.0..=140.0f64,
+    ) {
+        let patient = Patient { systolic_bp, egfr, ..patient };
+        for ((outcome, ten_yr), (_, thirty_yr)) in TEN_YR.into_iter().zip(THIRTY_YR) {
+            prop_assert!(patient.score(thirty_yr) >= patient.score(ten_yr), "{outcome}");
+        }
+    }
+
+    #[test]
+    fn continuous_at_spline_knots(patient in patient(79.0)) {
+        let knots: [Knot; 5] = [
+            ("SBP 110", 110.0, |p, v| p.systolic_bp = v),
+            ("SBP 130", 130.0, |p, v| p.systolic_bp = v),
+            ("eGFR 60", 60.0, |p, v| p.egfr = v),
+            ("eGFR 90", 90.0, |p, v| p.egfr = v),
+            ("BMI 30", 30.0, |p, v| p.bmi = v),
+        ];
+        let mut models = TEN_YR.to_vec();
+        if patient.age <= 59.0 {
+            models.extend(THIRTY_YR);
+        }
+        for (knot_name, knot, set) in knots {
+            for (outcome, risk_calc_fn) in &models {
+                let (below, above) = either_side_of_knot(&patient, knot, *risk_calc_fn, set);
+                prop_assert!((below - above).abs() < 1e-5, "{outcome} at {knot_name}: {below} vs {above}");
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::cvd::calculate_10_yr_cvd_risk;
+use crate::heart_failure::calculate_30_yr_heart_failure_risk;
+use crate::range_policy::{adjusted_fields, calculate_risk_with_policy, OutOfRange, RangePolicy};
+use std::collections::HashMap;
+
+fn policy(fields: &[(&str, &str)]) -> RangePolicy {
+    let map: HashMap<String, String> = fields
+        .iter()
+        .map(|(field, value)| (field.to_string(), value.to_string()))
+        .collect();
+    RangePolicy::from_map(&map).unwrap()
+}
+
+#[test]
+fn in_range_inputs_are_unchanged() {
+    let (risk, adjusted) = calculate_risk_with_policy(
+        "10_yr_cvd",
+        &policy(&[("systolic_bp", "clamp")]),
+        "female",
+        50.0,
+        200.0,
+        50.0,
+        120.0,
+        false,
+        true,
+        25.0,
+        90.0,
+        false,
+        false,
+    )
+    .unwrap();
+    let expected = calculate_10_yr_cvd_risk(
+        "female", 50.0, 200.0, 50.0, 120.0, false, true, 25.0, 90.0, false, false,
+    )
+    .unwrap();
+    assert_eq!(risk, expected);
+    assert_eq!(adjusted, 0);
+}
+
+#[test]
+fn reject_matches_validate_input() {
+    let result = calculate_risk_with_policy(
+        "10_yr_cvd",
+        &RangePolicy::default(),
+        "female",
+        50.0,
+        200.0,
+        50.0,
+        220.0,
+        false,
+        true,
+        25.0,
+        90.0,
+        false,
+        false,
+    );
+    assert_eq!(
+        result.unwrap_err(),
+        calculate_10_yr_cvd_risk(
+            "female", 50.0, 200.0, 50.0, 220.0, false, true, 25.0, 90.0, false, false
+        )
+        .unwrap_err()
+    );
+}
+
+#[test]
+fn clamp_scores_at_the_nearest_bound() {
+    let (risk, adjusted) = calculate_risk_with_policy(
+        "30_yr_hf",
+        &policy(&[("bmi", "clamp"), ("systolic_bp", "clamp"), ("age", "clamp")]),
+        "male",
+        45.0,
+        200.0,
+        50.0,
+        85.0,
+        false,
+        false,
+        44.0,
+        90.0,
+        false,
+        false,
+    )
+    .unwrap();
+    let expected = calculate_30_yr_heart_failure_risk(
+        "male", 45.0, 200.0, 50.0, 90.0, false, false, 39.9, 90.0, false, false,
+    )
+    .unwrap();
+    assert_eq!(risk, expected);
+    assert_eq!(adjusted_fields(adjusted), vec!["systolic_bp", "bmi"]);
+}
+
+#[test]
+fn extrapolate_uses_the_value_as_given() {
+    let score = |systolic_bp: f64| {
+        calculate_risk_with_policy(
+            "10_yr_cvd",
+            &policy(&[("systolic_bp", "extrapolate")]),
+            "male",
+            60.0,
+            200.0,
+            50.0,
+            systolic_bp,
+            false,
+            false,
+            25.0,
+            90.0,
+            false,
+            false,
+        )
+        .unwrap()
+    };
+    let (at_bound, adjusted) = score(200.0);
+    assert_eq!(adjusted, 0);
+    let (beyond, adjusted) = score(230.0);
+    assert!(beyond > at_bound && beyond < 100.0);
+    assert_eq!(adjusted_fields(adjusted), vec!["systolic_bp"]);
+}
+
+#[test]
+fn policy_names_are_validated() {
+    let unknown_field: HashMap<String, String> =
+        HashMap::from([("ldl".to_string(), "clamp".to_string())]);
+    assert!(RangePolicy::from_map(&unknown_field).is_err());
+    let unknown_policy: HashMap<String, String> =
+        HashMap::from([("bmi".to_string(), "ignore".to_string())]);
+    assert!(RangePolicy::from_map(&unknown_policy).is_err());
+    assert_eq!(
+        policy(&[("egfr", "Extrapolate")]).fields[5],
+        OutOfRange::Extrapolate
+    );
+}
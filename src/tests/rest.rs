@@ -0,0 +1,126 @@
+use super::test_patient;
+use crate::model::{models, PreventModel, RiskModel};
+use crate::rest::{handle_request, patient_from_json, MAX_BATCH_SIZE};
+use serde_json::{json, Value};
+
+/// `test_patient` as request JSON, with one flag given as a number.
+fn patient() -> Value {
+    json!({
+        "sex": "female",
+        "age": 50,
+        "total_cholesterol": 200,
+        "hdl_cholesterol": 45,
+        "systolic_bp": 160,
+        "has_diabetes": true,
+        "current_smoker": false,
+        "bmi": 35,
+        "egfr": 90,
+        "on_htn_meds": 1,
+        "on_cholesterol_meds": false,
+    })
+}
+
+#[test]
+fn patient_json_matches_fields() {
+    let (patient, given) = patient_from_json(&patient()).unwrap();
+    assert_eq!(patient, test_patient());
+    assert_eq!(given.len(), 11);
+
+    assert!(patient_from_json(&json!({"sex": "female", "ldl": 100})).is_err());
+    assert!(patient_from_json(&json!({"sex": "other"})).is_err());
+    assert!(patient_from_json(&json!({"has_diabetes": 2})).is_err());
+    assert!(patient_from_json(&json!({"age": "fifty"})).is_err());
+    assert!(patient_from_json(&json!([1, 2])).is_err());
+}
+
+#[test]
+fn score_returns_every_model() {
+    let body = json!({ "patient": patient() }).to_string();
+    let (status, response) = handle_request("POST", "/score", &body);
+    assert_eq!(status, 200, "{}", response);
+    let results = response["results"].as_array().unwrap();
+    assert_eq!(results.len(), models().len());
+
+    for model in PreventModel::ALL {
+        let metadata = model.metadata();
+        let result = results
+            .iter()
+            .find(|result| result["model"] == metadata.key)
+            .unwrap();
+        let risk = model.predict(&test_patient()).unwrap();
+        assert!((result["risk"].as_f64().unwrap() - risk).abs() < 1e-12);
+        assert_eq!(result["version"], metadata.version);
+        assert!(result["error"].is_null());
+        assert_eq!(result["category"].is_string(), model.is_ten_year());
+    }
+}
+
+#[test]
+fn score_reports_validation_and_missing_inputs() {
+    let mut young = patient();
+    young["age"] = json!(25);
+    let body = json!({"patient": young, "models": ["10_yr_cvd"]}).to_string();
+    let (status, response) = handle_request("POST", "/score", &body);
+    assert_eq!(status, 200);
+    let result = &response["results"][0];
+    assert!(result["risk"].is_null());
+    assert_eq!(result["error"], "Age must be between 30 and 79");
+
+    let body = json!({"patient": {"sex": "male", "age": 50}, "models": ["10_yr_hf"]}).to_string();
+    let (_, response) = handle_request("POST", "/score", &body);
+    let error = response["results"][0]["error"].as_str().unwrap();
+    assert!(
+        error.starts_with("Missing inputs: total_cholesterol"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn batch_scores_in_order_with_per_patient_errors() {
+    let mut older = patient();
+    older["age"] = json!(70);
+    let body = json!({
+        "patients": [patient(), "not a patient", older],
+        "models": ["10_yr_ascvd", "30_yr_ascvd"],
+    })
+    .to_string();
+    let (status, response) = handle_request("POST", "/score/batch", &body);
+    assert_eq!(status, 200);
+    let patients = response["patients"].as_array().unwrap();
+    assert_eq!(patients.len(), 3);
+    assert_eq!(patients[0]["results"].as_array().unwrap().len(), 2);
+    assert!(patients[1]["error"].is_string());
+    assert!(patients[2]["results"][0]["risk"].is_number());
+    assert!(patients[2]["results"][1]["error"].is_string());
+}
+
+#[test]
+fn batch_size_is_limited() {
+    let patients = vec![json!({}); MAX_BATCH_SIZE + 1];
+    let body = json!({ "patients": patients }).to_string();
+    let (status, _) = handle_request("POST", "/score/batch", &body);
+    assert_eq!(status, 413);
+}
+
+#[test]
+fn service_endpoints() {
+    let (status, health) = handle_request("GET", "/health", "");
+    assert_eq!((status, health["status"].as_str()), (200, Some("ok")));
+
+    let (status, list) = handle_request("GET", "/models", "");
+    assert_eq!(status, 200);
+    assert_eq!(list["models"].as_array().unwrap().len(), models().len());
+
+    let (status, spec) = handle_request("GET", "/openapi.json", "");
+    assert_eq!(status, 200);
+    for path in ["/health", "/models", "/score", "/score/batch"] {
+        assert!(spec["paths"][path].is_object(), "{}", path);
+    }
+
+    assert_eq!(handle_request("GET", "/score", "").0, 405);
+    assert_eq!(handle_request("GET", "/nothing", "").0, 404);
+    assert_eq!(handle_request("POST", "/score", "{").0, 400);
+    let unknown = json!({"patient": patient(), "models": ["5_yr_cvd"]}).to_string();
+    assert_eq!(handle_request("POST", "/score", &unknown).0, 400);
+}
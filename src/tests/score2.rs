@@ -0,0 +1,126 @@
+use crate::model::{find_model, Patient, RiskModel, Sex};
+use crate::score2::{calculate_score2_op_risk, calculate_score2_risk, Score2Model, Score2Region};
+
+const MG_PER_MMOL: f64 = 1.0 / 0.02586;
+
+/// At the centring values the linear predictor is zero, so the risk only
+/// depends on the baseline survival and the regional recalibration.
+fn reference_risk(baseline_survival: f64, mean: f64, scale1: f64, scale2: f64) -> f64 {
+    let cloglog = (-baseline_survival.ln()).ln() - mean;
+    100.0 * (1.0 - (-(scale1 + scale2 * cloglog).exp()).exp())
+}
+
+#[test]
+fn score2_at_centring_values() {
+    let risk = calculate_score2_risk(
+        "low",
+        "male",
+        60.0,
+        6.0 * MG_PER_MMOL,
+        1.3 * MG_PER_MMOL,
+        120.0,
+        false,
+        false,
+    )
+    .unwrap();
+    assert!((risk - reference_risk(0.9605, 0.0, -0.5699, 0.7476)).abs() < 1e-9);
+
+    let risk = calculate_score2_risk(
+        "very_high",
+        "female",
+        60.0,
+        6.0 * MG_PER_MMOL,
+        1.3 * MG_PER_MMOL,
+        120.0,
+        false,
+        false,
+    )
+    .unwrap();
+    assert!((risk - reference_risk(0.9776, 0.0, 0.9412, 0.8329)).abs() < 1e-9);
+}
+
+#[test]
+fn score2_op_at_centring_values() {
+    let risk = calculate_score2_op_risk(
+        "moderate",
+        "female",
+        73.0,
+        6.0 * MG_PER_MMOL,
+        1.4 * MG_PER_MMOL,
+        150.0,
+        false,
+        false,
+    )
+    .unwrap();
+    assert!((risk - reference_risk(0.8082, 0.2290, -0.10, 1.10)).abs() < 1e-9);
+}
+
+#[test]
+fn risk_increases_with_region_and_risk_factors() {
+    let risks: Vec<f64> = Score2Region::ALL
+        .iter()
+        .map(|region| {
+            Score2Model {
+                older_persons: false,
+                region: *region,
+            }
+            .predict(&Patient {
+                sex: Sex::Male,
+                age: 55.0,
+                total_cholesterol: 220.0,
+                hdl_cholesterol: 45.0,
+                systolic_bp: 140.0,
+                has_diabetes: false,
+                current_smoker: true,
+                bmi: 0.0,
+                egfr: 0.0,
+                on_htn_meds: false,
+                on_cholesterol_meds: false,
+            })
+            .unwrap()
+        })
+        .collect();
+    assert!(
+        risks.windows(2).all(|pair| pair[0] < pair[1]),
+        "{:?}",
+        risks
+    );
+
+    let non_smoker =
+        calculate_score2_risk("high", "female", 55.0, 220.0, 45.0, 140.0, false, false).unwrap();
+    let smoker =
+        calculate_score2_risk("high", "female", 55.0, 220.0, 45.0, 140.0, false, true).unwrap();
+    assert!(smoker > non_smoker);
+}
+
+#[test]
+fn age_ranges_do_not_overlap() {
+    let args = (200.0, 50.0, 130.0, false, false);
+    for (age, score2, score2_op) in [
+        (39.0, false, false),
+        (69.0, true, false),
+        (70.0, false, true),
+    ] {
+        let (tc, hdl, sbp, diabetes, smoker) = args;
+        assert_eq!(
+            calculate_score2_risk("low", "male", age, tc, hdl, sbp, diabetes, smoker).is_ok(),
+            score2
+        );
+        assert_eq!(
+            calculate_score2_op_risk("low", "male", age, tc, hdl, sbp, diabetes, smoker).is_ok(),
+            score2_op
+        );
+    }
+    assert!(
+        calculate_score2_risk("nordic", "male", 50.0, 200.0, 50.0, 130.0, false, false).is_err()
+    );
+}
+
+#[test]
+fn registered_as_models() {
+    let model = find_model("score2_op_very_high").unwrap();
+    assert_eq!(model.metadata().name, "SCORE2-OP (very high risk region)");
+    assert_eq!(model.required_inputs().len(), 7);
+    assert_eq!(Score2Model::all().len(), 8);
+    assert_eq!(Score2Region::parse("Very High"), Ok(Score2Region::VeryHigh));
+}
@@ -0,0 +1,128 @@
+use crate::model::{PreventModel, RiskModel};
+use crate::survey::{survey_summary, SurveyRecord};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+fn record(risk: f64, weight: f64, stratum: i64, psu: i64, group: i64) -> SurveyRecord {
+    SurveyRecord {
+        risk,
+        weight,
+        stratum,
+        psu,
+        group,
+    }
+}
+
+/// Two strata of two PSUs with one respondent each.
+fn example() -> Vec<SurveyRecord> {
+    vec![
+        record(2.0, 1.0, 1, 1, 0),
+        record(6.0, 2.0, 1, 2, 1),
+        record(10.0, 1.0, 2, 1, 0),
+        record(30.0, 1.0, 2, 2, 1),
+    ]
+}
+
+#[test]
+fn estimates_match_hand_calculation() {
+    let (overall, by_group) = survey_summary(&example(), Some(&PreventModel::Ascvd10Yr)).unwrap();
+    assert_eq!(overall.n, 4);
+    assert_eq!(overall.population, 5.0);
+    assert!((overall.mean_risk.value - 10.8).abs() < 1e-12);
+    let proportions: Vec<f64> = overall
+        .category_proportions
+        .unwrap()
+        .iter()
+        .map(|p| p.value)
+        .collect();
+    assert_eq!(proportions, vec![0.2, 0.4, 0.2, 0.2]);
+    assert!((overall.expected_events.value - 0.54).abs() < 1e-12);
+    // Expected events total: PSU totals 0.02 and 0.12, then 0.1 and 0.3, so
+    // the variance is 2 * (0.05^2 + 0.05^2) + 2 * (0.1^2 + 0.1^2).
+    assert!((overall.expected_events.se - 0.05f64.sqrt()).abs() < 1e-12);
+    // Mean risk: scores w (y - 10.8) / 5 are -1.76, -1.92 and -0.16, 3.84.
+    let variance = 2.0 * (2.0 * 0.08f64.powi(2)) + 2.0 * (2.0 * 2.0f64.powi(2));
+    assert!((overall.mean_risk.se - variance.sqrt()).abs() < 1e-12);
+
+    assert_eq!(by_group.len(), 2);
+    assert_eq!(by_group[0].0, 0);
+    assert_eq!(by_group[0].1.n, 2);
+    assert!((by_group[0].1.mean_risk.value - 6.0).abs() < 1e-12);
+    assert!((by_group[1].1.mean_risk.value - 14.0).abs() < 1e-12);
+    assert!((by_group[1].1.population - 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn missing_risks_stay_in_the_design() {
+    let mut data = example();
+    data.push(record(f64::NAN, 5.0, 3, 1, 2));
+    data.push(record(f64::NAN, 5.0, 3, 2, 2));
+    let (overall, by_group) = survey_summary(&data, None).unwrap();
+    let (expected, _) = survey_summary(&example(), None).unwrap();
+    assert_eq!(overall, expected);
+    assert_eq!(by_group[2].1.n, 0);
+    assert_eq!(by_group[2].1.population, 0.0);
+    assert!(by_group[2].1.mean_risk.value.is_nan());
+    assert_eq!(by_group[2].1.expected_events.value, 0.0);
+}
+
+#[test]
+fn only_ten_year_prevent_risks_are_categorized() {
+    let models: [Option<&dyn RiskModel>; 2] = [None, Some(&PreventModel::Ascvd30Yr)];
+    for model in models {
+        let (overall, by_group) = survey_summary(&example(), model).unwrap();
+        assert!(overall.category_proportions.is_none());
+        assert!(by_group
+            .iter()
+            .all(|(_, summary)| summary.category_proportions.is_none()));
+        let values = overall.to_vec();
+        assert!(values[4..12].iter().all(|value| value.is_nan()));
+    }
+}
+
+#[test]
+fn standard_errors_shrink_with_more_psus() {
+    let simulate = |psus: i64| {
+        let mut rng = ChaCha8Rng::seed_from_u64(psus as u64);
+        let data: Vec<SurveyRecord> = (0..psus * 20)
+            .map(|i| {
+                record(
+                    rng.gen_range(1.0..30.0),
+                    rng.gen_range(0.5..2.0),
+                    i % 4,
+                    i % psus,
+                    i % 3,
+                )
+            })
+            .collect();
+        survey_summary(&data, Some(&PreventModel::Cvd10Yr)).unwrap()
+    };
+    let (small, _) = simulate(8);
+    let (large, by_group) = simulate(200);
+    assert!(large.mean_risk.se < small.mean_risk.se);
+    assert!((large.mean_risk.value - 15.5).abs() < 3.0 * large.mean_risk.se);
+    for (_, summary) in &by_group {
+        assert!(summary.mean_risk.se > large.mean_risk.se);
+        let proportions = summary.category_proportions.unwrap();
+        let total: f64 = proportions.iter().map(|p| p.value).sum();
+        assert!((total - 1.0).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn invalid_designs_are_rejected() {
+    let mut data = example();
+    data[0].weight = 0.0;
+    assert!(survey_summary(&data, None).is_err());
+    let mut data = example();
+    data.push(record(5.0, 1.0, 3, 1, 0));
+    assert!(survey_summary(&data, None).is_err());
+    let data: Vec<SurveyRecord> = example()
+        .into_iter()
+        .map(|r| SurveyRecord {
+            risk: f64::NAN,
+            ..r
+        })
+        .collect();
+    assert!(survey_summary(&data, None).is_err());
+}
@@ -0,0 +1,77 @@
+use super::test_patient;
+use crate::model::{Patient, PreventModel, RiskModel};
+use crate::trajectory::{risk_trajectory, Trends, MIN_STEP_YEARS};
+use std::collections::HashMap;
+
+/// Low enough eGFR for a falling trend to reach the bound within 20 years.
+fn patient() -> Patient {
+    Patient {
+        egfr: 30.0,
+        ..test_patient()
+    }
+}
+
+#[test]
+fn constant_risk_factors_only_change_age() {
+    let trajectory = risk_trajectory(
+        PreventModel::Ascvd10Yr,
+        &patient(),
+        &Trends::default(),
+        5.0,
+        79.0,
+    )
+    .unwrap();
+    let ages: Vec<f64> = trajectory.iter().map(|point| point.0).collect();
+    assert_eq!(ages, [50.0, 55.0, 60.0, 65.0, 70.0, 75.0]);
+    for (age, risk) in trajectory {
+        let expected = PreventModel::Ascvd10Yr
+            .predict(&Patient { age, ..patient() })
+            .unwrap();
+        assert_eq!(risk, expected);
+    }
+}
+
+#[test]
+fn trends_are_applied_and_held_at_range_bounds() {
+    let trends = Trends::from_map(&HashMap::from([
+        ("systolic_bp".to_string(), 1.0),
+        ("egfr".to_string(), -1.0),
+    ]))
+    .unwrap();
+    let projected = trends.project(&patient(), 20.0);
+    assert_eq!(projected.age, 70.0);
+    assert_eq!(projected.systolic_bp, 180.0);
+    assert_eq!(projected.egfr, 15.0);
+    assert_eq!(projected.total_cholesterol, 200.0);
+
+    let flat = risk_trajectory(
+        PreventModel::Cvd10Yr,
+        &patient(),
+        &Trends::default(),
+        10.0,
+        79.0,
+    )
+    .unwrap();
+    let rising = risk_trajectory(PreventModel::Cvd10Yr, &patient(), &trends, 10.0, 79.0).unwrap();
+    assert_eq!(flat[0], rising[0]);
+    assert!(rising[1].1 > flat[1].1);
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let trends = Trends::default();
+    assert!(risk_trajectory(PreventModel::Cvd30Yr, &patient(), &trends, 5.0, 79.0).is_err());
+    assert!(risk_trajectory(PreventModel::Cvd10Yr, &patient(), &trends, 0.0, 79.0).is_err());
+    assert!(risk_trajectory(PreventModel::Cvd10Yr, &patient(), &trends, 1e-9, 79.0).is_err());
+    let finest = risk_trajectory(
+        PreventModel::Cvd10Yr,
+        &patient(),
+        &trends,
+        MIN_STEP_YEARS,
+        79.0,
+    )
+    .unwrap();
+    assert_eq!(finest.len(), 291);
+    assert!(risk_trajectory(PreventModel::Cvd10Yr, &patient(), &trends, 5.0, 85.0).is_err());
+    assert!(Trends::from_map(&HashMap::from([("age".to_string(), 1.0)])).is_err());
+}
@@ -0,0 +1,185 @@
+use crate::validation::{observations, validate_predictions, Observation};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Calibrated data: each patient has the event before `horizon` with
+/// probability equal to the predicted risk, at a uniform time. A fifth of
+/// patients, chosen independently, are censored halfway to the horizon.
+fn simulated(n: usize, seed: u64, horizon: f64) -> Vec<Observation> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    (0..n)
+        .map(|_| {
+            let risk: f64 = rng.gen_range(1.0..40.0);
+            let event_time = if rng.gen::<f64>() < risk / 100.0 {
+                rng.gen_range(0.0..horizon)
+            } else {
+                horizon + 1.0
+            };
+            let censoring_time = if rng.gen::<f64>() < 0.2 {
+                horizon / 2.0
+            } else {
+                f64::INFINITY
+            };
+            Observation {
+                risk,
+                event: event_time < censoring_time && event_time < horizon,
+                time: event_time.min(censoring_time),
+            }
+        })
+        .collect()
+}
+
+fn brute_force_c(observations: &[Observation]) -> f64 {
+    let (mut concordant, mut pairs) = (0.0, 0.0);
+    for a in observations.iter().filter(|o| o.event) {
+        for b in observations {
+            if b.time > a.time || (b.time == a.time && !b.event) {
+                pairs += 1.0;
+                if a.risk > b.risk {
+                    concordant += 1.0;
+                } else if a.risk == b.risk {
+                    concordant += 0.5;
+                }
+            }
+        }
+    }
+    concordant / pairs
+}
+
+#[test]
+fn harrell_c_matches_pairwise_count() {
+    let mut rng = ChaCha8Rng::seed_from_u64(11);
+    // Coarse risks and times so that there are ties in both.
+    let data: Vec<Observation> = (0..300)
+        .map(|_| Observation {
+            risk: rng.gen_range(1..20) as f64,
+            event: rng.gen::<f64>() < 0.3,
+            time: rng.gen_range(1..8) as f64,
+        })
+        .collect();
+    let report = validate_predictions(&data, 10.0, 0, 0.95, 0).unwrap();
+    assert!((report.metrics.c_statistic - brute_force_c(&data)).abs() < 1e-12);
+}
+
+#[test]
+fn uncensored_metrics_match_simple_formulas() {
+    let data: Vec<Observation> = (0..20)
+        .map(|i| Observation {
+            risk: 5.0 * (i % 10) as f64 + 2.0,
+            event: i % 4 == 0,
+            time: if i % 4 == 0 { 3.0 } else { 10.0 },
+        })
+        .collect();
+    let report = validate_predictions(&data, 10.0, 0, 0.95, 0).unwrap();
+    let outcome = |o: &Observation| if o.event { 1.0 } else { 0.0 };
+    let brier = data
+        .iter()
+        .map(|o| (outcome(o) - o.risk / 100.0).powi(2))
+        .sum::<f64>()
+        / 20.0;
+    let observed = data.iter().map(outcome).sum::<f64>() / 20.0;
+    let expected = data.iter().map(|o| o.risk / 100.0).sum::<f64>() / 20.0;
+    assert_eq!(report.n, 20);
+    assert_eq!(report.events, 5);
+    assert!((report.metrics.brier_score - brier).abs() < 1e-12);
+    assert!((report.metrics.observed_expected - observed / expected).abs() < 1e-12);
+}
+
+#[test]
+fn calibrated_predictions_are_recognised() {
+    let data = simulated(20000, 5, 10.0);
+    let report = validate_predictions(&data, 10.0, 0, 0.95, 0).unwrap();
+    let metrics = report.metrics;
+    assert!(metrics.c_statistic > 0.6 && metrics.c_statistic < 0.8);
+    assert!(metrics.calibration_intercept.abs() < 0.1);
+    assert!((metrics.calibration_slope - 1.0).abs() < 0.1);
+    assert!((metrics.observed_expected - 1.0).abs() < 0.05);
+
+    assert_eq!(report.deciles.len(), 10);
+    assert_eq!(report.deciles.iter().map(|bin| bin.n).sum::<usize>(), 20000);
+    assert_eq!(
+        report.deciles.iter().map(|bin| bin.events).sum::<usize>(),
+        report.events
+    );
+    for bin in &report.deciles {
+        assert!((bin.observed - bin.mean_predicted).abs() < 3.0);
+    }
+    assert!(report
+        .deciles
+        .windows(2)
+        .all(|pair| pair[0].mean_predicted < pair[1].mean_predicted));
+}
+
+#[test]
+fn overprediction_is_detected() {
+    let doubled: Vec<Observation> = simulated(20000, 6, 10.0)
+        .into_iter()
+        .map(|o| Observation {
+            risk: o.risk * 2.0,
+            ..o
+        })
+        .collect();
+    let metrics = validate_predictions(&doubled, 10.0, 0, 0.95, 0)
+        .unwrap()
+        .metrics;
+    assert!(metrics.calibration_intercept < -0.5);
+    assert!((metrics.observed_expected - 0.5).abs() < 0.05);
+}
+
+#[test]
+fn events_after_the_horizon_are_censored() {
+    let data = simulated(500, 7, 10.0);
+    let later: Vec<Observation> = data
+        .iter()
+        .map(|o| Observation {
+            event: o.event || o.time > 10.0,
+            ..*o
+        })
+        .collect();
+    assert_eq!(
+        validate_predictions(&data, 10.0, 0, 0.95, 0).unwrap(),
+        validate_predictions(&later, 10.0, 0, 0.95, 0).unwrap()
+    );
+}
+
+#[test]
+fn bootstrap_intervals_are_reproducible_and_cover_the_estimate() {
+    let data = simulated(2000, 8, 10.0);
+    let report = validate_predictions(&data, 10.0, 200, 0.95, 3).unwrap();
+    let intervals = report.intervals.unwrap();
+    for (estimate, (lower, upper)) in report.metrics.to_array().iter().zip(intervals) {
+        assert!(lower < *estimate && *estimate < upper);
+    }
+    assert_eq!(
+        report,
+        validate_predictions(&data, 10.0, 200, 0.95, 3).unwrap()
+    );
+    assert!(validate_predictions(&data, 10.0, 0, 0.95, 3)
+        .unwrap()
+        .intervals
+        .is_none());
+}
+
+#[test]
+fn invalid_inputs_are_rejected() {
+    assert!(observations(&[1.0, 2.0], &[true], &[1.0, 2.0]).is_err());
+    assert!(observations(&[150.0], &[true], &[1.0]).is_err());
+    assert!(observations(&[1.0], &[true], &[-1.0]).is_err());
+    let kept = observations(
+        &[1.0, f64::NAN, 3.0],
+        &[true, false, false],
+        &[1.0, 2.0, 3.0],
+    )
+    .unwrap();
+    assert_eq!(kept.len(), 2);
+
+    let data = simulated(100, 9, 10.0);
+    assert!(validate_predictions(&data[..9], 10.0, 0, 0.95, 0).is_err());
+    assert!(validate_predictions(&data, 0.0, 0, 0.95, 0).is_err());
+    assert!(validate_predictions(&data, 10.0, 0, 1.5, 0).is_err());
+    let no_events: Vec<Observation> = data
+        .iter()
+        .map(|o| Observation { event: false, ..*o })
+        .collect();
+    assert!(validate_predictions(&no_events, 10.0, 0, 0.95, 0).is_err());
+}
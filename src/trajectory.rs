@@ -0,0 +1,198 @@
+use crate::model::{Patient, PreventModel, RiskModel, Sex};
+use crate::utils::{input_ranges, patients};
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Yearly change in each continuous input other than age, in the order of
+/// `input_ranges` without age: total cholesterol, HDL cholesterol, systolic
+/// BP, BMI and eGFR. The default is no change.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Trends {
+    pub per_year: [f64; 5],
+}
+
+impl Trends {
+    pub fn from_map(trends: &HashMap<String, f64>) -> Result<Trends, String> {
+        let mut result = Trends::default();
+        for (field, change) in trends {
+            let index = input_ranges(true)[1..]
+                .iter()
+                .position(|range| range.field == field)
+                .ok_or_else(|| format!("Unknown input '{}' in trends.", field))?;
+            if !change.is_finite() {
+                return Err(format!("Trend for '{}' must be a finite number.", field));
+            }
+            result.per_year[index] = *change;
+        }
+        Ok(result)
+    }
+
+    /// The patient `years` from now. Projected values are held at the nearest
+    /// bound of their valid range, so a declining eGFR stops at 15.
+    pub fn project(&self, patient: &Patient, years: f64) -> Patient {
+        let ranges = input_ranges(true);
+        let mut values = [
+            patient.total_cholesterol,
+            patient.hdl_cholesterol,
+            patient.systolic_bp,
+            patient.bmi,
+            patient.egfr,
+        ];
+        for ((value, change), range) in values.iter_mut().zip(self.per_year).zip(&ranges[1..]) {
+            if change != 0.0 {
+                *value = (*value + change * years).clamp(range.min, range.max);
+            }
+        }
+        let [total_cholesterol, hdl_cholesterol, systolic_bp, bmi, egfr] = values;
+        Patient {
+            age: patient.age + years,
+            total_cholesterol,
+            hdl_cholesterol,
+            systolic_bp,
+            bmi,
+            egfr,
+            ..patient.clone()
+        }
+    }
+}
+
+/// Smallest step between trajectory points, about five weeks. Across the 30
+/// to 79 age range this keeps a trajectory under 500 points.
+pub const MIN_STEP_YEARS: f64 = 0.1;
+
+/// Years from now at which a trajectory is evaluated: 0, `step_years`,
+/// 2 × `step_years`, ... while the patient is at most `max_age`.
+fn trajectory_years(age: f64, step_years: f64, max_age: f64) -> Vec<f64> {
+    (0..)
+        .map(|i| i as f64 * step_years)
+        .take_while(|years| age + years <= max_age)
+        .collect()
+}
+
+fn check_trajectory_arguments(
+    model: PreventModel,
+    step_years: f64,
+    max_age: f64,
+) -> Result<(), String> {
+    if !model.is_ten_year() {
+        return Err("Trajectories are only available for 10-year outcomes.".to_string());
+    }
+    if step_years.is_nan() || step_years < MIN_STEP_YEARS {
+        return Err(format!("Step must be at least {} years.", MIN_STEP_YEARS));
+    }
+    let age_range = input_ranges(true)[0];
+    if !(age_range.min..=age_range.max).contains(&max_age) {
+        return Err(format!(
+            "Maximum age must be between {} and {}.",
+            age_range.min, age_range.max
+        ));
+    }
+    Ok(())
+}
+
+/// The 10-year risk of `model` at the patient's current age and every
+/// `step_years` after, up to `max_age`, with risk factors following `trends`.
+/// Returns (age, risk) pairs.
+pub fn risk_trajectory(
+    model: PreventModel,
+    patient: &Patient,
+    trends: &Trends,
+    step_years: f64,
+    max_age: f64,
+) -> Result<Vec<(f64, f64)>, String> {
+    check_trajectory_arguments(model, step_years, max_age)?;
+    model.validate(patient)?;
+    trajectory_years(patient.age, step_years, max_age)
+        .into_iter()
+        .map(|years| {
+            let projected = trends.project(patient, years);
+            Ok((projected.age, model.predict(&projected)?))
+        })
+        .collect()
+}
+
+#[pyfunction]
+pub fn risk_trajectory_rust(
+    outcome: String,
+    sex: String,
+    age: f64,
+    total_cholesterol: f64,
+    hdl_cholesterol: f64,
+    systolic_bp: f64,
+    has_diabetes: bool,
+    current_smoker: bool,
+    bmi: f64,
+    egfr: f64,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    trends: HashMap<String, f64>,
+    step_years: f64,
+    max_age: f64,
+) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let model = PreventModel::from_key(&outcome).map_err(PyValueError::new_err)?;
+    let trends = Trends::from_map(&trends).map_err(PyValueError::new_err)?;
+    let patient = Patient {
+        sex: Sex::parse(&sex).map_err(PyValueError::new_err)?,
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        has_diabetes,
+        current_smoker,
+        bmi,
+        egfr,
+        on_htn_meds,
+        on_cholesterol_meds,
+    };
+    match risk_trajectory(model, &patient, &trends, step_years, max_age) {
+        Ok(points) => Ok(points.into_iter().unzip()),
+        Err(e) => Err(PyValueError::new_err(e)), // Convert Rust String error to Python ValueError
+    }
+}
+
+/// Returns the years from now that were evaluated and an (n, k) array of
+/// risks, where column `j` is the risk `years[j]` from now. Entries past
+/// `max_age` and rows with invalid inputs are NaN.
+#[pyfunction]
+pub fn risk_trajectory_rust_parallel_np(
+    py: Python,
+    outcome: String,
+    data: PyReadonlyArrayDyn<f64>,
+    trends: HashMap<String, f64>,
+    step_years: f64,
+    max_age: f64,
+) -> PyResult<(Vec<f64>, PyObject)> {
+    let model = PreventModel::from_key(&outcome).map_err(PyValueError::new_err)?;
+    let trends = Trends::from_map(&trends).map_err(PyValueError::new_err)?;
+    check_trajectory_arguments(model, step_years, max_age).map_err(PyValueError::new_err)?;
+    let patients = patients(&data)?;
+
+    // Enough columns for the youngest valid patient's trajectory.
+    let min_age = input_ranges(true)[0].min;
+    let youngest = patients
+        .iter()
+        .map(|patient| patient.age)
+        .filter(|age| *age >= min_age)
+        .fold(max_age, f64::min);
+    let years = trajectory_years(youngest, step_years, max_age);
+    let results: Vec<Vec<f64>> = patients
+        .par_iter()
+        .map(|patient| {
+            let mut risks = vec![f64::NAN; years.len()];
+            if let Ok(points) = risk_trajectory(model, patient, &trends, step_years, max_age) {
+                for (risk, (_, point_risk)) in risks.iter_mut().zip(points) {
+                    *risk = point_risk;
+                }
+            }
+            risks
+        })
+        .collect();
+
+    let risks = PyArray::from_vec2(py, &results)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?
+        .to_object(py);
+    Ok((years, risks))
+}
@@ -1,10 +1,71 @@
 use crate::covariates::Covariates;
+use crate::model::{predict_batch, Patient, RiskModel, Sex};
 use numpy::{PyArray, PyReadonlyArrayDyn};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use rayon::prelude::*;
 use std::f64;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InputRange {
+    pub field: &'static str,
+    pub min: f64,
+    pub max: f64,
+    pub message: &'static str,
+}
+
+/// Valid range of each continuous input, in the order age, total cholesterol,
+/// HDL cholesterol, systolic blood pressure, BMI and eGFR.
+pub fn input_ranges(ten_year: bool) -> [InputRange; 6] {
+    let age = if ten_year {
+        InputRange {
+            field: "age",
+            min: 30.0,
+            max: 79.0,
+            message: "Age must be between 30 and 79",
+        }
+    } else {
+        InputRange {
+            field: "age",
+            min: 30.0,
+            max: 59.0,
+            message: "Age must be between 30 and 59",
+        }
+    };
+    [
+        age,
+        InputRange {
+            field: "total_cholesterol",
+            min: 130.0,
+            max: 320.0,
+            message: "Total cholesterol must be between 130 and 320",
+        },
+        InputRange {
+            field: "hdl_cholesterol",
+            min: 20.0,
+            max: 100.0,
+            message: "HDL cholesterol must be between 20 and 100",
+        },
+        InputRange {
+            field: "systolic_bp",
+            min: 90.0,
+            max: 200.0,
+            message: "Systolic blood pressure must be between 90 and 200",
+        },
+        InputRange {
+            field: "bmi",
+            min: 18.5,
+            max: 39.9,
+            message: "BMI must be between 18.5 and 39.9",
+        },
+        InputRange {
+            field: "egfr",
+            min: 15.0,
+            max: 140.0,
+            message: "eGFR must be between 15 and 140",
+        },
+    ]
+}
+
 pub fn validate_input(
     age: f64,
     total_cholesterol: f64,
@@ -14,45 +75,23 @@ pub fn validate_input(
     egfr: f64,
     ten_year: bool,
 ) -> Result<(), String> {
-    if ten_year {
-        validate_10_yr_age(&age)?
-    } else {
-        validate_30_yr_age(&age)?
-    }
-
-    if !(130.0..=320.0).contains(&total_cholesterol) {
-        return Err("Total cholesterol must be between 130 and 320".to_string());
-    }
-    if !(20.0..=100.0).contains(&hdl_cholesterol) {
-        return Err("HDL cholesterol must be between 20 and 100".to_string());
-    }
-    if !(90.0..=200.0).contains(&systolic_bp) {
-        return Err("Systolic blood pressure must be between 90 and 200".to_string());
-    }
-    if !(18.5..=39.9).contains(&bmi) {
-        return Err("BMI must be between 18.5 and 39.9".to_string());
-    }
-    if !(15.0..=140.0).contains(&egfr) {
-        return Err("eGFR must be between 15 and 140".to_string());
-    }
-    Ok(())
-}
-
-fn validate_10_yr_age(&age: &f64) -> Result<(), String> {
-    if !(30.0..=79.0).contains(&age) {
-        return Err("Age must be between 30 and 79".to_string());
-    }
-    Ok(())
-}
-
-fn validate_30_yr_age(&age: &f64) -> Result<(), String> {
-    if !(30.0..=59.0).contains(&age) {
-        return Err("Age must be between 30 and 59".to_string());
+    let values = [
+        age,
+        total_cholesterol,
+        hdl_cholesterol,
+        systolic_bp,
+        bmi,
+        egfr,
+    ];
+    for (value, range) in values.iter().zip(input_ranges(ten_year)) {
+        if !(range.min..=range.max).contains(value) {
+            return Err(range.message.to_string());
+        }
     }
     Ok(())
 }
 
-pub fn common_calculation(
+pub(crate) fn common_calculation(
     covariates: &Covariates,
     has_diabetes: bool,
     current_smoker: bool,
@@ -150,7 +189,208 @@ pub fn common_calculation(
     .sum()
 }
 
-type RiskCalcFn = fn(
+/// Partial derivatives of `common_calculation` with respect to age, total
+/// cholesterol, HDL cholesterol, systolic BP, BMI and eGFR, in the order of
+/// `input_ranges`, for the adjusted age `(age - 55) / 10` that every outcome
+/// uses. The splines are piecewise linear, so at a knot the slope above the
+/// knot is used.
+pub(crate) fn common_calculation_gradient(
+    covariates: &Covariates,
+    has_diabetes: bool,
+    current_smoker: bool,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    systolic_bp: f64,
+    cholesterol_diff: f64,
+    hdl_cholesterol: f64,
+    adjusted_age: f64,
+    egfr: f64,
+    bmi: f64,
+) -> [f64; 6] {
+    let indicator = |flag: bool| if flag { 1.0 } else { 0.0 };
+    let multiplier = covariates.cholesterol_base_multiplier;
+    let cholesterol_diff_factor = multiplier * cholesterol_diff - 3.5;
+    let hdl_cholesterol_diff_factor = (multiplier * hdl_cholesterol - 1.3) / 0.3;
+    let systolic_bp_adjusted_max = (systolic_bp.max(110.0) - 130.0) / 20.0;
+    let egfr_adjusted_min = (egfr.min(60.0) - 60.0) / -15.0;
+    let bmi_adjusted_max = (bmi.max(30.0) - 30.0) / 5.0;
+
+    let age = (covariates.age_adjustment_factor
+        + 2.0 * covariates.age_squared_factor * adjusted_age
+        + covariates.age_adjustment_cholesterol_diff_factor * cholesterol_diff_factor
+        + covariates.age_adjustment_hdl_cholesterol_diff_factor * hdl_cholesterol_diff_factor
+        + covariates.age_adjustment_systolic_bp_max_factor * systolic_bp_adjusted_max
+        + covariates.diabetes_age_factor * indicator(has_diabetes)
+        + covariates.smoker_age_factor * indicator(current_smoker)
+        + covariates.age_min_egfr_factor * egfr_adjusted_min
+        + covariates.age_bmi_max_factor * bmi_adjusted_max)
+        / 10.0;
+
+    let cholesterol_diff_slope = multiplier
+        * (covariates.total_cholesterol_diff_factor
+            + covariates.cholesterol_meds_cholesterol_diff_factor * indicator(on_cholesterol_meds)
+            + covariates.age_adjustment_cholesterol_diff_factor * adjusted_age);
+    let hdl_cholesterol_slope = multiplier / 0.3
+        * (covariates.hdl_cholesterol_diff_factor
+            + covariates.age_adjustment_hdl_cholesterol_diff_factor * adjusted_age);
+
+    let systolic_bp_slope = if systolic_bp < 110.0 {
+        covariates.systolic_bp_min_factor / 20.0
+    } else {
+        (covariates.systolic_bp_max_factor
+            + covariates.htn_meds_systolic_bp_max_factor * indicator(on_htn_meds)
+            + covariates.age_adjustment_systolic_bp_max_factor * adjusted_age)
+            / 20.0
+    };
+    let bmi_slope = if bmi < 30.0 {
+        covariates.bmi_min_factor / 5.0
+    } else {
+        (covariates.bmi_max_factor + covariates.age_bmi_max_factor * adjusted_age) / 5.0
+    };
+    let egfr_slope = if egfr < 60.0 {
+        (covariates.egfr_min_factor + covariates.age_min_egfr_factor * adjusted_age) / -15.0
+    } else {
+        covariates.egfr_max_factor / -15.0
+    };
+
+    [
+        age,
+        cholesterol_diff_slope,
+        hdl_cholesterol_slope - cholesterol_diff_slope,
+        systolic_bp_slope,
+        bmi_slope,
+        egfr_slope,
+    ]
+}
+
+/// The term each coefficient of `common_calculation` multiplies, in the
+/// order of `Covariates::coefficients`, for the adjusted age `(age - 55) / 10`
+/// that every outcome uses. The log-odds is the dot product of the two.
+pub(crate) fn common_calculation_design(
+    covariates: &Covariates,
+    has_diabetes: bool,
+    current_smoker: bool,
+    on_htn_meds: bool,
+    on_cholesterol_meds: bool,
+    systolic_bp: f64,
+    cholesterol_diff: f64,
+    hdl_cholesterol: f64,
+    adjusted_age: f64,
+    egfr: f64,
+    bmi: f64,
+) -> [f64; 24] {
+    let indicator = |flag: bool| if flag { 1.0 } else { 0.0 };
+    let multiplier = covariates.cholesterol_base_multiplier;
+    let cholesterol_diff_factor = multiplier * cholesterol_diff - 3.5;
+    let hdl_cholesterol_diff_factor = (multiplier * hdl_cholesterol - 1.3) / 0.3;
+    let systolic_bp_adjusted_max = (systolic_bp.max(110.0) - 130.0) / 20.0;
+    let systolic_bp_adjusted_min = (systolic_bp.min(110.0) - 110.0) / 20.0;
+    let egfr_adjusted_min = (egfr.min(60.0) - 60.0) / -15.0;
+    let egfr_adjusted_max = (egfr.max(60.0) - 90.0) / -15.0;
+    let bmi_adjusted_min = (bmi.min(30.0) - 25.0) / 5.0;
+    let bmi_adjusted_max = (bmi.max(30.0) - 30.0) / 5.0;
+
+    [
+        1.0,
+        indicator(has_diabetes),
+        indicator(current_smoker),
+        indicator(on_htn_meds),
+        indicator(on_cholesterol_meds),
+        adjusted_age,
+        adjusted_age.powi(2),
+        cholesterol_diff_factor,
+        hdl_cholesterol_diff_factor,
+        systolic_bp_adjusted_min,
+        systolic_bp_adjusted_max,
+        egfr_adjusted_max,
+        egfr_adjusted_min,
+        indicator(on_htn_meds) * systolic_bp_adjusted_max,
+        indicator(on_cholesterol_meds) * cholesterol_diff_factor,
+        adjusted_age * cholesterol_diff_factor,
+        adjusted_age * hdl_cholesterol_diff_factor,
+        adjusted_age * systolic_bp_adjusted_max,
+        adjusted_age * egfr_adjusted_min,
+        indicator(has_diabetes) * adjusted_age,
+        indicator(current_smoker) * adjusted_age,
+        bmi_adjusted_min,
+        bmi_adjusted_max,
+        adjusted_age * bmi_adjusted_max,
+    ]
+}
+
+/// 10-year risk categories used by the 2019 ACC/AHA primary prevention
+/// guideline, and the PREVENT statement for 10-year CVD.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskCategory {
+    Low,
+    Borderline,
+    Intermediate,
+    High,
+}
+
+impl RiskCategory {
+    pub const ALL: [RiskCategory; 4] = [
+        RiskCategory::Low,
+        RiskCategory::Borderline,
+        RiskCategory::Intermediate,
+        RiskCategory::High,
+    ];
+
+    /// Lower bound (%) of each category above `Low`.
+    pub const THRESHOLDS: [f64; 3] = [5.0, 7.5, 20.0];
+
+    /// Category of a 10-year risk percentage, or `None` if the risk is NaN.
+    pub fn from_10_yr_risk(risk: f64) -> Option<RiskCategory> {
+        if risk.is_nan() {
+            return None;
+        }
+        let index = RiskCategory::THRESHOLDS
+            .iter()
+            .filter(|&&threshold| risk >= threshold)
+            .count();
+        Some(RiskCategory::ALL[index])
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RiskCategory::Low => "low",
+            RiskCategory::Borderline => "borderline",
+            RiskCategory::Intermediate => "intermediate",
+            RiskCategory::High => "high",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Converts a linear predictor (log-odds) into a risk percentage.
+///
+/// Only ever exponentiates a non-positive number, so the result stays within
+/// [0, 100] for any finite or infinite predictor instead of overflowing to NaN.
+pub fn risk_from_log_odds(log_odds: f64) -> f64 {
+    let probability = if log_odds >= 0.0 {
+        1.0 / (1.0 + (-log_odds).exp())
+    } else {
+        let odds = log_odds.exp();
+        odds / (1.0 + odds)
+    };
+    probability * 100.0
+}
+
+/// Natural log of the risk as a proportion, i.e. the log-sigmoid of the
+/// linear predictor. Keeps full precision for risks far too small to be
+/// represented as a percentage.
+pub fn log_risk_from_log_odds(log_odds: f64) -> f64 {
+    if log_odds >= 0.0 {
+        -(-log_odds).exp().ln_1p()
+    } else {
+        log_odds - log_odds.exp().ln_1p()
+    }
+}
+
+pub type RiskCalcFn = fn(
     &str, // sex
     f64,  // age
     f64,  // total_cholesterol
@@ -164,69 +404,42 @@ type RiskCalcFn = fn(
     bool, // on_cholesterol_meds
 ) -> Result<f64, String>;
 
-pub fn calculate_risk_rust_parallel_np(
-    py: Python,
-    data: PyReadonlyArrayDyn<f64>,
-    risk_calc_fn: RiskCalcFn,
-) -> PyResult<PyObject> {
+pub(crate) fn patients(data: &PyReadonlyArrayDyn<f64>) -> PyResult<Vec<Patient>> {
     let shape = data.shape();
     if shape.len() != 2 || shape[1] != 11 {
         return Err(PyValueError::new_err("Array shape must be (n, 11)"));
     }
 
-    let rows = data
+    Ok(data
         .as_array()
         .outer_iter()
-        .map(|row| {
-            (
-                if row[0] == 1.0 { "male" } else { "female" }, // Convert numeric to "male" or "female"
-                row[1],
-                row[2],
-                row[3],
-                row[4],
-                row[5] != 0.0, // Convert float to bool
-                row[6] != 0.0, // Convert float to bool
-                row[7],
-                row[8],
-                row[9] != 0.0,  // Convert float to bool
-                row[10] != 0.0, // Convert float to bool
-            )
+        .map(|row| Patient {
+            sex: if row[0] == 1.0 {
+                Sex::Male
+            } else {
+                Sex::Female
+            }, // Convert numeric to male or female
+            age: row[1],
+            total_cholesterol: row[2],
+            hdl_cholesterol: row[3],
+            systolic_bp: row[4],
+            has_diabetes: row[5] != 0.0, // Convert float to bool
+            current_smoker: row[6] != 0.0,
+            bmi: row[7],
+            egfr: row[8],
+            on_htn_meds: row[9] != 0.0,
+            on_cholesterol_meds: row[10] != 0.0,
         })
-        .collect::<Vec<_>>();
-
-    let results: Vec<_> = rows
-        .into_par_iter()
-        .map(
-            |(
-                sex,
-                age,
-                total_cholesterol,
-                hdl_cholesterol,
-                systolic_bp,
-                has_diabetes,
-                current_smoker,
-                bmi,
-                egfr,
-                on_htn_meds,
-                on_cholesterol_meds,
-            )| {
-                risk_calc_fn(
-                    sex,
-                    age,
-                    total_cholesterol,
-                    hdl_cholesterol,
-                    systolic_bp,
-                    has_diabetes,
-                    current_smoker,
-                    bmi,
-                    egfr,
-                    on_htn_meds,
-                    on_cholesterol_meds,
-                )
-                .unwrap_or(f64::NAN) // Handle error by returning NaN
-            },
-        )
-        .collect();
+        .collect())
+}
+
+pub fn calculate_risk_rust_parallel_np(
+    py: Python,
+    data: PyReadonlyArrayDyn<f64>,
+    model: &dyn RiskModel,
+) -> PyResult<PyObject> {
+    let patients = patients(&data)?;
+    let results = predict_batch(model, &patients);
 
     Ok(PyArray::from_vec(py, results).to_object(py))
 }
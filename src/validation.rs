@@ -0,0 +1,484 @@
+use numpy::{PyArray, PyReadonlyArrayDyn};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+/// A predicted risk (%) with the observed outcome: whether the event
+/// happened, and the years to the event or to censoring.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Observation {
+    pub risk: f64,
+    pub event: bool,
+    pub time: f64,
+}
+
+impl Observation {
+    /// The outcome at `horizon_years`: events after the horizon are censored
+    /// at the horizon.
+    pub(crate) fn truncate(&self, horizon_years: f64) -> Observation {
+        Observation {
+            risk: self.risk,
+            event: self.event && self.time <= horizon_years,
+            time: self.time.min(horizon_years),
+        }
+    }
+}
+
+/// Builds observations from parallel columns, skipping rows whose prediction
+/// is NaN (as the batch calculators return for invalid inputs).
+pub fn observations(
+    risks: &[f64],
+    events: &[bool],
+    times: &[f64],
+) -> Result<Vec<Observation>, String> {
+    if risks.len() != events.len() || risks.len() != times.len() {
+        return Err("Predictions, events and times must have the same length.".to_string());
+    }
+    let mut result = Vec::with_capacity(risks.len());
+    for ((risk, event), time) in risks.iter().zip(events).zip(times) {
+        if risk.is_nan() {
+            continue;
+        }
+        if !(0.0..=100.0).contains(risk) {
+            return Err(format!("Predicted risk {} is not a percentage.", risk));
+        }
+        if !(time.is_finite() && *time >= 0.0) {
+            return Err(format!("Follow-up time {} must be non-negative.", time));
+        }
+        result.push(Observation {
+            risk: *risk,
+            event: *event,
+            time: *time,
+        });
+    }
+    Ok(result)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValidationMetrics {
+    /// Harrell's C: among usable pairs, where the patient with the shorter
+    /// follow-up had the event, the share in which that patient had the
+    /// higher predicted risk. Ties in risk count one half.
+    pub c_statistic: f64,
+    /// Mean squared error of the predicted probability at the horizon,
+    /// weighted by the inverse probability of remaining uncensored.
+    pub brier_score: f64,
+    /// Intercept of a logistic recalibration of the observed outcome on the
+    /// logit of the prediction with slope fixed at 1 (calibration in the
+    /// large); 0 when predictions are right on average.
+    pub calibration_intercept: f64,
+    /// Slope of a logistic recalibration on the logit of the prediction; 1
+    /// when predictions are neither too extreme nor too modest.
+    pub calibration_slope: f64,
+    /// Kaplan-Meier risk at the horizon over the mean predicted risk.
+    pub observed_expected: f64,
+}
+
+/// Names of the fields of `ValidationMetrics`, in order.
+pub const VALIDATION_METRICS: [&str; 5] = [
+    "c_statistic",
+    "brier_score",
+    "calibration_intercept",
+    "calibration_slope",
+    "observed_expected",
+];
+
+impl ValidationMetrics {
+    pub fn to_array(self) -> [f64; 5] {
+        [
+            self.c_statistic,
+            self.brier_score,
+            self.calibration_intercept,
+            self.calibration_slope,
+            self.observed_expected,
+        ]
+    }
+}
+
+/// Observed and predicted risk (%) in one tenth of the observations, ordered
+/// by predicted risk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CalibrationBin {
+    pub n: usize,
+    pub events: usize,
+    pub mean_predicted: f64,
+    /// Kaplan-Meier risk at the horizon.
+    pub observed: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationReport {
+    pub n: usize,
+    /// Events up to the horizon.
+    pub events: usize,
+    pub metrics: ValidationMetrics,
+    /// Bootstrap percentile (lower, upper) interval of each metric, in the
+    /// order of `VALIDATION_METRICS`.
+    pub intervals: Option<[(f64, f64); 5]>,
+    pub deciles: Vec<CalibrationBin>,
+}
+
+/// Harrell's C in O(n log n): patients are added to a Fenwick tree over the
+/// ranks of their risks in decreasing order of time, so each event is
+/// compared against everyone still followed after it. A patient censored at
+/// the same time as an event counts as followed longer.
+fn harrell_c(observations: &[Observation]) -> f64 {
+    let mut ranks: Vec<f64> = observations.iter().map(|o| o.risk).collect();
+    ranks.sort_by(f64::total_cmp);
+    ranks.dedup();
+    let rank = |risk: f64| ranks.partition_point(|r| *r < risk);
+    let mut tree = vec![0u64; ranks.len() + 1];
+    let add = |tree: &mut Vec<u64>, rank: usize| {
+        let mut i = rank + 1;
+        while i < tree.len() {
+            tree[i] += 1;
+            i += i & i.wrapping_neg();
+        }
+    };
+    // Patients added so far with a rank below `rank`.
+    let count_below = |tree: &Vec<u64>, rank: usize| {
+        let mut i = rank;
+        let mut total = 0;
+        while i > 0 {
+            total += tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        total
+    };
+
+    let mut order: Vec<&Observation> = observations.iter().collect();
+    order.sort_by(|a, b| b.time.total_cmp(&a.time));
+    let (mut concordant, mut pairs, mut added) = (0.0, 0u64, 0u64);
+    for group in order.chunk_by(|a, b| a.time == b.time) {
+        for censored in group.iter().filter(|o| !o.event) {
+            add(&mut tree, rank(censored.risk));
+            added += 1;
+        }
+        for event in group.iter().filter(|o| o.event) {
+            let r = rank(event.risk);
+            let below = count_below(&tree, r);
+            let tied = count_below(&tree, r + 1) - below;
+            concordant += below as f64 + 0.5 * tied as f64;
+            pairs += added;
+        }
+        for event in group.iter().filter(|o| o.event) {
+            add(&mut tree, rank(event.risk));
+            added += 1;
+        }
+    }
+    concordant / pairs as f64
+}
+
+/// Kaplan-Meier survival after each distinct time with a failure, where
+/// `failed` chooses which observations count as failures. Everyone followed
+/// to at least a time is at risk at that time.
+fn kaplan_meier(observations: &[Observation], failed: fn(&Observation) -> bool) -> Vec<(f64, f64)> {
+    let mut sorted: Vec<&Observation> = observations.iter().collect();
+    sorted.sort_by(|a, b| a.time.total_cmp(&b.time));
+    let mut at_risk = sorted.len() as f64;
+    let mut survival = 1.0;
+    let mut steps = Vec::new();
+    for group in sorted.chunk_by(|a, b| a.time == b.time) {
+        let failures = group.iter().filter(|o| failed(o)).count() as f64;
+        if failures > 0.0 {
+            survival *= 1.0 - failures / at_risk;
+            steps.push((group[0].time, survival));
+        }
+        at_risk -= group.len() as f64;
+    }
+    steps
+}
+
+/// Survival of a Kaplan-Meier curve at `time`, or just before it if
+/// `before`.
+fn survival_at(steps: &[(f64, f64)], time: f64, before: bool) -> f64 {
+    let count = steps.partition_point(|step| {
+        if before {
+            step.0 < time
+        } else {
+            step.0 <= time
+        }
+    });
+    if count == 0 {
+        1.0
+    } else {
+        steps[count - 1].1
+    }
+}
+
+fn observed_risk(observations: &[Observation], horizon_years: f64) -> f64 {
+    let steps = kaplan_meier(observations, |o| o.event);
+    (1.0 - survival_at(&steps, horizon_years, false)) * 100.0
+}
+
+fn logit(risk: f64) -> f64 {
+    let p = (risk / 100.0).clamp(1e-10, 1.0 - 1e-10);
+    (p / (1.0 - p)).ln()
+}
+
+/// Weighted logistic regression of `outcomes` on an intercept and, if
+/// `fit_slope`, on `x`; otherwise `x` is an offset. Returns (intercept,
+/// slope) by Newton-Raphson, with slope 1 when it is not fitted.
+fn logistic_recalibration(
+    x: &[f64],
+    outcomes: &[f64],
+    weights: &[f64],
+    fit_slope: bool,
+) -> (f64, f64) {
+    let (mut intercept, mut slope) = (0.0, 1.0);
+    for _ in 0..50 {
+        let (mut g0, mut g1, mut h00, mut h01, mut h11) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for ((x, y), w) in x.iter().zip(outcomes).zip(weights) {
+            let p = 1.0 / (1.0 + (-(intercept + slope * x)).exp());
+            let v = w * p * (1.0 - p);
+            g0 += w * (y - p);
+            g1 += w * (y - p) * x;
+            h00 += v;
+            h01 += v * x;
+            h11 += v * x * x;
+        }
+        let (step0, step1) = if fit_slope {
+            let determinant = h00 * h11 - h01 * h01;
+            (
+                (h11 * g0 - h01 * g1) / determinant,
+                (h00 * g1 - h01 * g0) / determinant,
+            )
+        } else {
+            (g0 / h00, 0.0)
+        };
+        if !(step0.is_finite() && step1.is_finite()) {
+            return (f64::NAN, f64::NAN);
+        }
+        intercept += step0;
+        slope += step1;
+        if step0.abs() < 1e-10 && step1.abs() < 1e-10 {
+            break;
+        }
+    }
+    (intercept, slope)
+}
+
+/// Binary outcomes at the horizon of observations already truncated at it,
+/// with inverse probability of censoring weights. Patients censored before
+/// the horizon without an event have unknown status and weight 0. Censoring
+/// at a time is taken to follow the events at that time.
+pub(crate) fn censoring_weighted_outcomes(
+    truncated: &[Observation],
+    horizon_years: f64,
+) -> (Vec<f64>, Vec<f64>) {
+    let censoring = kaplan_meier(truncated, |o| !o.event);
+    let weights = truncated
+        .iter()
+        .map(|o| {
+            if o.event {
+                1.0 / survival_at(&censoring, o.time, true)
+            } else if o.time >= horizon_years {
+                1.0 / survival_at(&censoring, horizon_years, true)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let outcomes = truncated
+        .iter()
+        .map(|o| if o.event { 1.0 } else { 0.0 })
+        .collect();
+    (outcomes, weights)
+}
+
+/// Percentile intervals at `level` of each value of `statistic` over
+/// `replicates` resamples of `items` with replacement, drawn in parallel.
+/// Replicate `b` uses seed `seed + b`. Non-finite replicate values are
+/// ignored.
+pub(crate) fn bootstrap_intervals<T: Copy + Sync>(
+    items: &[T],
+    replicates: usize,
+    level: f64,
+    seed: u64,
+    statistic: impl Fn(&[T]) -> Vec<f64> + Sync,
+) -> Vec<(f64, f64)> {
+    let values: Vec<Vec<f64>> = (0..replicates)
+        .into_par_iter()
+        .map(|b| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed.wrapping_add(b as u64));
+            let sample: Vec<T> = (0..items.len())
+                .map(|_| items[rng.gen_range(0..items.len())])
+                .collect();
+            statistic(&sample)
+        })
+        .collect();
+    let count = values.first().map_or(0, Vec::len);
+    (0..count)
+        .map(|index| {
+            let mut sorted: Vec<f64> = values
+                .iter()
+                .map(|replicate| replicate[index])
+                .filter(|value| value.is_finite())
+                .collect();
+            sorted.sort_by(f64::total_cmp);
+            if sorted.is_empty() {
+                return (f64::NAN, f64::NAN);
+            }
+            let quantile =
+                |p: f64| sorted[((p * sorted.len() as f64) as usize).min(sorted.len() - 1)];
+            (quantile(0.5 - level / 2.0), quantile(0.5 + level / 2.0))
+        })
+        .collect()
+}
+
+fn metrics(observations: &[Observation], horizon_years: f64) -> ValidationMetrics {
+    let truncated: Vec<Observation> = observations
+        .iter()
+        .map(|o| o.truncate(horizon_years))
+        .collect();
+    let (outcomes, weights) = censoring_weighted_outcomes(&truncated, horizon_years);
+
+    let n = truncated.len() as f64;
+    let brier_score = truncated
+        .iter()
+        .zip(&outcomes)
+        .zip(&weights)
+        .map(|((o, y), w)| w * (y - o.risk / 100.0).powi(2))
+        .sum::<f64>()
+        / n;
+    let x: Vec<f64> = truncated.iter().map(|o| logit(o.risk)).collect();
+    let (calibration_intercept, _) = logistic_recalibration(&x, &outcomes, &weights, false);
+    let (_, calibration_slope) = logistic_recalibration(&x, &outcomes, &weights, true);
+    let mean_predicted = truncated.iter().map(|o| o.risk).sum::<f64>() / n;
+
+    ValidationMetrics {
+        c_statistic: harrell_c(&truncated),
+        brier_score,
+        calibration_intercept,
+        calibration_slope,
+        observed_expected: observed_risk(&truncated, horizon_years) / mean_predicted,
+    }
+}
+
+fn deciles(observations: &[Observation], horizon_years: f64) -> Vec<CalibrationBin> {
+    let mut sorted = observations.to_vec();
+    sorted.sort_by(|a, b| a.risk.total_cmp(&b.risk));
+    let n = sorted.len();
+    (0..10)
+        .map(|decile| {
+            let bin = &sorted[decile * n / 10..(decile + 1) * n / 10];
+            CalibrationBin {
+                n: bin.len(),
+                events: bin
+                    .iter()
+                    .filter(|o| o.truncate(horizon_years).event)
+                    .count(),
+                mean_predicted: bin.iter().map(|o| o.risk).sum::<f64>() / bin.len() as f64,
+                observed: observed_risk(bin, horizon_years),
+            }
+        })
+        .collect()
+}
+
+/// Discrimination and calibration of predicted `horizon_years` risks against
+/// observed outcomes, with percentile intervals at `level` from `bootstrap`
+/// resamples if `bootstrap` is not 0. Replicate `b` is drawn with seed
+/// `seed + b`, and replicates run in parallel.
+pub fn validate_predictions(
+    observations: &[Observation],
+    horizon_years: f64,
+    bootstrap: usize,
+    level: f64,
+    seed: u64,
+) -> Result<ValidationReport, String> {
+    if observations.len() < 10 {
+        return Err("At least 10 observations with predictions are needed.".to_string());
+    }
+    if !(horizon_years.is_finite() && horizon_years > 0.0) {
+        return Err("Horizon must be a positive number of years.".to_string());
+    }
+    if !(level > 0.0 && level < 1.0) {
+        return Err("Confidence level must be between 0 and 1.".to_string());
+    }
+    let events = observations
+        .iter()
+        .filter(|o| o.truncate(horizon_years).event)
+        .count();
+    if events == 0 || events == observations.len() {
+        return Err("Both events and non-events up to the horizon are needed.".to_string());
+    }
+
+    let intervals = (bootstrap > 0).then(|| {
+        let intervals = bootstrap_intervals(observations, bootstrap, level, seed, |sample| {
+            metrics(sample, horizon_years).to_array().to_vec()
+        });
+        std::array::from_fn(|metric| intervals[metric])
+    });
+
+    Ok(ValidationReport {
+        n: observations.len(),
+        events,
+        metrics: metrics(observations, horizon_years),
+        intervals,
+        deciles: deciles(observations, horizon_years),
+    })
+}
+
+/// (n, events, metric names, estimates, lower bounds, upper bounds, deciles).
+type ValidationSummary = (
+    usize,
+    usize,
+    Vec<&'static str>,
+    Vec<f64>,
+    Vec<f64>,
+    Vec<f64>,
+    PyObject,
+);
+
+/// Returns the sample size and event count, the metric names, their
+/// estimates, lower and upper bounds (NaN without bootstrap), and a (10, 4)
+/// array of deciles with columns n, events, mean predicted and observed risk.
+#[pyfunction]
+pub fn validate_predictions_rust(
+    py: Python,
+    risks: PyReadonlyArrayDyn<f64>,
+    events: PyReadonlyArrayDyn<bool>,
+    times: PyReadonlyArrayDyn<f64>,
+    horizon_years: f64,
+    bootstrap: usize,
+    level: f64,
+    seed: u64,
+) -> PyResult<ValidationSummary> {
+    let (risks, events, times) = (risks.as_array(), events.as_array(), times.as_array());
+    let observations = observations(
+        &risks.iter().copied().collect::<Vec<_>>(),
+        &events.iter().copied().collect::<Vec<_>>(),
+        &times.iter().copied().collect::<Vec<_>>(),
+    )
+    .map_err(PyValueError::new_err)?;
+    let report = validate_predictions(&observations, horizon_years, bootstrap, level, seed)
+        .map_err(PyValueError::new_err)?; // Convert Rust String error to Python ValueError
+
+    let intervals = report.intervals.unwrap_or([(f64::NAN, f64::NAN); 5]);
+    let deciles: Vec<Vec<f64>> = report
+        .deciles
+        .iter()
+        .map(|bin| {
+            vec![
+                bin.n as f64,
+                bin.events as f64,
+                bin.mean_predicted,
+                bin.observed,
+            ]
+        })
+        .collect();
+    let deciles = PyArray::from_vec2(py, &deciles)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?
+        .to_object(py);
+    Ok((
+        report.n,
+        report.events,
+        VALIDATION_METRICS.to_vec(),
+        report.metrics.to_array().to_vec(),
+        intervals.iter().map(|interval| interval.0).collect(),
+        intervals.iter().map(|interval| interval.1).collect(),
+        deciles,
+    ))
+}
@@ -0,0 +1,46 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+
+def _cohort():
+    return pd.DataFrame(
+        {
+            "sex": ["female", "female", "male", "male", "male"],
+            "age": [45, 52, 58, 61, 20],
+            "total_cholesterol": [200] * 5,
+            "hdl_cholesterol": [45] * 5,
+            "systolic_bp": [140] * 5,
+            "has_diabetes": [False, True, False, True, False],
+            "current_smoker": [False] * 5,
+            "bmi": [28] * 5,
+            "egfr": [95, 70, 50, 20, 90],
+            "on_htn_meds": [False] * 5,
+            "on_cholesterol_meds": [False] * 5,
+        }
+    )
+
+
+def test_cohort_summary_with_predictions():
+    risks = np.array([2.0, 6.0, 10.0, 30.0, np.nan])
+    summary = pyprevent.cohort_summary(_cohort(), ["10_yr_cvd"], predictions={"10_yr_cvd": risks})
+    overall = summary.loc[("10_yr_cvd", "overall", "all")]
+    assert overall["n"] == 5
+    assert overall["invalid"] == 1
+    slash.assert_almost_equal(overall["mean"], 12.0, delta=1e-9)
+    slash.assert_almost_equal(overall["iqr"], 10.0, delta=1e-9)
+    assert set(summary.loc[("10_yr_cvd", "ckd_stage")].index) == {"G1", "G2", "G3a", "G4"}
+    assert summary.loc[("10_yr_cvd", "sex")]["n"].sum() == 5
+
+
+def test_cohort_summary_scores_missing_models():
+    summary = pyprevent.cohort_summary(_cohort(), ["10_yr_ascvd", "30_yr_hf"])
+    assert set(summary.index.get_level_values("model")) == {"10_yr_ascvd", "30_yr_hf"}
+    assert np.isnan(summary.loc[("30_yr_hf", "overall", "all"), "proportion_high"])
+    assert "model,stratifier,level" in summary.to_csv()
+
+
+def test_cohort_summary_rejects_mismatched_predictions():
+    with slash.assert_raises(ValueError):
+        pyprevent.cohort_summary(_cohort(), ["10_yr_cvd"], predictions={"10_yr_cvd": [1.0, 2.0]})
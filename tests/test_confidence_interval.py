@@ -0,0 +1,97 @@
+import os
+import tempfile
+
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+COVARIANCE = """model,sex,row,column,covariance
+10_yr_cvd,female,constant,constant,0.0025
+10_yr_cvd,female,age_adjustment_factor,age_adjustment_factor,0.0004
+10_yr_cvd,female,constant,age_adjustment_factor,-0.0002
+"""
+
+
+def _write_covariance(directory):
+    path = os.path.join(directory, "covariance.csv")
+    with open(path, "w") as f:
+        f.write(COVARIANCE)
+    return path
+
+
+PATIENT = ("female", 55, 220, 45, 140, False, True, 30, 80, False, False)
+
+
+def test_coefficient_names():
+    names = pyprevent.coefficient_names()
+    assert len(names) == 24
+    assert names[0] == "constant"
+
+
+def test_calculate_risk_with_confidence_interval():
+    with tempfile.TemporaryDirectory() as directory:
+        path = _write_covariance(directory)
+        result = pyprevent.calculate_risk("10_yr_cvd", *PATIENT, covariance=path, seed=1)
+        assert result["risk"] == pyprevent.calculate_risk("10_yr_cvd", *PATIENT)
+        # Age 55, so only the constant's variance applies.
+        slash.assert_almost_equal(result["log_odds_se"], 0.05, places=12)
+        assert result["delta_lower"] < result["risk"] < result["delta_upper"]
+        assert result["simulation_lower"] < result["risk"] < result["simulation_upper"]
+
+        with slash.assert_raises(ValueError):
+            pyprevent.calculate_risk("10_yr_cvd", "male", *PATIENT[1:], covariance=path)
+        with slash.assert_raises(ValueError):
+            pyprevent.calculate_risk("score2_low", *PATIENT, covariance=path)
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_risk("10_yr_cvd", *PATIENT, covariance="missing.csv")
+
+
+def test_batch_calculate_risk_with_confidence_interval():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "male", "female"],
+            "age": [55, 55, 90],
+            "total_cholesterol": [220, 220, 220],
+            "hdl_cholesterol": [45, 45, 45],
+            "systolic_bp": [140, 140, 140],
+            "has_diabetes": [False, False, False],
+            "current_smoker": [True, True, True],
+            "bmi": [30, 30, 30],
+            "egfr": [80, 80, 80],
+            "on_htn_meds": [False, False, False],
+            "on_cholesterol_meds": [False, False, False],
+        }
+    )
+    with tempfile.TemporaryDirectory() as directory:
+        path = _write_covariance(directory)
+        result = pyprevent.batch_calculate_risk(df, "10_yr_cvd", covariance=path, seed=1)
+        single = pyprevent.calculate_risk("10_yr_cvd", *PATIENT, covariance=path, seed=1)
+        assert list(result.columns) == list(single)
+        np.testing.assert_allclose(result.iloc[0].values, list(single.values()))
+        assert result.iloc[1].isna().all()
+        assert result.iloc[2].isna().all()
+
+
+def test_outcome_functions_with_confidence_interval():
+    columns = [
+        "sex",
+        "age",
+        "total_cholesterol",
+        "hdl_cholesterol",
+        "systolic_bp",
+        "has_diabetes",
+        "current_smoker",
+        "bmi",
+        "egfr",
+        "on_htn_meds",
+        "on_cholesterol_meds",
+    ]
+    df = pd.DataFrame([PATIENT], columns=columns)
+    with tempfile.TemporaryDirectory() as directory:
+        path = _write_covariance(directory)
+        single = pyprevent.calculate_10_yr_cvd_risk(*PATIENT, covariance=path, seed=1)
+        assert single == pyprevent.calculate_risk("10_yr_cvd", *PATIENT, covariance=path, seed=1)
+        result = pyprevent.batch_calculate_10_yr_cvd_risk(df, covariance=path, seed=1)
+        np.testing.assert_allclose(result.iloc[0].values, list(single.values()))
+    assert pyprevent.calculate_10_yr_cvd_risk(*PATIENT) == single["risk"]
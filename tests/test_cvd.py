@@ -38,6 +38,17 @@ def test_calculate_30_yr_cvd_risk_default_patient():
     slash.assert_almost_equal(result, expected_result, delta=0.1)
 
 
+def test_calculate_cvd_log_risk_default_patient():
+    test_dict = {k: v for k, v in TEST_PATIENT.items() if not k.endswith("_expected")}
+    result = pyprevent.calculate_10_yr_cvd_log_risk(**test_dict)
+    expected_result = np.log(TEST_PATIENT["10_yr_cvd_expected"] / 100)
+    slash.assert_almost_equal(result, expected_result, delta=0.01)
+
+    result = pyprevent.calculate_30_yr_cvd_log_risk(**test_dict)
+    expected_result = np.log(TEST_PATIENT["30_yr_cvd_expected"] / 100)
+    slash.assert_almost_equal(result, expected_result, delta=0.01)
+
+
 @given(generate_10_yr_test_case())
 def test_calculate_10_yr_cvd_risk_with_hypothesis(test_case):
     result = pyprevent.calculate_10_yr_cvd_risk(**test_case)
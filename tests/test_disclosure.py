@@ -0,0 +1,51 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+
+def _cohort(n):
+    rng = np.random.default_rng(0)
+    return pd.DataFrame(
+        {
+            "sex": np.where(np.arange(n) < 8, "female", "male"),
+            "age": rng.integers(40, 70, n),
+            "total_cholesterol": 200,
+            "hdl_cholesterol": 45,
+            "systolic_bp": 140,
+            "has_diabetes": rng.uniform(size=n) < 0.3,
+            "current_smoker": False,
+            "bmi": 28,
+            "egfr": 90,
+            "on_htn_meds": False,
+            "on_cholesterol_meds": False,
+        }
+    )
+
+
+def test_small_cells_are_suppressed():
+    summary = pyprevent.protected_cohort_summary(_cohort(200), ["10_yr_cvd"])
+    sex = summary.loc[("10_yr_cvd", "sex")]
+    assert sex.loc["female", "suppression"] == "primary"
+    assert sex.loc["male", "suppression"] == "complementary"
+    assert sex["mean"].isna().all()
+    assert summary.loc[("10_yr_cvd", "overall", "all"), "n"] == 200
+
+
+def test_rounding_and_noise():
+    df = _cohort(200)
+    summary = pyprevent.protected_cohort_summary(
+        df, ["10_yr_cvd"], count_base=5, decimals=1, epsilon=1.0, seed=3
+    )
+    published = summary[summary["suppression"] == ""]
+    assert (published["n"] % 5 == 0).all()
+    assert np.allclose(published["median"], published["median"].round(1))
+    again = pyprevent.protected_cohort_summary(
+        df, ["10_yr_cvd"], count_base=5, decimals=1, epsilon=1.0, seed=3
+    )
+    pd.testing.assert_frame_equal(summary, again)
+
+
+def test_invalid_policy_is_rejected():
+    with slash.assert_raises(ValueError):
+        pyprevent.protected_cohort_summary(_cohort(50), ["10_yr_cvd"], epsilon=-1.0)
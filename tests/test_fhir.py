@@ -0,0 +1,72 @@
+import pyprevent
+import slash
+
+
+def _observation(id, loinc, value, unit):
+    return {
+        "resourceType": "Observation",
+        "id": id,
+        "status": "final",
+        "subject": {"reference": "Patient/p1"},
+        "code": {"coding": [{"system": "http://loinc.org", "code": loinc}]},
+        "effectiveDateTime": "2024-03-01",
+        "valueQuantity": {"value": value, "unit": unit},
+    }
+
+
+def _bundle():
+    resources = [
+        {"resourceType": "Patient", "id": "p1", "gender": "male", "birthDate": "1969-01-20"},
+        _observation("tc", "2093-3", 210, "mg/dL"),
+        _observation("hdl", "2085-9", 42, "mg/dL"),
+        _observation("sbp", "8480-6", 136, "mm[Hg]"),
+        _observation("bmi", "39156-5", 29, "kg/m2"),
+        _observation("egfr", "98979-8", 85, "mL/min/{1.73_m2}"),
+        {
+            "resourceType": "Observation",
+            "id": "smoking",
+            "status": "final",
+            "subject": {"reference": "Patient/p1"},
+            "code": {"coding": [{"system": "http://loinc.org", "code": "72166-2"}]},
+            "effectiveDateTime": "2024-03-01",
+            "valueCodeableConcept": {
+                "coding": [{"system": "http://snomed.info/sct", "code": "8517006"}]
+            },
+        },
+        {
+            "resourceType": "MedicationStatement",
+            "id": "statin",
+            "status": "active",
+            "subject": {"reference": "Patient/p1"},
+            "medicationCodeableConcept": {
+                "coding": [{"system": "http://www.whocc.no/atc", "code": "C10AA05"}]
+            },
+        },
+    ]
+    return {"resourceType": "Bundle", "type": "collection", "entry": [{"resource": r} for r in resources]}
+
+
+def test_assess_fhir_bundle():
+    result = pyprevent.assess_fhir_bundle(_bundle(), ["10_yr_cvd"], as_of="2024-06-01")
+    inputs = result["inputs"]
+    assert inputs["sex"] == "male"
+    assert inputs["age"] == 55
+    assert inputs["on_cholesterol_meds"] is True
+    assert inputs["current_smoker"] is False
+    assert result["provenance"]["systolic_bp"] == "Observation/sbp"
+    assert result["provenance"]["has_diabetes"] is None
+    assert result["errors"] == {}
+
+    expected = pyprevent.calculate_risk("10_yr_cvd", *inputs.values())
+    resource = result["risk_assessments"]["entry"][0]["resource"]
+    assert resource["resourceType"] == "RiskAssessment"
+    slash.assert_almost_equal(
+        resource["prediction"][0]["probabilityDecimal"], expected / 100, delta=1e-12
+    )
+
+
+def test_missing_observation_is_rejected():
+    bundle = _bundle()
+    bundle["entry"] = [e for e in bundle["entry"] if e["resource"]["id"] != "egfr"]
+    with slash.assert_raises(ValueError):
+        pyprevent.assess_fhir_bundle(bundle, as_of="2024-06-01")
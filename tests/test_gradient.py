@@ -0,0 +1,55 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+PATIENT = ("female", 50, 220, 45, 145, True, True, 33, 75, True, True)
+
+
+def test_risk_gradient_matches_finite_difference():
+    gradient = pyprevent.risk_gradient("10_yr_cvd", *PATIENT)
+    assert list(gradient.index) == [
+        "age",
+        "total_cholesterol",
+        "hdl_cholesterol",
+        "systolic_bp",
+        "bmi",
+        "egfr",
+    ]
+    assert gradient.attrs["risk"] == pyprevent.calculate_10_yr_cvd_risk(*PATIENT)
+
+    step = 1e-4
+    above = list(PATIENT)
+    above[4] += step
+    below = list(PATIENT)
+    below[4] -= step
+    slope = (
+        pyprevent.calculate_10_yr_cvd_risk(*above)
+        - pyprevent.calculate_10_yr_cvd_risk(*below)
+    ) / (2 * step)
+    slash.assert_almost_equal(gradient.loc["systolic_bp", "risk"], slope, delta=1e-6)
+
+
+def test_batch_risk_gradient():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "female"],
+            "age": [50, 90],
+            "total_cholesterol": [220, 220],
+            "hdl_cholesterol": [45, 45],
+            "systolic_bp": [145, 145],
+            "has_diabetes": [True, True],
+            "current_smoker": [True, True],
+            "bmi": [33, 33],
+            "egfr": [75, 75],
+            "on_htn_meds": [True, True],
+            "on_cholesterol_meds": [True, True],
+        }
+    )
+    result = pyprevent.batch_risk_gradient(df, "30_yr_hf")
+    single = pyprevent.risk_gradient("30_yr_hf", *PATIENT)
+    np.testing.assert_allclose(result["risk"].iloc[0].values, single["risk"].values)
+    np.testing.assert_allclose(
+        result["log_odds"].iloc[0].values, single["log_odds"].values
+    )
+    assert result["risk"].iloc[1].isna().all()
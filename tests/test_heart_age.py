@@ -0,0 +1,49 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+
+def test_optimal_profile_heart_age():
+    result = pyprevent.calculate_heart_age(
+        "female", 50, 170, 50, 110, False, False, 25, 90, False, False
+    )
+    slash.assert_almost_equal(result["heart_age"], 50, delta=1e-4)
+    assert result["bound"] == "within"
+
+
+def test_heart_age_bounds():
+    result = pyprevent.calculate_heart_age(
+        "male", 75, 320, 20, 200, True, True, 39, 15, True, False
+    )
+    assert result == {"heart_age": 79.0, "difference": 4.0, "bound": "above_maximum"}
+
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_heart_age(
+            "male", 80, 200, 50, 120, False, False, 25, 90, False, False
+        )
+
+
+def test_batch_heart_age():
+    df = pd.DataFrame(
+        {
+            "sex": ["male", "male"],
+            "age": [50, 80],
+            "total_cholesterol": [240, 200],
+            "hdl_cholesterol": [40, 50],
+            "systolic_bp": [150, 120],
+            "has_diabetes": [False, False],
+            "current_smoker": [True, False],
+            "bmi": [30, 25],
+            "egfr": [80, 90],
+            "on_htn_meds": [True, False],
+            "on_cholesterol_meds": [False, False],
+        }
+    )
+    result = pyprevent.batch_calculate_heart_age(df)
+    single = pyprevent.calculate_heart_age(
+        "male", 50, 240, 40, 150, False, True, 30, 80, True, False
+    )
+    slash.assert_almost_equal(result["heart_age"][0], single["heart_age"], delta=1e-9)
+    assert result["bound"][0] == "within"
+    assert np.isnan(result["heart_age"][1])
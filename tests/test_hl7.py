@@ -0,0 +1,47 @@
+import pyprevent
+import slash
+
+_HEADER = [
+    "MSH|^~\\&|LAB|HOSP|EHR|HOSP|20240601120000||ORU^R01^ORU_R01|MSG0001|P|2.5.1",
+    "PID|1||12345^^^HOSP^MR||DOE^JOHN||19690120|M",
+    "OBR|1|||57698-3^Lipid panel^LN|||20240530081500",
+]
+
+
+def _message(*obx):
+    return "\r".join(_HEADER + list(obx))
+
+
+def test_parse_hl7_oru_gives_calculator_inputs():
+    message = _message(
+        "OBX|1|NM|CHOL^Cholesterol^L||210|mg/dL|||||F",
+        "OBX|2|NM|2085-9^HDL^LN||42|mg/dL|||||F",
+        "OBX|3|NM|8480-6^Systolic BP^LN||136|mm[Hg]|||||F",
+        "OBX|4|NM|39156-5^BMI^LN||29|kg/m2|||||F",
+        "OBX|5|NM|2160-0^Creatinine^LN||1.0|mg/dL|||||F",
+    )
+    result = pyprevent.parse_hl7_oru(message, {"CHOL": "total_cholesterol"})
+    assert result["missing"] == []
+    inputs = result["inputs"]
+    assert inputs["sex"] == "male"
+    assert inputs["age"] == 55
+    assert inputs["total_cholesterol"] == 210
+    assert inputs["current_smoker"] is False
+    assert result["provenance"]["egfr"] == "OBX-5 (CKD-EPI 2021)"
+
+    risk = pyprevent.calculate_risk("10_yr_cvd", **inputs)
+    assert 0 < risk < 100
+
+
+def test_parse_hl7_oru_reports_missing_values():
+    message = _message("OBX|1|NM|2093-3^Cholesterol^LN||5.4|mmol/L|||||F")
+    result = pyprevent.parse_hl7_oru(message, as_of="2024-06-01")
+    assert result["missing"] == ["hdl_cholesterol", "systolic_bp", "bmi", "egfr"]
+    assert result["inputs"]["egfr"] is None
+
+
+def test_parse_hl7_oru_rejects_other_messages():
+    with slash.assert_raises(ValueError):
+        pyprevent.parse_hl7_oru(_message().replace("ORU^R01^ORU_R01", "ADT^A01"))
+    with slash.assert_raises(ValueError):
+        pyprevent.parse_hl7_oru(_message(), {"X": "ldl"})
@@ -0,0 +1,66 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+PATIENT = ("female", 45, 210, 55, 135, True, False, 31, 85, True, False)
+
+
+def test_risk_at_horizon_matches_model_horizons():
+    ten = pyprevent.calculate_risk_at_horizon("ascvd", 10, *PATIENT)
+    assert ten["derived"]
+    assert ten["method"] == "piecewise_constant_hazard"
+    assert not ten["hazard_clamped"]
+    slash.assert_almost_equal(
+        ten["risk"], pyprevent.calculate_10_yr_ascvd_risk(*PATIENT), delta=1e-9
+    )
+    thirty = pyprevent.calculate_risk_at_horizon("ascvd", 30, *PATIENT)
+    slash.assert_almost_equal(
+        thirty["risk"], pyprevent.calculate_30_yr_ascvd_risk(*PATIENT), delta=1e-9
+    )
+
+    five = pyprevent.calculate_risk_at_horizon("ascvd", 5, *PATIENT)["risk"]
+    twenty = pyprevent.calculate_risk_at_horizon("ascvd", 20, *PATIENT)["risk"]
+    assert five < ten["risk"] < twenty < thirty["risk"]
+
+
+def test_risk_at_horizon_errors():
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_risk_at_horizon("ascvd", 40, *PATIENT)
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_risk_at_horizon("stroke", 5, *PATIENT)
+
+
+def test_risk_at_horizon_clamps_hazard():
+    # The 10-year heart failure risk is above the 30-year risk for this patient.
+    patient = ("male", 56, 200, 45, 194, True, True, 18.5, 15, False, False)
+    ten = pyprevent.calculate_10_yr_heart_failure_risk(*patient)
+    assert pyprevent.calculate_30_yr_heart_failure_risk(*patient) < ten
+    twenty = pyprevent.calculate_risk_at_horizon("hf", 20, *patient)
+    assert twenty["hazard_clamped"]
+    slash.assert_almost_equal(twenty["risk"], ten, delta=1e-9)
+
+
+def test_batch_risk_at_horizon():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "female"],
+            "age": [45, 70],
+            "total_cholesterol": [210, 210],
+            "hdl_cholesterol": [55, 55],
+            "systolic_bp": [135, 135],
+            "has_diabetes": [True, True],
+            "current_smoker": [False, False],
+            "bmi": [31, 31],
+            "egfr": [85, 85],
+            "on_htn_meds": [True, True],
+            "on_cholesterol_meds": [False, False],
+        }
+    )
+    result = pyprevent.batch_calculate_risk_at_horizon(df, "cvd", 20)
+    assert result.name == "derived_20_yr_cvd"
+    assert result.attrs["derived"]
+    assert not result.attrs["hazard_clamped"].any()
+    single = pyprevent.calculate_risk_at_horizon("cvd", 20, *PATIENT)
+    slash.assert_almost_equal(result[0], single["risk"], delta=1e-9)
+    assert np.isnan(result[1])
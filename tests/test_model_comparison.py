@@ -0,0 +1,52 @@
+import numpy as np
+import pyprevent
+import slash
+
+
+def _simulated(n, seed):
+    rng = np.random.default_rng(seed)
+    true_risks = rng.uniform(1, 40, n)
+    events = rng.uniform(size=n) < true_risks / 100
+    times = np.where(events, rng.uniform(0, 10, n), 10.0)
+    reference = np.clip(true_risks + rng.uniform(-15, 15, n), 0.5, 60)
+    comparison = np.clip(true_risks + rng.uniform(-5, 5, n), 0.5, 60)
+    return reference, comparison, events, times
+
+
+def test_compare_models():
+    reference, comparison, events, times = _simulated(2000, 1)
+    result = pyprevent.compare_models(
+        reference, comparison, events, times, net_benefit_thresholds=[5, 10], bootstrap=50
+    )
+    metrics = result["metrics"]
+    assert result["n"] == 2000
+    assert metrics.loc["idi", "estimate"] > 0
+    assert (metrics["lower"] <= metrics["estimate"]).all()
+    assert (metrics["estimate"] <= metrics["upper"]).all()
+    curve = result["decision_curve"]
+    assert list(curve["threshold"]) == [5, 10]
+    assert (curve["comparison_lower"] <= curve["comparison"]).all()
+
+    default = pyprevent.compare_models(reference, comparison, events, times, bootstrap=0)
+    assert len(default["decision_curve"]) == 50
+    assert default["metrics"]["lower"].isna().all()
+
+
+def test_compare_identical_models():
+    reference, _, events, times = _simulated(200, 2)
+    result = pyprevent.compare_models(reference, reference, events, times, bootstrap=0)
+    assert (result["metrics"]["estimate"] == 0).all()
+    slash.assert_almost_equal(
+        (result["decision_curve"]["reference"] - result["decision_curve"]["comparison"]).abs().max(),
+        0,
+    )
+
+
+def test_compare_models_errors():
+    reference, comparison, events, times = _simulated(100, 3)
+    with slash.assert_raises(ValueError):
+        pyprevent.compare_models(reference, comparison[:-1], events, times)
+    with slash.assert_raises(ValueError):
+        pyprevent.compare_models(reference, comparison, events, times, category_thresholds=[10, 5])
+    with slash.assert_raises(ValueError):
+        pyprevent.compare_models(reference, comparison, events, times, net_benefit_thresholds=[0])
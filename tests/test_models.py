@@ -0,0 +1,85 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+
+def test_available_models():
+    models = pyprevent.available_models()
+    assert "10_yr_cvd" in models
+    assert "30_yr_hf" in models
+    assert pyprevent.model_metadata("30_yr_ascvd") == {
+        "name": "PREVENT 30-year ASCVD",
+        "version": "2024 base model",
+        "horizon_years": 30,
+        "outcome": "ascvd",
+    }
+    with slash.assert_raises(ValueError):
+        pyprevent.model_metadata("framingham")
+
+
+def test_calculate_risk_matches_outcome_functions():
+    args = ("male", 50, 200, 50, 120, False, False, 25, 90, False, False)
+    assert pyprevent.calculate_risk(
+        "10_yr_cvd", *args
+    ) == pyprevent.calculate_10_yr_cvd_risk(*args)
+    assert pyprevent.calculate_risk(
+        "30_yr_hf", *args
+    ) == pyprevent.calculate_30_yr_heart_failure_risk(*args)
+
+
+def test_batch_calculate_risk():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "male"],
+            "age": [50, 50],
+            "total_cholesterol": [200, 200],
+            "hdl_cholesterol": [45, 45],
+            "systolic_bp": [160, 160],
+            "has_diabetes": [True, True],
+            "current_smoker": [False, False],
+            "bmi": [35, 35],
+            "egfr": [90, 10],
+            "on_htn_meds": [True, True],
+            "on_cholesterol_meds": [False, False],
+        }
+    )
+    result = pyprevent.batch_calculate_risk(df, "10_yr_ascvd")
+    expected = pyprevent.batch_calculate_10_yr_ascvd_risk(df)
+    assert result[0] == expected[0]
+    assert np.isnan(result[1])
+
+
+def test_calculate_risk_with_warnings():
+    args = ("male", 50, 150, 160, 120, False, False, 25, 90, False, False)
+    risk, warnings = pyprevent.calculate_risk("10_yr_cvd", *args, warnings=True)
+    assert risk == pyprevent.calculate_risk("10_yr_cvd", *args)
+    assert [code for code, _ in warnings] == [
+        "hdl_not_below_total_cholesterol",
+        "non_hdl_cholesterol_out_of_range",
+    ]
+
+
+def test_batch_calculate_risk_with_warnings():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "male"],
+            "age": [50, 65],
+            "total_cholesterol": [200, 300],
+            "hdl_cholesterol": [45, 40],
+            "systolic_bp": [160, 160],
+            "has_diabetes": [True, True],
+            "current_smoker": [False, False],
+            "bmi": [35, 35],
+            "egfr": [90, 90],
+            "on_htn_meds": [True, True],
+            "on_cholesterol_meds": [False, True],
+        }
+    )
+    result, warnings = pyprevent.batch_calculate_risk(df, "30_yr_cvd", warnings=True)
+    np.testing.assert_array_equal(result, pyprevent.batch_calculate_risk(df, "30_yr_cvd"))
+    assert pyprevent.plausibility_warnings(warnings[0]) == []
+    assert pyprevent.plausibility_warnings(warnings[1]) == [
+        "high_non_hdl_cholesterol_on_statin",
+        "age_above_30_yr_range",
+    ]
@@ -0,0 +1,62 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+PATIENT = ("male", 60, 210, 45, 140, False, False, 28, 85, False, False)
+
+
+def test_simulate_measurement_error():
+    result = pyprevent.simulate_measurement_error("10_yr_cvd", *PATIENT, seed=3)
+    assert result["risk"] == pyprevent.calculate_10_yr_cvd_risk(*PATIENT)
+    assert result["p2_5"] <= result["p50"] <= result["p97_5"]
+    assert 0 < result["probability_reclassified"] < 1
+    assert result == pyprevent.simulate_measurement_error("10_yr_cvd", *PATIENT, seed=3)
+
+    exact = pyprevent.simulate_measurement_error(
+        "10_yr_cvd",
+        *PATIENT,
+        sds={"total_cholesterol": 0, "hdl_cholesterol": 0, "systolic_bp": 0, "bmi": 0, "egfr": 0},
+        samples=10,
+    )
+    slash.assert_almost_equal(exact["mean"], exact["risk"], places=10)
+    assert exact["probability_reclassified"] == 0
+
+    with slash.assert_raises(ValueError):
+        pyprevent.simulate_measurement_error("10_yr_cvd", *PATIENT, sds={"weight": 1})
+    with slash.assert_raises(ValueError):
+        pyprevent.simulate_measurement_error("10_yr_cvd", *PATIENT, sds={"systolic_bp": -1})
+    with slash.assert_raises(ValueError):
+        pyprevent.simulate_measurement_error("10_yr_cvd", *PATIENT, samples=1)
+
+
+def test_simulate_measurement_error_categories():
+    young = ("male", 50) + PATIENT[2:]
+    result = pyprevent.simulate_measurement_error("30_yr_cvd", *young, samples=100)
+    assert np.isnan(result["probability_at_least_5"])
+    assert np.isnan(result["probability_reclassified"])
+    assert not np.isnan(result["mean"])
+
+
+def test_batch_simulate_measurement_error():
+    df = pd.DataFrame(
+        {
+            "sex": ["male", "female", "female"],
+            "age": [60, 55, 90],
+            "total_cholesterol": [210, 200, 200],
+            "hdl_cholesterol": [45, 50, 50],
+            "systolic_bp": [140, 130, 130],
+            "has_diabetes": [False, True, False],
+            "current_smoker": [False, False, False],
+            "bmi": [28, 27, 27],
+            "egfr": [85, 90, 90],
+            "on_htn_meds": [False, False, False],
+            "on_cholesterol_meds": [False, False, False],
+        }
+    )
+    result = pyprevent.batch_simulate_measurement_error(df, "10_yr_cvd", samples=200, seed=5)
+    single = pyprevent.simulate_measurement_error("10_yr_cvd", *PATIENT, samples=200, seed=5)
+    assert list(result.columns) == list(single)
+    np.testing.assert_allclose(result.iloc[0].values, list(single.values()))
+    assert not result.iloc[1].isna().any()
+    assert result.iloc[2].isna().all()
@@ -0,0 +1,70 @@
+import os
+import tempfile
+
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+REFERENCE = """model,sex,age_min,age_max,percentile,risk
+10_yr_cvd,female,50,60,0,0.5
+10_yr_cvd,female,50,60,50,2.0
+10_yr_cvd,female,50,60,90,8.0
+10_yr_cvd,female,50,60,100,20.0
+"""
+
+
+def _write_reference(directory):
+    path = os.path.join(directory, "reference.csv")
+    with open(path, "w") as f:
+        f.write(REFERENCE)
+    return path
+
+
+PATIENT = ("female", 55, 220, 45, 140, False, True, 30, 80, False, False)
+
+
+def test_compare_to_peers_without_reference():
+    result = pyprevent.compare_to_peers("30_yr_hf", *PATIENT)
+    assert result["risk"] == pyprevent.calculate_30_yr_heart_failure_risk(*PATIENT)
+    assert result["optimal_risk"] < result["risk"]
+    assert result["percentile"] is None
+
+
+def test_compare_to_peers_with_reference():
+    with tempfile.TemporaryDirectory() as directory:
+        path = _write_reference(directory)
+        result = pyprevent.compare_to_peers("10_yr_cvd", *PATIENT, reference_path=path)
+        assert 0 < result["percentile"] < 100
+
+        with slash.assert_raises(ValueError):
+            pyprevent.compare_to_peers(
+                "10_yr_cvd",
+                *PATIENT,
+                reference_path=os.path.join(directory, "missing.csv"),
+            )
+
+
+def test_batch_compare_to_peers():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "female"],
+            "age": [55, 45],
+            "total_cholesterol": [220, 220],
+            "hdl_cholesterol": [45, 45],
+            "systolic_bp": [140, 140],
+            "has_diabetes": [False, False],
+            "current_smoker": [True, True],
+            "bmi": [30, 30],
+            "egfr": [80, 80],
+            "on_htn_meds": [False, False],
+            "on_cholesterol_meds": [False, False],
+        }
+    )
+    with tempfile.TemporaryDirectory() as directory:
+        path = _write_reference(directory)
+        result = pyprevent.batch_compare_to_peers(df, "10_yr_cvd", reference_path=path)
+        single = pyprevent.compare_to_peers("10_yr_cvd", *PATIENT, reference_path=path)
+    slash.assert_almost_equal(result["percentile"][0], single["percentile"], delta=1e-9)
+    assert np.isnan(result["percentile"][1])
+    assert not np.isnan(result["optimal_risk"][1])
@@ -0,0 +1,37 @@
+import pandas as pd
+import pyprevent
+
+
+def test_check_plausibility():
+    assert pyprevent.check_plausibility(50, 200, 50, True) == []
+
+    codes = [code for code, _ in pyprevent.check_plausibility(79, 150, 160, False, True)]
+    assert codes == [
+        "hdl_not_below_total_cholesterol",
+        "non_hdl_cholesterol_out_of_range",
+        "age_above_30_yr_range",
+    ]
+    assert pyprevent.check_plausibility(79, 200, 50, False) == []
+
+
+def test_batch_check_plausibility():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "male"],
+            "age": [40, 40],
+            "total_cholesterol": [200, 300],
+            "hdl_cholesterol": [50, 40],
+            "systolic_bp": [120, 120],
+            "has_diabetes": [True, True],
+            "current_smoker": [True, True],
+            "bmi": [25, 25],
+            "egfr": [70, 70],
+            "on_htn_meds": [True, True],
+            "on_cholesterol_meds": [True, True],
+        }
+    )
+    warnings = pyprevent.batch_check_plausibility(df)
+    assert pyprevent.plausibility_warnings(warnings[0]) == []
+    assert pyprevent.plausibility_warnings(warnings[1]) == [
+        "high_non_hdl_cholesterol_on_statin"
+    ]
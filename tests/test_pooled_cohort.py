@@ -0,0 +1,50 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+
+def test_calculate_10_yr_pce_ascvd_risk_published_example():
+    result = pyprevent.calculate_10_yr_pce_ascvd_risk(
+        "female", "white", 55, 213, 50, 120, False, False, False
+    )
+    slash.assert_almost_equal(result, 2.1, delta=0.1)
+
+    result = pyprevent.calculate_10_yr_pce_ascvd_risk(
+        "MALE", "African American", 55, 213, 50, 120, False, False, False
+    )
+    slash.assert_almost_equal(result, 6.1, delta=0.1)
+
+
+def test_invalid_pce_age():
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_10_yr_pce_ascvd_risk(
+            "female", "white", 35, 213, 50, 120, False, False, False
+        )
+
+
+def test_batch_pce_and_reclassification():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "male", "male"],
+            "race": ["white", "black", "white"],
+            "age": [55, 55, 35],
+            "total_cholesterol": [213, 213, 213],
+            "hdl_cholesterol": [50, 50, 50],
+            "systolic_bp": [120, 120, 120],
+            "has_diabetes": [False, False, False],
+            "current_smoker": [False, False, False],
+            "bmi": [25, 25, 25],
+            "egfr": [90, 90, 90],
+            "on_htn_meds": [False, False, False],
+            "on_cholesterol_meds": [False, False, False],
+        }
+    )
+    pce = pyprevent.batch_calculate_10_yr_pce_ascvd_risk(df)
+    slash.assert_almost_equal(pce[0], 2.1, delta=0.1)
+    slash.assert_almost_equal(pce[1], 6.1, delta=0.1)
+    assert np.isnan(pce[2])
+
+    prevent = pyprevent.batch_calculate_10_yr_ascvd_risk(df)
+    table = pyprevent.reclassification_table(pce, prevent)
+    assert table.values.sum() == 2
@@ -0,0 +1,58 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+
+def test_clamp_flags_adjusted_fields():
+    risk, adjusted = pyprevent.calculate_risk_with_policy(
+        "10_yr_cvd",
+        "female",
+        40,
+        200,
+        50,
+        215,
+        True,
+        True,
+        25,
+        70,
+        True,
+        True,
+        policy={"systolic_bp": "clamp"},
+    )
+    expected = pyprevent.calculate_10_yr_cvd_risk(
+        "female", 40, 200, 50, 200, True, True, 25, 70, True, True
+    )
+    slash.assert_almost_equal(risk, expected, delta=1e-9)
+    assert adjusted == ["systolic_bp"]
+
+
+def test_reject_is_the_default():
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_risk_with_policy(
+            "10_yr_cvd", "female", 40, 200, 50, 215, True, True, 25, 70, True, True
+        )
+
+
+def test_batch_with_policy():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "male"],
+            "age": [40, 40],
+            "total_cholesterol": [200, 200],
+            "hdl_cholesterol": [50, 50],
+            "systolic_bp": [120, 120],
+            "has_diabetes": [True, True],
+            "current_smoker": [True, True],
+            "bmi": [25, 45],
+            "egfr": [70, 70],
+            "on_htn_meds": [True, True],
+            "on_cholesterol_meds": [True, True],
+        }
+    )
+    risks, adjusted = pyprevent.batch_calculate_risk_with_policy(
+        df, "10_yr_hf", policy={"bmi": "extrapolate"}
+    )
+    assert not np.isnan(risks).any()
+    assert pyprevent.adjusted_fields(adjusted[0]) == []
+    assert pyprevent.adjusted_fields(adjusted[1]) == ["bmi"]
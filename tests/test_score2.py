@@ -0,0 +1,53 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+
+def test_score2_regions_are_ordered():
+    risks = [
+        pyprevent.calculate_score2_risk(region, "male", 55, 220, 45, 140, False, True)
+        for region in ["low", "moderate", "high", "very_high"]
+    ]
+    assert risks == sorted(risks)
+
+
+def test_score2_age_ranges():
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_score2_risk("low", "female", 70, 220, 45, 140, False, False)
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_score2_op_risk("low", "female", 69, 220, 45, 140, False, False)
+    with slash.assert_raises(ValueError):
+        pyprevent.calculate_score2_risk("nordic", "female", 50, 220, 45, 140, False, False)
+
+
+def test_batch_score2_matches_model_registry():
+    df = pd.DataFrame(
+        {
+            "sex": ["female", "male", "male"],
+            "age": [55, 65, 75],
+            "total_cholesterol": [213, 240, 200],
+            "hdl_cholesterol": [50, 40, 55],
+            "systolic_bp": [120, 150, 140],
+            "has_diabetes": [False, True, False],
+            "current_smoker": [False, True, False],
+            "bmi": [25, 25, 25],
+            "egfr": [90, 90, 90],
+            "on_htn_meds": [False, False, False],
+            "on_cholesterol_meds": [False, False, False],
+        }
+    )
+    score2 = pyprevent.batch_calculate_score2_risk(df, "high")
+    slash.assert_almost_equal(
+        score2[0],
+        pyprevent.calculate_score2_risk("high", "female", 55, 213, 50, 120, False, False),
+        delta=1e-12,
+    )
+    assert np.isnan(score2[2])
+    np.testing.assert_array_equal(
+        score2, pyprevent.batch_calculate_risk(df, "score2_high")
+    )
+
+    score2_op = pyprevent.batch_calculate_score2_op_risk(df, "high")
+    assert np.isnan(score2_op[0])
+    assert not np.isnan(score2_op[2])
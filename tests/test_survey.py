@@ -0,0 +1,49 @@
+import numpy as np
+import pyprevent
+import slash
+
+
+def test_survey_summary():
+    risks = [2.0, 6.0, 10.0, 30.0]
+    weights = [1.0, 2.0, 1.0, 1.0]
+    strata = ["a", "a", "b", "b"]
+    psu = [1, 2, 1, 2]
+    result = pyprevent.survey_summary(
+        risks, weights, strata, psu, by=["F", "M", "F", "M"], model="10_yr_ascvd"
+    )
+    assert list(result.index) == ["overall", "F", "M"]
+    slash.assert_almost_equal(result.loc["overall", "mean_risk"], 10.8, delta=1e-9)
+    slash.assert_almost_equal(result.loc["overall", "expected_events"], 0.54, delta=1e-9)
+    slash.assert_almost_equal(result.loc["overall", "expected_events_se"], np.sqrt(0.05), delta=1e-9)
+    slash.assert_almost_equal(result.loc["overall", "proportion_borderline"], 0.4, delta=1e-9)
+    slash.assert_almost_equal(result.loc["M", "mean_risk"], 14.0, delta=1e-9)
+    assert result.loc["F", "n"] == 2
+
+
+def test_missing_predictions_and_no_subgroups():
+    result = pyprevent.survey_summary(
+        [2.0, np.nan, 10.0, 30.0, 5.0], np.ones(5), [1, 1, 2, 2, 2], [1, 2, 1, 2, 2]
+    )
+    assert list(result.index) == ["overall"]
+    assert result.loc["overall", "n"] == 4
+    # Without a 10-year PREVENT model there are no risk categories.
+    assert np.isnan(result.loc["overall", "proportion_high"])
+
+
+def test_empty_subgroup_and_two_dimensional_input():
+    result = pyprevent.survey_summary(
+        [2.0, 6.0, np.nan, np.nan],
+        np.ones(4),
+        [1, 1, 1, 1],
+        [1, 2, 1, 2],
+        by=["F", "F", "M", "M"],
+    )
+    assert result.loc["M", "n"] == 0
+    assert np.isnan(result.loc["M", "mean_risk"])
+    with slash.assert_raises(ValueError):
+        pyprevent.survey_summary(np.ones((2, 2)), np.ones(4), [1, 1, 2, 2], [1, 2, 1, 2])
+
+
+def test_single_psu_stratum_is_rejected():
+    with slash.assert_raises(ValueError):
+        pyprevent.survey_summary([2.0, 6.0], [1.0, 1.0], [1, 1], [1, 1])
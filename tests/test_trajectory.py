@@ -0,0 +1,46 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+PATIENT = ("male", 52, 200, 45, 130, False, False, 27, 90, False, False)
+
+
+def test_risk_trajectory():
+    result = pyprevent.risk_trajectory("10_yr_cvd", *PATIENT)
+    assert list(result["age"]) == [52, 57, 62, 67, 72, 77]
+    assert result["risk"][0] == pyprevent.calculate_10_yr_cvd_risk(*PATIENT)
+
+    rising = pyprevent.risk_trajectory("10_yr_cvd", *PATIENT, trends={"systolic_bp": 1})
+    assert rising["risk"][0] == result["risk"][0]
+    assert rising["risk"][5] > result["risk"][5]
+
+    with slash.assert_raises(ValueError):
+        pyprevent.risk_trajectory("30_yr_cvd", *PATIENT)
+    with slash.assert_raises(ValueError):
+        pyprevent.risk_trajectory("10_yr_cvd", *PATIENT, trends={"age": 1})
+
+
+def test_batch_risk_trajectory():
+    df = pd.DataFrame(
+        {
+            "sex": ["male", "female", "female"],
+            "age": [52, 70, 90],
+            "total_cholesterol": [200, 200, 200],
+            "hdl_cholesterol": [45, 45, 45],
+            "systolic_bp": [130, 130, 130],
+            "has_diabetes": [False, False, False],
+            "current_smoker": [False, False, False],
+            "bmi": [27, 27, 27],
+            "egfr": [90, 90, 90],
+            "on_htn_meds": [False, False, False],
+            "on_cholesterol_meds": [False, False, False],
+        }
+    )
+    result = pyprevent.batch_risk_trajectory(df, "10_yr_hf", step_years=10)
+    assert list(result.columns) == [0, 10, 20]
+    single = pyprevent.risk_trajectory("10_yr_hf", *PATIENT, step_years=10)
+    np.testing.assert_allclose(result.iloc[0].values, single["risk"].values)
+    assert not np.isnan(result.iloc[1][0])
+    assert np.isnan(result.iloc[1][10])
+    assert result.iloc[2].isna().all()
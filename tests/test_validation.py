@@ -0,0 +1,72 @@
+import numpy as np
+import pandas as pd
+import pyprevent
+import slash
+
+
+def _simulated(n, seed):
+    rng = np.random.default_rng(seed)
+    risks = rng.uniform(1, 40, n)
+    event_times = np.where(rng.uniform(size=n) < risks / 100, rng.uniform(0, 10, n), 11.0)
+    censoring_times = np.where(rng.uniform(size=n) < 0.2, 5.0, np.inf)
+    events = event_times < censoring_times
+    times = np.minimum(event_times, censoring_times)
+    return risks, events & (times < 10), times
+
+
+def test_validate_predictions():
+    risks, events, times = _simulated(5000, 1)
+    result = pyprevent.validate_predictions(risks, events, times, bootstrap=50, seed=2)
+    metrics = result["metrics"]
+    assert result["n"] == 5000
+    assert list(metrics.index) == [
+        "c_statistic",
+        "brier_score",
+        "calibration_intercept",
+        "calibration_slope",
+        "observed_expected",
+    ]
+    assert 0.6 < metrics.loc["c_statistic", "estimate"] < 0.8
+    slash.assert_almost_equal(metrics.loc["observed_expected", "estimate"], 1, delta=0.1)
+    assert (metrics["lower"] < metrics["estimate"]).all()
+    assert (metrics["estimate"] < metrics["upper"]).all()
+    assert len(result["deciles"]) == 10
+    assert result["deciles"]["n"].sum() == 5000
+
+    again = pyprevent.validate_predictions(risks, events, times, bootstrap=50, seed=2)
+    pd.testing.assert_frame_equal(metrics, again["metrics"])
+
+    without = pyprevent.validate_predictions(risks, events, times, bootstrap=0)
+    assert without["metrics"]["lower"].isna().all()
+
+    with slash.assert_raises(ValueError):
+        pyprevent.validate_predictions(risks[:5], events[:5], times[:5])
+    with slash.assert_raises(ValueError):
+        pyprevent.validate_predictions(risks, events[:-1], times)
+
+
+def test_batch_validate_model():
+    n = 200
+    rng = np.random.default_rng(3)
+    df = pd.DataFrame(
+        {
+            "sex": rng.choice(["female", "male"], n),
+            "age": rng.uniform(40, 75, n),
+            "total_cholesterol": rng.uniform(150, 250, n),
+            "hdl_cholesterol": rng.uniform(35, 70, n),
+            "systolic_bp": rng.uniform(100, 170, n),
+            "has_diabetes": rng.uniform(size=n) < 0.2,
+            "current_smoker": rng.uniform(size=n) < 0.2,
+            "bmi": rng.uniform(20, 35, n),
+            "egfr": rng.uniform(50, 110, n),
+            "on_htn_meds": rng.uniform(size=n) < 0.3,
+            "on_cholesterol_meds": rng.uniform(size=n) < 0.3,
+            "event": rng.uniform(size=n) < 0.2,
+            "time": rng.uniform(1, 12, n),
+        }
+    )
+    result = pyprevent.batch_validate_model(df, "10_yr_cvd", bootstrap=0)
+    direct = pyprevent.validate_predictions(
+        pyprevent.batch_calculate_risk(df, "10_yr_cvd"), df["event"], df["time"], bootstrap=0
+    )
+    pd.testing.assert_frame_equal(result["metrics"], direct["metrics"])